@@ -1,36 +1,251 @@
+use std::fmt::{self, Write};
+use std::io;
 use std::path::Path;
 use std::process::ExitCode;
 use std::time::Instant;
 
 use colored::Colorize;
+use serde_json::json;
+use xschem_parser::symbol::SchematicKind;
+use xschem_parser::token::Schematic;
+
+/// Placeholder path of the standard input in messages.
+const STDIN_PATH: &str = "<stdin>";
+
+/// What to do with the parsed files.
+#[derive(Clone, Copy, Default, PartialEq)]
+enum Mode {
+    /// Only report errors.
+    #[default]
+    Check,
+    /// Print each parsed schematic or error as a line of JSON to stdout.
+    Json,
+    /// Print a table of object counts per file to stdout.
+    Stats,
+    /// Print each file in canonical form to stdout.
+    Format,
+}
+
+/// Command line options.
+#[derive(Default)]
+struct Options {
+    mode: Mode,
+    /// Rewrite files in canonical form instead of printing them.
+    in_place: bool,
+    paths: Vec<String>,
+}
+
+impl Options {
+    fn parse(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        for a in args {
+            let mode = match a.as_str() {
+                "--json" => Mode::Json,
+                "--stats" => Mode::Stats,
+                "--format" | "--canonicalize" => Mode::Format,
+                "--in-place" => {
+                    options.in_place = true;
+                    continue;
+                }
+                flag if flag.starts_with("--") => return Err(format!("unknown option `{flag}`")),
+                _ => {
+                    options.paths.push(a);
+                    continue;
+                }
+            };
+            if options.mode != Mode::Check && options.mode != mode {
+                return Err(
+                    "only one of `--json`, `--stats` and `--format` can be given".to_string(),
+                );
+            }
+            options.mode = mode;
+        }
+        if options.in_place && options.mode != Mode::Format {
+            return Err("`--in-place` requires `--format`".to_string());
+        }
+        if options.in_place && options.paths.is_empty() {
+            return Err("`--in-place` requires file paths".to_string());
+        }
+        Ok(options)
+    }
+}
+
+/// Prints an error as a line of JSON, with the line and column of the error
+/// if it is located in the input.
+fn print_json_error(
+    path: &str,
+    location: Option<(u32, usize)>,
+    code: &str,
+    message: &dyn fmt::Display,
+) {
+    let (line, column) = location.unzip();
+    println!(
+        "{}",
+        json!({
+            "path": path,
+            "error": {
+                "line": line,
+                "column": column,
+                "code": code,
+                "message": message.to_string(),
+            },
+        })
+    );
+}
+
+/// Prints an error of reading or writing a file.
+fn print_io_error(path: &Path, e: &io::Error) {
+    eprintln!(
+        "{error}: {desc}\n\
+         {ptr}{path}",
+        error = "error".red().bold(),
+        desc = e.to_string().bold(),
+        ptr = "  --> ".blue().bold(),
+        path = path.display(),
+    );
+}
+
+/// Column headers of the object counts in the `--stats` table.
+const STATS_COLUMNS: [&str; 8] = [
+    "components",
+    "wires",
+    "lines",
+    "rectangles",
+    "polygons",
+    "arcs",
+    "texts",
+    "embeddings",
+];
+
+/// Returns the object counts of a schematic, in the order of [`STATS_COLUMNS`].
+fn stats<I>(schematic: &Schematic<I>) -> [usize; 8] {
+    [
+        schematic.components.len(),
+        schematic.wires.len(),
+        schematic.lines.len(),
+        schematic.rectangles.len(),
+        schematic.polygons.len(),
+        schematic.arcs.len(),
+        schematic.texts.len(),
+        schematic
+            .components
+            .iter()
+            .filter(|c| c.embedding.is_some())
+            .count(),
+    ]
+}
+
+/// Prints the `--stats` table with a row per file and a total row.
+fn print_stats(rows: &[(&str, SchematicKind, [usize; 8])]) {
+    let mut total = [0; 8];
+    for (_, _, counts) in rows {
+        for (t, c) in total.iter_mut().zip(counts) {
+            *t += c;
+        }
+    }
+
+    let path_width = rows
+        .iter()
+        .map(|(path, _, _)| path.chars().count())
+        .chain(["file".len(), "total".len()])
+        .max()
+        .unwrap_or_default();
+    let kind_width = "schematic".len();
+    let widths: [usize; 8] =
+        std::array::from_fn(|i| STATS_COLUMNS[i].len().max(total[i].to_string().len()));
+
+    let row = |path: &str, kind: &str, counts: &[String]| {
+        let mut line = format!("{path:path_width$}  {kind:kind_width$}");
+        for (count, width) in counts.iter().zip(widths) {
+            // Writing to a `String` does not fail.
+            let _ = write!(line, "  {count:>width$}");
+        }
+        println!("{}", line.trim_end());
+    };
+
+    row("file", "kind", &STATS_COLUMNS.map(str::to_string));
+    for (path, kind, counts) in rows {
+        row(path, &kind.to_string(), &counts.map(|c| c.to_string()));
+    }
+    row("total", "", &total.map(|c| c.to_string()));
+}
 
 fn main() -> ExitCode {
     let start = Instant::now();
 
-    let (count, errors) = std::env::args().skip(1).fold((0, 0), |(count, errors), a| {
-        let path = Path::new(&a);
-        match std::fs::read_to_string(path) {
+    let mut options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!(
+                "{error}: {desc}",
+                error = "error".red().bold(),
+                desc = e.bold()
+            );
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Without paths the standard input is parsed as a single file.
+    let stdin = options.paths.is_empty();
+    if stdin {
+        options.paths.push(STDIN_PATH.to_string());
+    }
+
+    let mut rows = Vec::new();
+    let (count, errors) = options.paths.iter().fold((0, 0), |(count, errors), a| {
+        let path = Path::new(a);
+        let contents = if stdin {
+            io::read_to_string(io::stdin())
+        } else {
+            std::fs::read_to_string(path)
+        };
+        match contents {
             Ok(contents) => match xschem_parser::from_str_file(&contents, path) {
-                Ok(_) => (count + 1, errors),
+                Ok(mut schematic) => {
+                    if options.mode == Mode::Format {
+                        schematic.sort_canonical();
+                        let formatted = schematic.to_xschem_string();
+                        if !options.in_place {
+                            print!("{formatted}");
+                        } else if let Err(e) = std::fs::write(path, formatted) {
+                            print_io_error(path, &e);
+                            return (count + 1, errors + 1);
+                        }
+                    }
+                    if options.mode == Mode::Json {
+                        let schematic = schematic.map(|i| *i.fragment());
+                        println!("{}", json!({ "path": a, "schematic": schematic }));
+                    }
+                    if options.mode == Mode::Stats {
+                        rows.push((a.as_str(), schematic.kind(), stats(&schematic)));
+                    }
+                    (count + 1, errors)
+                }
                 Err(e) => {
-                    eprintln!("{e}");
+                    if options.mode == Mode::Json {
+                        let location = Some(e.line_column());
+                        print_json_error(a, location, e.err.kind.code(), &e.err.kind);
+                    } else {
+                        eprintln!("{e}");
+                    }
                     (count + 1, errors + 1)
                 }
             },
             Err(e) => {
-                eprintln!(
-                    "{error}: {desc}\n\
-                     {ptr}{path}",
-                    error = "error".red().bold(),
-                    desc = e.to_string().bold(),
-                    ptr = "  --> ".blue().bold(),
-                    path = path.display(),
-                );
+                if options.mode == Mode::Json {
+                    print_json_error(a, None, "io", &e);
+                } else {
+                    print_io_error(path, &e);
+                }
                 (count + 1, errors + 1)
             }
         }
     });
 
+    if options.mode == Mode::Stats {
+        print_stats(&rows);
+    }
+
     let end = Instant::now();
     let elapsed = end.duration_since(start);
 
@@ -0,0 +1,155 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use serde_json::Value;
+
+const ASSETS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets");
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_xschem-parser-cli"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn run_stdin(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_xschem-parser-cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn json_lines(output: &Output) -> Vec<Value> {
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect()
+}
+
+#[test]
+fn json() {
+    let path = format!("{ASSETS}/7805.sym");
+    let output = run(&["--json", &path]);
+    assert!(output.status.success());
+
+    let lines = json_lines(&output);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["path"], path);
+    let schematic = &lines[0]["schematic"];
+    assert_eq!(schematic["version"]["attrs"]["file_version"], "1.2");
+    assert_eq!(schematic["symbol_property"]["attrs"]["type"], "regulator");
+    assert_eq!(schematic["texts"][0]["text"], "@name");
+    assert_eq!(schematic["texts"][0]["position"]["x"], -17.5);
+    assert_eq!(schematic["texts"][0]["rotation"], 0);
+}
+
+#[test]
+fn json_errors() {
+    let malformed = format!("{}/malformed.sch", env!("CARGO_TARGET_TMPDIR"));
+    std::fs::write(&malformed, "v {}\nL 4 0 x 10 0 {}\n").unwrap();
+    let missing = format!("{ASSETS}/missing.sch");
+    let output = run(&["--json", &malformed, &missing]);
+    assert!(!output.status.success());
+
+    let lines = json_lines(&output);
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0]["path"], malformed);
+    assert_eq!(lines[0]["error"]["line"], 2);
+    assert_eq!(lines[0]["error"]["column"], 7);
+    assert_eq!(lines[0]["error"]["code"], "expected-char");
+    assert_eq!(lines[1]["path"], missing);
+    assert_eq!(lines[1]["error"]["code"], "io");
+    assert!(lines[1]["error"]["line"].is_null());
+}
+
+#[test]
+fn stats() {
+    let path = format!("{ASSETS}/pcb_test1.sch");
+    let output = run(&["--stats", &path, &path]);
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|l| l.split_whitespace().collect())
+        .collect();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(
+        rows[0],
+        [
+            "file",
+            "kind",
+            "components",
+            "wires",
+            "lines",
+            "rectangles",
+            "polygons",
+            "arcs",
+            "texts",
+            "embeddings"
+        ]
+    );
+    assert_eq!(
+        rows[1],
+        [&path, "schematic", "24", "20", "0", "1", "0", "0", "2", "0"]
+    );
+    assert_eq!(rows[3], ["total", "48", "40", "0", "2", "0", "0", "4", "0"]);
+
+    let lengths: Vec<usize> = stdout.lines().map(|l| l.trim_end().len()).collect();
+    assert!(lengths.iter().all(|&l| l == lengths[0]));
+}
+
+#[test]
+fn stdin() {
+    let input = include_str!("../../../assets/pmos.sym");
+    let output = run_stdin(&["--json"], input);
+    assert!(output.status.success());
+
+    let lines = json_lines(&output);
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["path"], "<stdin>");
+    assert_eq!(lines[0]["schematic"]["arcs"][0]["layer"], 4);
+
+    let output = run_stdin(&[], "v {}\nL 4 0 x 10 0 {}\n");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--> <stdin>:2:7"));
+}
+
+#[test]
+fn format() {
+    let input = include_str!("../../../assets/pcb_test1.sch");
+    let path = format!("{}/format.sch", env!("CARGO_TARGET_TMPDIR"));
+    std::fs::write(&path, input).unwrap();
+
+    let output = run(&["--format", &path]);
+    assert!(output.status.success());
+    let formatted = String::from_utf8(output.stdout).unwrap();
+
+    let mut expected = xschem_parser::from_str(input).unwrap();
+    expected.sort_canonical();
+    let schematic = xschem_parser::from_str(&formatted).unwrap();
+    assert_eq!(
+        schematic.map(|i| *i.fragment()),
+        expected.map(|i| *i.fragment())
+    );
+
+    let output = run(&["--canonicalize", "--in-place", &path]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), formatted);
+
+    let output = run_stdin(&["--format"], "v {}\nL 4 0 x 10 0 {}\n");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
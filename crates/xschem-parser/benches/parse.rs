@@ -0,0 +1,88 @@
+//! Compares ways of parsing large schematics to `from_str`:
+//!
+//! - `from_str_plain`, which parses without locations.
+//! - `parse_parallel`, which parses top-level objects in parallel. Requires the
+//!   `rayon` feature.
+//!
+//! Run with `cargo bench --all-features`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ASSETS: [(&str, &str); 2] = [
+    (
+        "pcb_test1.sch",
+        include_str!("../../../assets/pcb_test1.sch"),
+    ),
+    (
+        "embedding.sch",
+        include_str!("../../../assets/embedding.sch"),
+    ),
+];
+
+/// Number of times the objects of an asset are repeated.
+const REPEAT: usize = 500;
+
+/// Number of timed parses of each input.
+const ITERATIONS: u32 = 20;
+
+/// Parser returning the number of parsed objects.
+type Parse = fn(&str) -> usize;
+
+/// Returns a schematic with the objects of `input` repeated.
+fn repeat_objects(input: &str) -> String {
+    let (header, body) = input.split_once('\n').unwrap_or((input, ""));
+    let mut output = String::with_capacity(header.len() + 1 + body.len() * REPEAT);
+    output.push_str(header);
+    output.push('\n');
+    for _ in 0..REPEAT {
+        output.push_str(body);
+        output.push('\n');
+    }
+    output
+}
+
+/// Returns the mean duration of parsing `input`.
+fn time(input: &str, parse: Parse) -> Duration {
+    black_box(parse(input));
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(parse(black_box(input)));
+    }
+    start.elapsed() / ITERATIONS
+}
+
+/// Returns the parsers to compare to `from_str` by name.
+fn parsers() -> Vec<(&'static str, Parse)> {
+    vec![
+        ("from_str_plain", |s| {
+            xschem_parser::from_str_plain(s).unwrap().objects().count()
+        }),
+        #[cfg(feature = "rayon")]
+        ("parse_parallel", |s| {
+            xschem_parser::parse_parallel(s).unwrap().objects().count()
+        }),
+    ]
+}
+
+fn main() {
+    #[cfg(feature = "rayon")]
+    println!("{} threads", rayon::current_num_threads());
+
+    let from_str: Parse = |s| xschem_parser::from_str(s).unwrap().objects().count();
+    for (name, asset) in ASSETS {
+        let input = repeat_objects(asset);
+        let baseline = time(&input, from_str);
+        println!(
+            "{name} x{REPEAT} ({} KiB): from_str {baseline:?}",
+            input.len() / 1024,
+        );
+        for (parser, parse) in parsers() {
+            assert_eq!(parse(&input), from_str(&input), "{parser}");
+            let duration = time(&input, parse);
+            println!(
+                "  {parser} {duration:?} ({:.2}x)",
+                baseline.as_secs_f64() / duration.as_secs_f64(),
+            );
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Default layer colors of Xschem.
+//!
+//! The palettes are the `dark_colors` and `light_colors` defaults of
+//! `xschem.tcl` in the Xschem sources, for the default 22 layers. Layers are
+//! used by Xschem as follows: 0 is the background, 1 wires, 2 the grid and
+//! selection, 3 texts, 4 symbol graphics and 5 pins. Other layers are
+//! generic drawing layers.
+use derive_more::Display;
+
+/// RGB color.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash)]
+#[display("#{r:02x}{g:02x}{b:02x}")]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    /// Creates a color from its red, green and blue component.
+    #[must_use]
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Creates a color from a `0xRRGGBB` value.
+    #[must_use]
+    pub const fn from_hex(hex: u32) -> Self {
+        let [_, r, g, b] = hex.to_be_bytes();
+        Self { r, g, b }
+    }
+}
+
+/// Layer colors of the dark color scheme, which is the default of Xschem.
+pub static DARK_COLORS: [Rgb; 22] = [
+    Rgb::from_hex(0x00_0000),
+    Rgb::from_hex(0x00_ccee),
+    Rgb::from_hex(0x3f_3f3f),
+    Rgb::from_hex(0xcc_cccc),
+    Rgb::from_hex(0x88_dd00),
+    Rgb::from_hex(0xbb_2200),
+    Rgb::from_hex(0x00_ccee),
+    Rgb::from_hex(0xff_0000),
+    Rgb::from_hex(0xff_ff00),
+    Rgb::from_hex(0xff_ffff),
+    Rgb::from_hex(0xff_00ff),
+    Rgb::from_hex(0x00_ff00),
+    Rgb::from_hex(0x00_44dd),
+    Rgb::from_hex(0xaa_aa00),
+    Rgb::from_hex(0xaa_ccaa),
+    Rgb::from_hex(0xff_7777),
+    Rgb::from_hex(0xbf_ff81),
+    Rgb::from_hex(0x00_ffcc),
+    Rgb::from_hex(0xce_0097),
+    Rgb::from_hex(0xd2_d46b),
+    Rgb::from_hex(0xef_6158),
+    Rgb::from_hex(0xfd_b200),
+];
+
+/// Layer colors of the light color scheme.
+pub static LIGHT_COLORS: [Rgb; 22] = [
+    Rgb::from_hex(0xff_ffff),
+    Rgb::from_hex(0x00_44ee),
+    Rgb::from_hex(0xaa_aaaa),
+    Rgb::from_hex(0x22_2222),
+    Rgb::from_hex(0x22_9900),
+    Rgb::from_hex(0xbb_2200),
+    Rgb::from_hex(0x00_ccee),
+    Rgb::from_hex(0xff_0000),
+    Rgb::from_hex(0x88_8800),
+    Rgb::from_hex(0x00_aaaa),
+    Rgb::from_hex(0x88_0088),
+    Rgb::from_hex(0x00_ff00),
+    Rgb::from_hex(0x00_00cc),
+    Rgb::from_hex(0x66_6600),
+    Rgb::from_hex(0x55_7755),
+    Rgb::from_hex(0xaa_2222),
+    Rgb::from_hex(0x7c_cc40),
+    Rgb::from_hex(0x00_ffcc),
+    Rgb::from_hex(0xce_0097),
+    Rgb::from_hex(0xd2_d46b),
+    Rgb::from_hex(0xef_6158),
+    Rgb::from_hex(0xfd_b200),
+];
+
+/// Returns the default color of a layer in Xschem, from [`DARK_COLORS`], or
+/// [`None`] if the layer is not one of the default layers.
+///
+/// ```
+/// use xschem_parser::color::{Rgb, layer_color};
+///
+/// assert_eq!(layer_color(4), Some(Rgb::new(0x88, 0xdd, 0x00)));
+/// assert_eq!(layer_color(4).unwrap().to_string(), "#88dd00");
+/// ```
+#[must_use]
+pub fn layer_color(layer: u64) -> Option<Rgb> {
+    usize::try_from(layer)
+        .ok()
+        .and_then(|layer| DARK_COLORS.get(layer))
+        .copied()
+}
@@ -0,0 +1,247 @@
+//! Differences between schematics.
+//!
+//! See [`Schematic::diff`].
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::Hash;
+
+use crate::HashMap;
+use crate::token::{FiniteDouble, ObjectKind, ObjectRef, Schematic};
+
+/// Change of an object between two schematics.
+#[derive(Debug)]
+pub enum Change<'a, I> {
+    /// Object that is only in the new schematic.
+    Added(ObjectRef<'a, I>),
+    /// Object that is only in the old schematic.
+    Removed(ObjectRef<'a, I>),
+    /// Object that is in both schematics, but differs.
+    Modified {
+        old: ObjectRef<'a, I>,
+        new: ObjectRef<'a, I>,
+    },
+}
+
+impl<I: fmt::Display> fmt::Display for Change<'_, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::Added(o) => write!(f, "+ {o}"),
+            Change::Removed(o) => write!(f, "- {o}"),
+            Change::Modified { old, new } => write!(f, "- {old}\n+ {new}"),
+        }
+    }
+}
+
+/// Changes from one schematic to another, created with [`Schematic::diff`].
+///
+/// Displays as a line per added (`+`) and removed (`-`) object, where a
+/// modified object is shown as removed and added, followed by a summary.
+#[derive(Debug)]
+pub struct SchematicDiff<'a, I> {
+    pub changes: Vec<Change<'a, I>>,
+}
+
+impl<'a, I> SchematicDiff<'a, I> {
+    /// Returns whether the schematics have the same objects.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns an iterator over the changes.
+    pub fn iter(&self) -> core::slice::Iter<'_, Change<'a, I>> {
+        self.changes.iter()
+    }
+
+    /// Returns the number of added, removed and modified objects.
+    #[must_use]
+    pub fn counts(&self) -> (usize, usize, usize) {
+        self.changes
+            .iter()
+            .fold((0, 0, 0), |(added, removed, modified), c| match c {
+                Change::Added(_) => (added + 1, removed, modified),
+                Change::Removed(_) => (added, removed + 1, modified),
+                Change::Modified { .. } => (added, removed, modified + 1),
+            })
+    }
+}
+
+impl<'a, I> IntoIterator for SchematicDiff<'a, I> {
+    type Item = Change<'a, I>;
+    type IntoIter = vec::IntoIter<Change<'a, I>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.into_iter()
+    }
+}
+
+impl<'a, 'b, I> IntoIterator for &'b SchematicDiff<'a, I> {
+    type Item = &'b Change<'a, I>;
+    type IntoIter = core::slice::Iter<'b, Change<'a, I>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.changes.iter()
+    }
+}
+
+impl<I: fmt::Display> fmt::Display for SchematicDiff<'_, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        let (added, removed, modified) = self.counts();
+        write!(f, "{added} added, {removed} removed, {modified} modified")
+    }
+}
+
+/// Identity of an object used to match objects of two schematics.
+#[derive(PartialEq, Eq, Hash)]
+enum Key<'a> {
+    /// Component with a `name` attribute.
+    Named(&'a str),
+    /// Kind, layer and coordinates of any other object.
+    Placed(ObjectKind, u64, Vec<FiniteDouble>),
+    /// Tag and tokens of an unknown object.
+    Unknown(&'a str, Vec<&'a str>),
+}
+
+impl<'a> Key<'a> {
+    fn of<I: AsRef<str>>(object: ObjectRef<'a, I>) -> Self {
+        let placed =
+            |kind, layer, values: &[FiniteDouble]| Key::Placed(kind, layer, values.to_vec());
+        match object {
+            ObjectRef::Arc(o) => placed(
+                ObjectKind::Arc,
+                o.layer,
+                &[
+                    o.center.x,
+                    o.center.y,
+                    o.radius,
+                    o.start_angle,
+                    o.sweep_angle,
+                ],
+            ),
+            ObjectRef::Component(o) => match o.property.get_value("name") {
+                Some(name) => Key::Named(name),
+                None => placed(ObjectKind::Component, 0, &[o.position.x, o.position.y]),
+            },
+            ObjectRef::Line(o) => placed(
+                ObjectKind::Line,
+                o.layer,
+                &[o.start.x, o.start.y, o.end.x, o.end.y],
+            ),
+            ObjectRef::Polygon(o) => Key::Placed(
+                ObjectKind::Polygon,
+                o.layer,
+                o.points.iter().flat_map(|p| [p.x, p.y]).collect(),
+            ),
+            ObjectRef::Rectangle(o) => placed(
+                ObjectKind::Rectangle,
+                o.layer,
+                &[o.start.x, o.start.y, o.end.x, o.end.y],
+            ),
+            ObjectRef::Text(o) => placed(ObjectKind::Text, 0, &[o.position.x, o.position.y]),
+            ObjectRef::Wire(o) => placed(
+                ObjectKind::Wire,
+                0,
+                &[o.start.x, o.start.y, o.end.x, o.end.y],
+            ),
+            ObjectRef::Unknown(o) => {
+                Key::Unknown(o.tag.as_ref(), o.tokens.iter().map(AsRef::as_ref).collect())
+            }
+        }
+    }
+}
+
+/// Returns whether two objects are equal, comparing the strings of input
+/// references rather than the references themselves.
+///
+/// Spans of objects at different places of the input are not equal, even if
+/// the objects are the same, see [`Schematic::content_eq`].
+fn equal<I: AsRef<str>>(a: ObjectRef<'_, I>, b: ObjectRef<'_, I>) -> bool {
+    let mut f = AsRef::<str>::as_ref;
+    match (a, b) {
+        (ObjectRef::Arc(a), ObjectRef::Arc(b)) => a.map_with(&mut f) == b.map_with(&mut f),
+        (ObjectRef::Component(a), ObjectRef::Component(b)) => {
+            a.map_with(&mut f) == b.map_with(&mut f)
+        }
+        (ObjectRef::Line(a), ObjectRef::Line(b)) => a.map_with(&mut f) == b.map_with(&mut f),
+        (ObjectRef::Polygon(a), ObjectRef::Polygon(b)) => a.map_with(&mut f) == b.map_with(&mut f),
+        (ObjectRef::Rectangle(a), ObjectRef::Rectangle(b)) => {
+            a.map_with(&mut f) == b.map_with(&mut f)
+        }
+        (ObjectRef::Text(a), ObjectRef::Text(b)) => a.map_with(&mut f) == b.map_with(&mut f),
+        (ObjectRef::Wire(a), ObjectRef::Wire(b)) => a.map_with(&mut f) == b.map_with(&mut f),
+        (ObjectRef::Unknown(a), ObjectRef::Unknown(b)) => a.map_with(&mut f) == b.map_with(&mut f),
+        _ => false,
+    }
+}
+
+impl<I: AsRef<str>> Schematic<I> {
+    /// Returns the changes of the objects from this schematic to another.
+    ///
+    /// Components are matched by their `name` attribute, so a moved or
+    /// edited component is modified. Other objects, and components without
+    /// a name, are matched by their kind, layer and coordinates, so only a
+    /// changed property or text is a modification, and moving the object
+    /// removes and adds it. Unknown objects are matched by their tag and
+    /// tokens. Embedded symbols are compared as part of their
+    /// component. The version and global properties are not compared.
+    /// Input references are compared by their strings, so the same object at
+    /// another place of the input is unchanged.
+    ///
+    /// Removed objects come first in the order of this schematic, followed
+    /// by modified and added objects in the order of `other`.
+    ///
+    /// ```
+    /// let old = xschem_parser::from_str("v {}\nN 0 0 10 0 {lab=A}").unwrap();
+    /// let new = xschem_parser::from_str("v {}\nN 0 0 10 0 {lab=B}").unwrap();
+    ///
+    /// let diff = old.diff(&new);
+    /// assert_eq!(diff.counts(), (0, 0, 1));
+    /// assert_eq!(
+    ///     diff.to_string(),
+    ///     "- N 0 0 10 0 {lab=A}\n+ N 0 0 10 0 {lab=B}\n0 added, 0 removed, 1 modified",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn diff<'a>(&'a self, other: &'a Schematic<I>) -> SchematicDiff<'a, I> {
+        let objects: Vec<_> = self.objects().collect();
+        let mut unmatched: HashMap<Key<'a>, VecDeque<usize>> = HashMap::new();
+        for (i, &object) in objects.iter().enumerate() {
+            unmatched.entry(Key::of(object)).or_default().push_back(i);
+        }
+
+        let mut matched = vec![false; objects.len()];
+        let mut changes = Vec::new();
+        for new in other.objects() {
+            match unmatched
+                .get_mut(&Key::of(new))
+                .and_then(VecDeque::pop_front)
+            {
+                Some(i) => {
+                    matched[i] = true;
+                    if !equal(objects[i], new) {
+                        changes.push(Change::Modified {
+                            old: objects[i],
+                            new,
+                        });
+                    }
+                }
+                None => changes.push(Change::Added(new)),
+            }
+        }
+
+        let removed = objects
+            .into_iter()
+            .zip(matched)
+            .filter(|&(_, matched)| !matched)
+            .map(|(o, _)| Change::Removed(o));
+
+        SchematicDiff {
+            changes: removed.chain(changes).collect(),
+        }
+    }
+}
@@ -1,11 +1,27 @@
 //! Parser errors.
-use std::fmt::{self, Display};
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Display};
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::path::PathBuf;
 
-use colored::Colorize;
+#[cfg(feature = "std")]
+use colored::{ColoredString, Colorize};
 use derive_more::From;
+use nom::AsBytes;
 use nom::error::{ContextError, ErrorKind as NomErrorKind, FromExternalError, ParseError};
+use nom_locate::LocatedSpan;
 
-use crate::{FileSpan, Span};
+use crate::symbol::SchematicKind;
+#[cfg(feature = "std")]
+use crate::{ByteFileSpan, FileSpan};
+use crate::{ByteSpan, Span};
 
 #[derive(Clone, Debug, Eq, From, PartialEq)]
 pub enum ErrorKind {
@@ -13,6 +29,33 @@ pub enum ErrorKind {
     Char(char),
     /// Error kind given by various nom parsers
     Nom(NomErrorKind),
+    /// Declared file version is newer than the maximum accepted version
+    #[from(skip)]
+    FileVersion {
+        version: (u32, u32),
+        max: (u32, u32),
+    },
+    /// Embedded symbols are nested deeper than the maximum depth
+    #[from(skip)]
+    EmbeddingDepth { max: usize },
+    /// Layer index is not a non-negative integer
+    #[from(skip)]
+    Layer,
+    /// Declared number of polygon points differs from the number of points
+    #[from(skip)]
+    PolygonPoints { declared: usize, found: usize },
+    /// Schematic does not begin with a version object
+    #[from(skip)]
+    MissingVersion,
+    /// Version object without a property, such as a line with only `v`
+    #[from(skip)]
+    MissingVersionProperty,
+    /// Parsed schematic is of another kind than expected
+    #[from(skip)]
+    UnexpectedKind {
+        expected: SchematicKind,
+        found: SchematicKind,
+    },
 }
 
 /// Input with an error.
@@ -35,9 +78,286 @@ pub struct Error<I> {
     pub context: Vec<InputContext<'static, I>>,
 }
 
-impl std::error::Error for Error<&str> {}
-impl std::error::Error for Error<Span<'_>> {}
-impl std::error::Error for Error<FileSpan<'_, '_>> {}
+/// Owned location in the input.
+///
+/// Used as input of an [`Error`] that outlives the parsed input, see
+/// [`Error::map_input`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Location<X = ()> {
+    /// Byte offset from the start of the input.
+    pub offset: usize,
+    /// Line number, starting at 1.
+    pub line: u32,
+    /// Column number in characters, starting at 1.
+    pub column: usize,
+    /// Full line of input containing the location.
+    pub line_text: String,
+    /// Extra information of the input, such as the file path.
+    pub extra: X,
+}
+
+/// Error when reading and parsing a schematic from a stream or file.
+#[cfg(feature = "std")]
+#[derive(Debug, From)]
+pub enum ReadError<X = ()> {
+    /// Reading the input failed.
+    Io(io::Error),
+    /// Parsing the input failed.
+    Parse(Error<Location<X>>),
+}
+
+/// Error when reading and parsing a schematic from a file, with the path of
+/// the file.
+///
+/// Unlike [`ReadError`], the path is also known if reading the file failed.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct FileError {
+    pub path: PathBuf,
+    pub error: Box<ReadError<PathBuf>>,
+}
+
+impl core::error::Error for Error<&str> {}
+impl core::error::Error for Error<Span<'_>> {}
+#[cfg(feature = "std")]
+impl core::error::Error for Error<FileSpan<'_, '_>> {}
+impl core::error::Error for Error<Location> {}
+#[cfg(feature = "std")]
+impl core::error::Error for Error<Location<PathBuf>> {}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::Parse(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReadError<PathBuf> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(e) => Some(e),
+            ReadError::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl ErrorKind {
+    /// Returns a stable, machine-readable code of the error kind.
+    ///
+    /// ```
+    /// use nom::error::ErrorKind as NomErrorKind;
+    /// use xschem_parser::error::ErrorKind;
+    ///
+    /// assert_eq!(ErrorKind::Char('{').code(), "expected-char");
+    /// assert_eq!(ErrorKind::Nom(NomErrorKind::Float).code(), "bad-float");
+    /// ```
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        match self {
+            ErrorKind::Char(_) => "expected-char",
+            ErrorKind::Nom(NomErrorKind::Float) => "bad-float",
+            ErrorKind::Nom(NomErrorKind::Digit) => "bad-integer",
+            ErrorKind::Nom(NomErrorKind::Escaped) => "bad-escape",
+            ErrorKind::Nom(NomErrorKind::Eof) => "trailing-input",
+            ErrorKind::Nom(_) => "syntax",
+            ErrorKind::FileVersion { .. } => "unsupported-file-version",
+            ErrorKind::EmbeddingDepth { .. } => "embedding-too-deep",
+            ErrorKind::Layer => "bad-layer",
+            ErrorKind::PolygonPoints { .. } => "polygon-point-count",
+            ErrorKind::MissingVersion => "missing-version",
+            ErrorKind::MissingVersionProperty => "missing-version-property",
+            ErrorKind::UnexpectedKind { .. } => "unexpected-kind",
+        }
+    }
+}
+
+impl<T: AsBytes, X> Error<LocatedSpan<T, X>> {
+    /// Returns the byte range of the offending token in the input.
+    ///
+    /// The range starts at the error location and spans up to the next ASCII
+    /// whitespace or closing brace, so it is empty if the error is located at
+    /// whitespace, a closing brace or the end of the input.
+    ///
+    /// ```
+    /// let error = xschem_parser::from_str("v {}\nL 4 0 x 10 0 {}").unwrap_err();
+    ///
+    /// assert_eq!(error.err.kind.code(), "expected-char");
+    /// assert_eq!(error.byte_range(), 11..12);
+    /// ```
+    pub fn byte_range(&self) -> Range<usize> {
+        let start = self.offset();
+        let rest = self.err.input.fragment().as_bytes();
+        let len = rest
+            .iter()
+            .position(|&b| b.is_ascii_whitespace() || b == b'}')
+            .unwrap_or(rest.len());
+        start..start + len
+    }
+
+    /// Returns the byte offset of the error in the input.
+    ///
+    /// ```
+    /// let error = xschem_parser::from_str("v []").unwrap_err();
+    ///
+    /// assert_eq!(error.offset(), 2);
+    /// assert_eq!(error.line_column(), (1, 3));
+    /// assert_eq!(error.context_spans().collect::<Vec<_>>(), [("version", 0)]);
+    /// ```
+    pub fn offset(&self) -> usize {
+        self.err.input.location_offset()
+    }
+
+    /// Returns the line and column of the error, both starting at 1.
+    pub fn line_column(&self) -> (u32, usize) {
+        (
+            self.err.input.location_line(),
+            self.err.input.get_utf8_column(),
+        )
+    }
+
+    /// Returns the name and byte offset of each context of the error, from
+    /// the innermost to the outermost.
+    pub fn context_spans(&self) -> impl Iterator<Item = (&'static str, usize)> + '_ {
+        self.context
+            .iter()
+            .map(|c| (c.name, c.input.location_offset()))
+    }
+}
+
+impl<X> Error<Location<X>> {
+    /// Returns the byte offset of the error in the input.
+    pub fn offset(&self) -> usize {
+        self.err.input.offset
+    }
+
+    /// Returns the line and column of the error, both starting at 1.
+    pub fn line_column(&self) -> (u32, usize) {
+        (self.err.input.line, self.err.input.column)
+    }
+
+    /// Returns the name and byte offset of each context of the error, from
+    /// the innermost to the outermost.
+    pub fn context_spans(&self) -> impl Iterator<Item = (&'static str, usize)> + '_ {
+        self.context.iter().map(|c| (c.name, c.input.offset))
+    }
+}
+
+impl<I> Error<I>
+where
+    Self: Display,
+{
+    /// Returns the human readable error without colors, regardless of the
+    /// global [`colored`] state.
+    ///
+    /// This is the same as formatting with `{:#}`.
+    pub fn to_plain_string(&self) -> String {
+        format!("{self:#}")
+    }
+}
+
+impl<I> Error<I> {
+    /// Returns the error to display with tabs in source lines expanded to
+    /// `tab_width` columns, instead of [`DEFAULT_TAB_WIDTH`].
+    ///
+    /// Formatting options are the same as for the error, so `{:#}` writes
+    /// it without colors.
+    ///
+    /// ```
+    /// let error = xschem_parser::from_str("v {}\n\tN 0 0 x 0 {}").unwrap_err();
+    /// let message = format!("{:#}", error.display_with_tab_width(8));
+    ///
+    /// assert!(message.contains("\n 2 |         N 0 0 x 0 {}\n"));
+    /// ```
+    #[must_use]
+    pub fn display_with_tab_width(&self, tab_width: usize) -> WithTabWidth<'_, I> {
+        WithTabWidth {
+            error: self,
+            tab_width,
+        }
+    }
+
+    /// Converts the input of the error and all of its context.
+    ///
+    /// ```
+    /// use xschem_parser::error::{Error, Location};
+    ///
+    /// let error: Error<Location> = {
+    ///     let input = String::from("v []");
+    ///     let error = xschem_parser::from_str(&input).unwrap_err();
+    ///     error.map_input(Location::from)
+    /// };
+    ///
+    /// assert_eq!((error.err.input.line, error.err.input.column), (1, 3));
+    /// ```
+    pub fn map_input<J>(self, mut f: impl FnMut(I) -> J) -> Error<J> {
+        Error {
+            err: ErrorInput {
+                input: f(self.err.input),
+                kind: self.err.kind,
+            },
+            context: self
+                .context
+                .into_iter()
+                .map(|c| InputContext {
+                    input: f(c.input),
+                    name: c.name,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl<X> Location<X> {
+    /// Converts the extra information of the location.
+    pub fn map_extra<Y>(self, f: impl FnOnce(X) -> Y) -> Location<Y> {
+        Location {
+            offset: self.offset,
+            line: self.line,
+            column: self.column,
+            line_text: self.line_text,
+            extra: f(self.extra),
+        }
+    }
+
+    /// Returns the line number, see [`LocatedSpan::location_line`].
+    pub fn location_line(&self) -> u32 {
+        self.line
+    }
+
+    /// Returns the column number, see [`LocatedSpan::get_utf8_column`].
+    pub fn get_utf8_column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the line containing the location, see
+    /// [`LocatedSpan::get_line_beginning`].
+    pub fn get_line_beginning(&self) -> &[u8] {
+        self.line_text.as_bytes()
+    }
+}
+
+impl<T: AsBytes, X> From<LocatedSpan<T, X>> for Location<X> {
+    fn from(span: LocatedSpan<T, X>) -> Self {
+        Self {
+            offset: span.location_offset(),
+            line: span.location_line(),
+            column: span.get_utf8_column(),
+            line_text: String::from_utf8_lossy(span.get_line_beginning()).into_owned(),
+            extra: span.extra,
+        }
+    }
+}
 
 impl<I> ParseError<I> for Error<I> {
     fn from_error_kind(input: I, kind: NomErrorKind) -> Self {
@@ -72,110 +392,197 @@ impl<I> ContextError<I> for Error<I> {
     }
 }
 
-impl<I, E> FromExternalError<I, E> for Error<I> {
-    fn from_external_error(input: I, kind: NomErrorKind, _e: E) -> Self {
+impl<I, E: Into<ErrorKind>> FromExternalError<I, E> for Error<I> {
+    fn from_external_error(input: I, _kind: NomErrorKind, e: E) -> Self {
         Self {
             err: ErrorInput {
                 input,
-                kind: kind.into(),
+                kind: e.into(),
             },
             context: Vec::default(),
         }
     }
 }
 
+/// Applies a style to text, unless writing plain text.
+#[cfg(feature = "std")]
+fn style(s: &str, plain: bool, style: impl FnOnce(&str) -> ColoredString) -> ColoredString {
+    if plain { s.normal() } else { style(s) }
+}
+
+/// Leaves text unstyled, since colors require `std`.
+#[cfg(not(feature = "std"))]
+fn style<'a>(s: &'a str, _plain: bool, _style: impl FnOnce(&'a str) -> &'a str) -> &'a str {
+    s
+}
+
+/// Stand-in for the styles of `colored` without `std`.
+#[cfg(not(feature = "std"))]
+trait Colorize {
+    fn blue(self) -> Self;
+    fn bold(self) -> Self;
+    fn red(self) -> Self;
+}
+
+#[cfg(not(feature = "std"))]
+impl Colorize for &str {
+    fn blue(self) -> Self {
+        self
+    }
+
+    fn bold(self) -> Self {
+        self
+    }
+
+    fn red(self) -> Self {
+        self
+    }
+}
+
+/// Number of columns a tab advances to in the source line of an error,
+/// unless overridden with [`Error::display_with_tab_width`].
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+/// Returns the source line of an error with tabs expanded to spaces.
+fn expand_tabs(line: &[u8], tab_width: usize) -> String {
+    let tab_width = tab_width.max(1);
+    let mut expanded = String::new();
+    let mut width = 0;
+    for c in String::from_utf8_lossy(line).trim_end_matches('\r').chars() {
+        if c == '\t' {
+            let spaces = tab_width - width % tab_width;
+            expanded.extend(core::iter::repeat_n(' ', spaces));
+            width += spaces;
+        } else {
+            expanded.push(c);
+            width += 1;
+        }
+    }
+    expanded
+}
+
+/// Returns the display column of a character column, with tabs expanded as
+/// in [`expand_tabs`].
+fn display_column(line: &[u8], column: usize, tab_width: usize) -> usize {
+    let tab_width = tab_width.max(1);
+    String::from_utf8_lossy(line)
+        .chars()
+        .take(column.saturating_sub(1))
+        .fold(0, |width, c| {
+            if c == '\t' {
+                width + tab_width - width % tab_width
+            } else {
+                width + 1
+            }
+        })
+        + 1
+}
+
 macro_rules! format_line {
-    ($input:expr) => {
+    ($plain:expr, $tab_width:expr, $input:expr) => {
         format_args!(
             "{space:width$}{ptr}:{line_number}:{column_number}\n\
              {space:width$}{gutter}\n\
              {line_number:>width$}{gutter} {line}\n\
-             {space:width$}{gutter}{space:column_number$}{column}\n\
+             {space:width$}{gutter}{space:caret$}{column}\n\
              {space:width$}{gutter}",
             space = ' ',
-            ptr = "--> ".blue().bold(),
-            gutter = " |".blue(),
+            ptr = style("--> ", $plain, |s| s.blue().bold()),
+            gutter = style(" |", $plain, |s| s.blue()),
             line_number = $input.location_line(),
             column_number = $input.get_utf8_column(),
             width = usize::try_from($input.location_line().ilog10() + 1).unwrap_or(6) + 1,
-            line = std::str::from_utf8($input.get_line_beginning()).unwrap_or("<invalid UTF-8>"),
-            column = "^".red().bold(),
+            caret = display_column(
+                $input.get_line_beginning(),
+                $input.get_utf8_column(),
+                $tab_width,
+            ),
+            line = expand_tabs($input.get_line_beginning(), $tab_width),
+            column = style("^", $plain, |s| s.red().bold()),
         )
     };
 }
+#[cfg(feature = "std")]
 macro_rules! format_file_line {
-    ($input:expr, $path:expr $(,)?) => {
+    ($plain:expr, $tab_width:expr, $input:expr, $path:expr $(,)?) => {
         format_args!(
             "{space:width$}{ptr}{path}:{line_number}:{column_number}\n\
              {space:width$}{gutter}\n\
              {line_number:>width$}{gutter} {line}\n\
-             {space:width$}{gutter}{space:column_number$}{column}\n\
+             {space:width$}{gutter}{space:caret$}{column}\n\
              {space:width$}{gutter}",
             space = ' ',
-            ptr = "--> ".blue().bold(),
-            gutter = " |".blue(),
+            ptr = style("--> ", $plain, |s| s.blue().bold()),
+            gutter = style(" |", $plain, |s| s.blue()),
             path = $path.display(),
             line_number = $input.location_line(),
             column_number = $input.get_utf8_column(),
             width = usize::try_from($input.location_line().ilog10() + 1).unwrap_or(6) + 1,
-            line = std::str::from_utf8($input.get_line_beginning()).unwrap_or("<invalid UTF-8>"),
-            column = "^".red().bold(),
+            caret = display_column(
+                $input.get_line_beginning(),
+                $input.get_utf8_column(),
+                $tab_width,
+            ),
+            line = expand_tabs($input.get_line_beginning(), $tab_width),
+            column = style("^", $plain, |s| s.red().bold()),
         )
     };
 }
 
 macro_rules! format_error {
-    ($desc:expr) => {
+    ($plain:expr, $desc:expr) => {
         format_args!(
             "{error}: {desc}",
-            error = "error".red().bold(),
-            desc = format!("{}", $desc).bold(),
+            error = style("error", $plain, |s| s.red().bold()),
+            desc = style(&format!("{}", $desc), $plain, |s| s.bold()),
         )
     };
 }
 macro_rules! format_error_line {
-    ($input:expr, $desc:expr $(,)?) => {
+    ($plain:expr, $tab_width:expr, $input:expr, $desc:expr $(,)?) => {
         format_args!(
             "{error}\n{line}",
-            error = format_error!($desc),
-            line = format_line!($input),
+            error = format_error!($plain, $desc),
+            line = format_line!($plain, $tab_width, $input),
         )
     };
 }
+#[cfg(feature = "std")]
 macro_rules! format_error_file_line {
-    ($input:expr, $desc:expr $(,)?) => {
+    ($plain:expr, $tab_width:expr, $input:expr, $desc:expr $(,)?) => {
         format_args!(
             "{error}\n{line}",
-            error = format_error!($desc),
-            line = format_file_line!($input, $input.extra),
+            error = format_error!($plain, $desc),
+            line = format_file_line!($plain, $tab_width, $input, $input.extra),
         )
     };
 }
 
 macro_rules! format_context {
-    ($context:expr) => {
+    ($plain:expr, $context:expr) => {
         format_args!(
             "{context_in} {context}",
-            context_in = "in".blue().bold(),
-            context = $context.bold(),
+            context_in = style("in", $plain, |s| s.blue().bold()),
+            context = style($context, $plain, |s| s.bold()),
         )
     };
 }
 macro_rules! format_context_line {
-    ($input:expr, $context:expr $(,)?) => {
+    ($plain:expr, $tab_width:expr, $input:expr, $context:expr $(,)?) => {
         format_args!(
             "{context}\n{line}",
-            context = format_context!($context),
-            line = format_line!($input),
+            context = format_context!($plain, $context),
+            line = format_line!($plain, $tab_width, $input),
         )
     };
 }
+#[cfg(feature = "std")]
 macro_rules! format_context_file_line {
-    ($input:expr, $context:expr $(,)?) => {
+    ($plain:expr, $tab_width:expr, $input:expr, $context:expr $(,)?) => {
         format_args!(
             "{context}\n{line}",
-            context = format_context!($context),
-            line = format_file_line!($input, $input.extra),
+            context = format_context!($plain, $context),
+            line = format_file_line!($plain, $tab_width, $input, $input.extra),
         )
     };
 }
@@ -185,57 +592,259 @@ impl Display for ErrorKind {
         match self {
             ErrorKind::Char(expected) => write!(f, "expected '{expected}'"),
             ErrorKind::Nom(nom_err) => write!(f, "{}", nom_err.description()),
+            ErrorKind::FileVersion {
+                version: (major, minor),
+                max: (max_major, max_minor),
+            } => write!(
+                f,
+                "unsupported file version {major}.{minor}, \
+                 expected at most {max_major}.{max_minor}"
+            ),
+            ErrorKind::EmbeddingDepth { max } => {
+                write!(f, "embedded symbols nested deeper than {max} levels")
+            }
+            ErrorKind::Layer => write!(f, "expected layer index (non-negative integer)"),
+            ErrorKind::PolygonPoints { declared, found } => {
+                write!(f, "polygon declared {declared} points but found {found}")
+            }
+            ErrorKind::MissingVersion => {
+                write!(f, "schematic must begin with a version line (v {{ ... }})")
+            }
+            ErrorKind::MissingVersionProperty => {
+                write!(
+                    f,
+                    "version line has no property, expected `v {{}}` or `v {{ ... }}`"
+                )
+            }
+            ErrorKind::UnexpectedKind { expected, found } => {
+                write!(f, "expected a {expected}, found a {found}")
+            }
         }
     }
 }
 
-impl Display for ErrorInput<&str> {
+/// Formatting of a part of an [`Error`] with the tab width of its source
+/// line.
+///
+/// Implemented by [`ErrorInput`] and [`InputContext`] for the inputs that
+/// they are displayed for, see [`Error::display_with_tab_width`].
+pub trait DisplayTabWidth {
+    /// Formats the value like [`Display`], with tabs in the source line
+    /// expanded to `tab_width` columns.
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, tab_width: usize) -> fmt::Result;
+}
+
+impl<I> Display for ErrorInput<I>
+where
+    Self: DisplayTabWidth,
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_fmt(format_error!(format_args!("{}", self.kind)))
+        self.fmt_with_tab_width(f, DEFAULT_TAB_WIDTH)
     }
 }
 
-impl Display for ErrorInput<Span<'_>> {
+impl<I> Display for InputContext<'_, I>
+where
+    Self: DisplayTabWidth,
+{
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_fmt(format_error_line!(self.input, self.kind))
+        self.fmt_with_tab_width(f, DEFAULT_TAB_WIDTH)
     }
 }
 
-impl Display for ErrorInput<FileSpan<'_, '_>> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_fmt(format_error_file_line!(self.input, self.kind))
+impl DisplayTabWidth for ErrorInput<&str> {
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, _tab_width: usize) -> fmt::Result {
+        let plain = f.alternate();
+        f.write_fmt(format_error!(plain, format_args!("{}", self.kind)))
     }
 }
 
-impl Display for InputContext<'_, &str> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_fmt(format_context!(self.name))
+impl DisplayTabWidth for ErrorInput<Span<'_>> {
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, tab_width: usize) -> fmt::Result {
+        let plain = f.alternate();
+        f.write_fmt(format_error_line!(plain, tab_width, self.input, self.kind))
     }
 }
 
-impl Display for InputContext<'_, Span<'_>> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_fmt(format_context_line!(self.input, self.name))
+#[cfg(feature = "std")]
+impl DisplayTabWidth for ErrorInput<FileSpan<'_, '_>> {
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, tab_width: usize) -> fmt::Result {
+        let plain = f.alternate();
+        f.write_fmt(format_error_file_line!(
+            plain, tab_width, self.input, self.kind
+        ))
     }
 }
 
-impl Display for InputContext<'_, FileSpan<'_, '_>> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.write_fmt(format_context_file_line!(self.input, self.name))
+impl DisplayTabWidth for ErrorInput<ByteSpan<'_>> {
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, tab_width: usize) -> fmt::Result {
+        let plain = f.alternate();
+        f.write_fmt(format_error_line!(plain, tab_width, self.input, self.kind))
+    }
+}
+
+#[cfg(feature = "std")]
+impl DisplayTabWidth for ErrorInput<ByteFileSpan<'_, '_>> {
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, tab_width: usize) -> fmt::Result {
+        let plain = f.alternate();
+        f.write_fmt(format_error_file_line!(
+            plain, tab_width, self.input, self.kind
+        ))
+    }
+}
+
+impl DisplayTabWidth for ErrorInput<Location> {
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, tab_width: usize) -> fmt::Result {
+        let plain = f.alternate();
+        f.write_fmt(format_error_line!(plain, tab_width, self.input, self.kind))
+    }
+}
+
+#[cfg(feature = "std")]
+impl DisplayTabWidth for ErrorInput<Location<PathBuf>> {
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, tab_width: usize) -> fmt::Result {
+        let plain = f.alternate();
+        f.write_fmt(format_error_file_line!(
+            plain, tab_width, self.input, self.kind
+        ))
+    }
+}
+
+impl DisplayTabWidth for InputContext<'_, &str> {
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, _tab_width: usize) -> fmt::Result {
+        let plain = f.alternate();
+        f.write_fmt(format_context!(plain, self.name))
+    }
+}
+
+impl DisplayTabWidth for InputContext<'_, Span<'_>> {
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, tab_width: usize) -> fmt::Result {
+        let plain = f.alternate();
+        f.write_fmt(format_context_line!(
+            plain, tab_width, self.input, self.name
+        ))
+    }
+}
+
+#[cfg(feature = "std")]
+impl DisplayTabWidth for InputContext<'_, FileSpan<'_, '_>> {
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, tab_width: usize) -> fmt::Result {
+        let plain = f.alternate();
+        f.write_fmt(format_context_file_line!(
+            plain, tab_width, self.input, self.name
+        ))
+    }
+}
+
+impl DisplayTabWidth for InputContext<'_, ByteSpan<'_>> {
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, tab_width: usize) -> fmt::Result {
+        let plain = f.alternate();
+        f.write_fmt(format_context_line!(
+            plain, tab_width, self.input, self.name
+        ))
+    }
+}
+
+#[cfg(feature = "std")]
+impl DisplayTabWidth for InputContext<'_, ByteFileSpan<'_, '_>> {
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, tab_width: usize) -> fmt::Result {
+        let plain = f.alternate();
+        f.write_fmt(format_context_file_line!(
+            plain, tab_width, self.input, self.name
+        ))
+    }
+}
+
+impl DisplayTabWidth for InputContext<'_, Location> {
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, tab_width: usize) -> fmt::Result {
+        let plain = f.alternate();
+        f.write_fmt(format_context_line!(
+            plain, tab_width, self.input, self.name
+        ))
+    }
+}
+
+#[cfg(feature = "std")]
+impl DisplayTabWidth for InputContext<'_, Location<PathBuf>> {
+    fn fmt_with_tab_width(&self, f: &mut fmt::Formatter<'_>, tab_width: usize) -> fmt::Result {
+        let plain = f.alternate();
+        f.write_fmt(format_context_file_line!(
+            plain, tab_width, self.input, self.name
+        ))
     }
 }
 
 impl<I> Display for Error<I>
 where
-    ErrorInput<I>: Display,
-    InputContext<'static, I>: Display,
+    ErrorInput<I>: DisplayTabWidth,
+    InputContext<'static, I>: DisplayTabWidth,
 {
     /// Write human readable error.
+    ///
+    /// The alternate format `{:#}` writes the error without colors. Tabs in
+    /// source lines are expanded to [`DEFAULT_TAB_WIDTH`] columns, so the
+    /// caret stays aligned. Use [`Error::display_with_tab_width`] for another
+    /// tab width.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.err.fmt(f)?;
+        self.display_with_tab_width(DEFAULT_TAB_WIDTH).fmt(f)
+    }
+}
 
-        self.context
-            .iter()
-            .try_for_each(|context| write!(f, "\n{context}"))
+/// Error displayed with a tab width, see [`Error::display_with_tab_width`].
+#[derive(Clone, Copy, Debug)]
+pub struct WithTabWidth<'a, I> {
+    error: &'a Error<I>,
+    tab_width: usize,
+}
+
+impl<I> Display for WithTabWidth<'_, I>
+where
+    ErrorInput<I>: DisplayTabWidth,
+    InputContext<'static, I>: DisplayTabWidth,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.err.fmt_with_tab_width(f, self.tab_width)?;
+        self.error.context.iter().try_for_each(|context| {
+            f.write_str("\n")?;
+            context.fmt_with_tab_width(f, self.tab_width)
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl<X> Display for ReadError<X>
+where
+    Error<Location<X>>: Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io(e) => {
+                let plain = f.alternate();
+                f.write_fmt(format_error!(plain, e))
+            }
+            ReadError::Parse(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Display for FileError {
+    /// Writes the error with the path of the file.
+    ///
+    /// Parse errors already refer to the path, so they are written as is.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &*self.error {
+            ReadError::Io(e) => {
+                let plain = f.alternate();
+                write!(
+                    f,
+                    "{error}\n  {ptr}{path}",
+                    error = format_error!(plain, e),
+                    ptr = style("--> ", plain, |s| s.blue().bold()),
+                    path = self.path.display(),
+                )
+            }
+            ReadError::Parse(e) => e.fmt(f),
+        }
     }
 }
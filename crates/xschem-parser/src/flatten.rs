@@ -0,0 +1,228 @@
+//! Flattening of embedded symbols and resolution of symbol references.
+use crate::options::DEFAULT_MAX_EMBEDDING_DEPTH;
+use crate::token::{
+    Arc, Component, Embedding, FiniteDouble, Flip, Line, Object, ObjectRef, Polygon, Rectangle,
+    Rotation, Schematic, Text, Wire,
+};
+
+/// Returns the orientation of an object of a symbol placed by a component.
+///
+/// This follows Xschem: a flipped component reverses the rotation of objects,
+/// which for odd rotations is a half turn more than the sum of rotations.
+fn orientation<I>(component: &Component<I>, rotation: Rotation, flip: Flip) -> (Rotation, Flip) {
+    let reversed = component.flip == Flip::Flipped && u8::from(rotation) % 2 == 1;
+    let steps = u8::from(component.rotation) + u8::from(rotation) + if reversed { 2 } else { 0 };
+    (
+        Rotation::try_from(steps % 4).unwrap_or_default(),
+        Flip::from(bool::from(component.flip) != bool::from(flip)),
+    )
+}
+
+/// Returns the start angle in degrees of an arc of a symbol placed by a
+/// component, in the range `[0, 360)`.
+///
+/// Angles are counterclockwise on screen, so each rotation step subtracts 90
+/// degrees and flipping mirrors the arc around the vertical axis.
+fn start_angle<I>(component: &Component<I>, arc: &Arc<I>) -> f64 {
+    let rotation = 270.0 * f64::from(u8::from(component.rotation));
+    let angle = match component.flip {
+        Flip::Unflipped => rotation + *arc.start_angle,
+        Flip::Flipped => rotation + 180.0 - *arc.sweep_angle - *arc.start_angle,
+    } % 360.0;
+    if angle < 0.0 { angle + 360.0 } else { angle }
+}
+
+/// Clones a borrowed object.
+fn cloned<I: Clone>(object: ObjectRef<'_, I>) -> Object<I> {
+    match object {
+        ObjectRef::Arc(o) => Object::Arc(o.clone()),
+        ObjectRef::Component(o) => Object::Component(o.clone()),
+        ObjectRef::Line(o) => Object::Line(o.clone()),
+        ObjectRef::Polygon(o) => Object::Polygon(o.clone()),
+        ObjectRef::Rectangle(o) => Object::Rectangle(o.clone()),
+        ObjectRef::Text(o) => Object::Text(o.clone()),
+        ObjectRef::Wire(o) => Object::Wire(o.clone()),
+        ObjectRef::Unknown(o) => Object::Unknown(o.clone()),
+    }
+}
+
+/// Maps an object of a symbol into the schematic coordinates of a component.
+///
+/// Unknown objects have no known coordinates and are copied unchanged.
+fn place<I: Clone>(component: &Component<I>, object: ObjectRef<'_, I>) -> Object<I> {
+    match object {
+        ObjectRef::Arc(arc) => Object::Arc(Arc {
+            center: component.transform(arc.center),
+            start_angle: FiniteDouble(start_angle(component, arc)),
+            ..arc.clone()
+        }),
+        ObjectRef::Component(c) => {
+            let (rotation, flip) = orientation(component, c.rotation, c.flip);
+            Object::Component(Component {
+                position: component.transform(c.position),
+                rotation,
+                flip,
+                ..c.clone()
+            })
+        }
+        ObjectRef::Line(line) => Object::Line(Line {
+            start: component.transform(line.start),
+            end: component.transform(line.end),
+            ..line.clone()
+        }),
+        ObjectRef::Polygon(polygon) => Object::Polygon(Polygon {
+            points: polygon
+                .points
+                .iter()
+                .map(|&p| component.transform(p))
+                .collect(),
+            ..polygon.clone()
+        }),
+        ObjectRef::Rectangle(rectangle) => {
+            let start = component.transform(rectangle.start);
+            let end = component.transform(rectangle.end);
+            Object::Rectangle(Rectangle {
+                start: start.min(end),
+                end: start.max(end),
+                ..rectangle.clone()
+            })
+        }
+        ObjectRef::Text(text) => {
+            let (rotation, flip) = orientation(component, text.rotation, text.flip);
+            Object::Text(Text {
+                position: component.transform(text.position),
+                rotation,
+                flip,
+                ..text.clone()
+            })
+        }
+        ObjectRef::Wire(wire) => Object::Wire(Wire {
+            start: component.transform(wire.start),
+            end: component.transform(wire.end),
+            ..wire.clone()
+        }),
+        ObjectRef::Unknown(o) => Object::Unknown(o.clone()),
+    }
+}
+
+impl<I: Clone> Schematic<I> {
+    /// Returns a copy of the schematic with embedded symbols inlined.
+    ///
+    /// The objects of each embedded symbol are transformed by the position,
+    /// rotation and flip of the component (see [`Component::transform`]) and
+    /// inserted after the component, which is kept without its embedding.
+    /// Pins of the symbol thereby end up at the connection points of the
+    /// component. Nested embeddings are flattened recursively, which is
+    /// bounded by the embedding depth limit of parsing (see
+    /// [`ParseOptions::max_embedding_depth`]).
+    ///
+    /// Only geometry is inlined: global properties of embedded symbols are
+    /// dropped and texts are not substituted, so `@name` and similar
+    /// references are kept as is.
+    ///
+    /// ```
+    /// let input = "v {xschem version=3.4.5 file_version=1.2}
+    /// C {res.sym} 100 0 0 0 {name=R1 embed=true}
+    /// [
+    /// v {xschem version=3.4.5 file_version=1.2}
+    /// L 4 0 -30 0 30 {}
+    /// ]
+    /// ";
+    /// let schematic = xschem_parser::from_str(input).unwrap().flatten();
+    ///
+    /// assert!(!schematic.has_embeddings());
+    /// assert_eq!(schematic.lines[0].start, (100.0, -30.0).try_into().unwrap());
+    /// ```
+    ///
+    /// [`ParseOptions::max_embedding_depth`]: crate::options::ParseOptions::max_embedding_depth
+    #[must_use]
+    pub fn flatten(&self) -> Schematic<I> {
+        let mut flat = Schematic::new(self.version.clone());
+        flat.vhdl_property.clone_from(&self.vhdl_property);
+        flat.symbol_property.clone_from(&self.symbol_property);
+        flat.verilog_property.clone_from(&self.verilog_property);
+        flat.spice_property.clone_from(&self.spice_property);
+        flat.tedax_property.clone_from(&self.tedax_property);
+
+        for object in self.objects() {
+            let ObjectRef::Component(component) = object else {
+                flat.add_object_mut(cloned(object));
+                continue;
+            };
+            flat.push_component(Component {
+                reference: component.reference.clone(),
+                position: component.position,
+                rotation: component.rotation,
+                flip: component.flip,
+                extra: component.extra.clone(),
+                property: component.property.clone(),
+                embedding: None,
+            });
+            if let Some(embedding) = &component.embedding {
+                for o in embedding.0.flatten().objects() {
+                    flat.add_object_mut(place(component, o));
+                }
+            }
+        }
+        flat
+    }
+}
+
+impl<I: AsRef<str> + Clone> Schematic<I> {
+    /// Returns a copy of the schematic with referenced symbols embedded.
+    ///
+    /// The `loader` hook returns the schematic or symbol for a reference
+    /// (see [`Component::symbol_path`]), for example by reading and parsing
+    /// the file from a library path. Each component without an embedded
+    /// symbol gets the loaded schematic as its embedding, and references in
+    /// loaded and already embedded symbols are resolved recursively, so the
+    /// result holds the whole hierarchy and can be used with
+    /// [`Schematic::flatten`] or [`Schematic::embedded_schematics`].
+    ///
+    /// Components for which the loader returns `None` are kept unresolved.
+    /// The loader is called for every unresolved component, so callers
+    /// loading files should cache them. Resolution stops at a depth of
+    /// [`DEFAULT_MAX_EMBEDDING_DEPTH`], which bounds recursive references.
+    ///
+    /// ```
+    /// let input = "v {}\nC {res.sym} 100 0 0 0 {name=R1}";
+    /// let resistor = xschem_parser::from_str("v {}\nL 4 0 -30 0 30 {}").unwrap();
+    ///
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    /// let resolved = schematic.resolve_references(|path| {
+    ///     (path == "res.sym").then(|| resistor.clone())
+    /// });
+    ///
+    /// assert!(resolved.has_embeddings());
+    /// assert_eq!(resolved.flatten().lines.len(), 1);
+    /// ```
+    #[must_use]
+    pub fn resolve_references<F>(&self, mut loader: F) -> Schematic<I>
+    where
+        F: FnMut(&str) -> Option<Schematic<I>>,
+    {
+        let mut resolved = self.clone();
+        resolved.resolve_mut(&mut loader, DEFAULT_MAX_EMBEDDING_DEPTH);
+        resolved
+    }
+
+    fn resolve_mut<F>(&mut self, loader: &mut F, depth: usize)
+    where
+        F: FnMut(&str) -> Option<Schematic<I>>,
+    {
+        if depth == 0 {
+            return;
+        }
+        for component in &mut self.components {
+            if component.embedding.is_none() {
+                component.embedding = component
+                    .symbol_path()
+                    .and_then(&mut *loader)
+                    .map(Embedding);
+            }
+            if let Some(embedding) = &mut component.embedding {
+                embedding.0.resolve_mut(loader, depth - 1);
+            }
+        }
+    }
+}
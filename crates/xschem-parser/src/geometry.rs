@@ -0,0 +1,517 @@
+//! Geometry helpers for parsed objects.
+//!
+//! Coordinates computed by these helpers that would exceed the range of a
+//! finite double saturate to the largest finite value of the same sign.
+use core::ops::{Add, Mul, Neg, Sub};
+
+use crate::token::{
+    Arc, Component, FiniteDouble, Flip, Line, Object, ObjectRef, Polygon, Rectangle, Rotation,
+    Schematic, Text, Vec2, Wire,
+};
+
+/// Bounding box as minimum and maximum corner.
+type BoundingBox = (Vec2, Vec2);
+
+/// Returns the euclidean distance of a point to the segment between two
+/// points.
+#[cfg(feature = "std")]
+fn segment_distance(start: Vec2, end: Vec2, p: Vec2) -> f64 {
+    let (dx, dy) = (*end.x - *start.x, *end.y - *start.y);
+    let length = dx * dx + dy * dy;
+    // Project the point onto the segment, clamped to its end points.
+    let t = if length > 0.0 {
+        (((*p.x - *start.x) * dx + (*p.y - *start.y) * dy) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (*start.x + t * dx - *p.x).hypot(*start.y + t * dy - *p.y)
+}
+
+impl FiniteDouble {
+    /// Converts a value to a finite double, saturating infinite values to the
+    /// largest finite value of the same sign.
+    fn saturating(value: f64) -> Self {
+        Self(value.clamp(f64::MIN, f64::MAX))
+    }
+}
+
+impl Vec2 {
+    /// Returns the component-wise minimum of two vectors.
+    pub(crate) fn min(self, other: Self) -> Self {
+        Self {
+            x: FiniteDouble(self.x.min(*other.x)),
+            y: FiniteDouble(self.y.min(*other.y)),
+        }
+    }
+
+    /// Returns the component-wise maximum of two vectors.
+    pub(crate) fn max(self, other: Self) -> Self {
+        Self {
+            x: FiniteDouble(self.x.max(*other.x)),
+            y: FiniteDouble(self.y.max(*other.y)),
+        }
+    }
+
+    /// Returns the euclidean distance between two coordinates.
+    ///
+    /// Requires the `std` feature.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn distance(&self, other: &Self) -> f64 {
+        (*self.x - *other.x).hypot(*self.y - *other.y)
+    }
+
+    /// Returns the coordinate halfway between two coordinates.
+    #[must_use]
+    pub fn midpoint(&self, other: &Self) -> Self {
+        Self {
+            x: FiniteDouble(self.x.midpoint(*other.x)),
+            y: FiniteDouble(self.y.midpoint(*other.y)),
+        }
+    }
+}
+
+/// Component-wise addition.
+impl Add for Vec2 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            x: FiniteDouble::saturating(*self.x + *rhs.x),
+            y: FiniteDouble::saturating(*self.y + *rhs.y),
+        }
+    }
+}
+
+/// Component-wise subtraction.
+impl Sub for Vec2 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self {
+            x: FiniteDouble::saturating(*self.x - *rhs.x),
+            y: FiniteDouble::saturating(*self.y - *rhs.y),
+        }
+    }
+}
+
+/// Scales a coordinate.
+///
+/// # Panics
+///
+/// Panics if the factor is not finite.
+impl Mul<f64> for Vec2 {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        assert!(rhs.is_finite(), "factor is not finite");
+        Self {
+            x: FiniteDouble::saturating(*self.x * rhs),
+            y: FiniteDouble::saturating(*self.y * rhs),
+        }
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            x: FiniteDouble(-*self.x),
+            y: FiniteDouble(-*self.y),
+        }
+    }
+}
+
+impl Rotation {
+    /// Rotates a coordinate around the origin.
+    ///
+    /// Each step rotates by 90 degrees, mapping `(x, y)` to `(-y, x)`. Since
+    /// the y-axis of Xschem points down, this is a clockwise rotation on
+    /// screen.
+    #[must_use]
+    pub fn apply(&self, v: Vec2) -> Vec2 {
+        let Vec2 { x, y } = v;
+        let (x, y) = match self {
+            Rotation::Zero => (*x, *y),
+            Rotation::One => (-*y, *x),
+            Rotation::Two => (-*x, -*y),
+            Rotation::Three => (*y, -*x),
+        };
+        Vec2 {
+            x: FiniteDouble(x),
+            y: FiniteDouble(y),
+        }
+    }
+}
+
+impl Flip {
+    /// Mirrors a coordinate around the y-axis, mapping `(x, y)` to `(-x, y)`
+    /// if flipped.
+    #[must_use]
+    pub fn apply(&self, v: Vec2) -> Vec2 {
+        match self {
+            Flip::Unflipped => v,
+            Flip::Flipped => Vec2 {
+                x: FiniteDouble(-*v.x),
+                y: v.y,
+            },
+        }
+    }
+}
+
+/// Returns the smallest bounding box containing both boxes.
+fn union(a: BoundingBox, b: BoundingBox) -> BoundingBox {
+    (a.0.min(b.0), a.1.max(b.1))
+}
+
+/// Returns the bounding box of a set of points.
+fn bounds(points: impl IntoIterator<Item = Vec2>) -> Option<BoundingBox> {
+    points.into_iter().map(|p| (p, p)).reduce(union)
+}
+
+/// Cosine and sine of the multiples of 90 degrees, which bound an arc on
+/// the axes.
+const QUADRANTS: [(f64, f64); 4] = [(1.0, 0.0), (0.0, 1.0), (-1.0, 0.0), (0.0, -1.0)];
+
+impl<I> Arc<I> {
+    /// Returns the point on the circle of the arc with the given cosine and
+    /// sine of its angle.
+    ///
+    /// The circle has the absolute radius of the arc, so that a negative
+    /// radius does not mirror the arc through its center.
+    fn point(&self, cos: f64, sin: f64) -> Vec2 {
+        let radius = self.radius.abs();
+        Vec2 {
+            x: FiniteDouble::saturating(*self.center.x + radius * cos),
+            y: FiniteDouble::saturating(*self.center.y - radius * sin),
+        }
+    }
+
+    /// Returns the bounding box of the arc.
+    ///
+    /// The box contains the start and end point and the points where the arc
+    /// crosses the horizontal or vertical axis through its center. A sweep of
+    /// 360 degrees or more spans the full circle, `center ± radius`. The box
+    /// of an arc with a zero radius is its center.
+    ///
+    /// Without the `std` feature, which is required for the trigonometry, the
+    /// box always spans the full circle.
+    pub fn bounding_box(&self) -> BoundingBox {
+        #[cfg(feature = "std")]
+        if self.sweep_angle.abs() < 360.0 {
+            let low = self.start_angle.min(*self.start_angle + *self.sweep_angle);
+            let crossings = QUADRANTS
+                .iter()
+                .zip([0.0, 90.0, 180.0, 270.0])
+                .filter(|(_, angle)| {
+                    let offset = (angle - low) % 360.0;
+                    let offset = if offset < 0.0 { offset + 360.0 } else { offset };
+                    offset <= self.sweep_angle.abs()
+                })
+                .map(|(&(cos, sin), _)| self.point(cos, sin));
+            let ends = [self.start_point(), self.end_point()];
+            if let Some(bounds) = bounds(ends.into_iter().chain(crossings)) {
+                return bounds;
+            }
+        }
+
+        QUADRANTS
+            .iter()
+            .map(|&(cos, sin)| self.point(cos, sin))
+            .map(|p| (p, p))
+            .reduce(union)
+            .unwrap_or((self.center, self.center))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<I> Arc<I> {
+    /// Returns the point on the arc at a fraction of its sweep, where 0 is
+    /// the start point and 1 the end point.
+    ///
+    /// Like Xschem, angles are in degrees and counterclockwise on screen, so
+    /// the y-axis is negated. Requires the `std` feature.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the fraction is not finite.
+    #[must_use]
+    pub fn point_at(&self, fraction: f64) -> Vec2 {
+        assert!(fraction.is_finite(), "fraction is not finite");
+        let angle = *self.start_angle + fraction * *self.sweep_angle;
+        let (sin, cos) = angle.to_radians().sin_cos();
+        self.point(cos, sin)
+    }
+
+    /// Returns the start point of the arc, see [`Arc::point_at`].
+    #[must_use]
+    pub fn start_point(&self) -> Vec2 {
+        self.point_at(0.0)
+    }
+
+    /// Returns the end point of the arc, see [`Arc::point_at`].
+    #[must_use]
+    pub fn end_point(&self) -> Vec2 {
+        self.point_at(1.0)
+    }
+}
+
+impl<I> Component<I> {
+    /// Maps a symbol-local coordinate into schematic coordinates.
+    ///
+    /// The same convention as Xschem is used: the coordinate is first flipped
+    /// (see [`Flip::apply`]), then rotated around the symbol origin (see
+    /// [`Rotation::apply`]) and finally translated by the component position.
+    pub fn transform(&self, local: Vec2) -> Vec2 {
+        self.position + self.rotation.apply(self.flip.apply(local))
+    }
+}
+
+impl<I> Line<I> {
+    /// Returns the bounding box of the line.
+    pub fn bounding_box(&self) -> BoundingBox {
+        (self.start.min(self.end), self.start.max(self.end))
+    }
+
+    /// Returns the euclidean distance of a point to the line segment.
+    ///
+    /// Requires the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn distance_to(&self, p: Vec2) -> f64 {
+        segment_distance(self.start, self.end, p)
+    }
+}
+
+impl<I> Polygon<I> {
+    /// Returns the bounding box of the polygon points, or [`None`] if the
+    /// polygon has no points.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        bounds(self.points.iter().copied())
+    }
+
+    /// Returns whether a point lies inside the polygon.
+    ///
+    /// The polygon is implicitly closed from the last to the first point and
+    /// self-intersecting polygons use the even-odd rule. Points exactly on
+    /// an edge may be reported as either inside or outside.
+    pub fn contains(&self, p: Vec2) -> bool {
+        let points = &self.points;
+        let Some(&last) = points.last() else {
+            return false;
+        };
+        // Cast a ray in the positive x direction and count the crossed edges.
+        let mut inside = false;
+        let mut previous = last;
+        for &point in points.iter() {
+            if (*point.y > *p.y) != (*previous.y > *p.y) {
+                let x = *point.x
+                    + (*p.y - *point.y) / (*previous.y - *point.y) * (*previous.x - *point.x);
+                if *p.x < x {
+                    inside = !inside;
+                }
+            }
+            previous = point;
+        }
+        inside
+    }
+}
+
+impl<I> Rectangle<I> {
+    /// Returns the center of the rectangle.
+    pub fn center(&self) -> Vec2 {
+        Vec2 {
+            x: FiniteDouble(*self.start.x / 2.0 + *self.end.x / 2.0),
+            y: FiniteDouble(*self.start.y / 2.0 + *self.end.y / 2.0),
+        }
+    }
+
+    /// Returns the bounding box of the rectangle.
+    ///
+    /// The start and end corner may be given in any order.
+    pub fn bounding_box(&self) -> BoundingBox {
+        (self.start.min(self.end), self.start.max(self.end))
+    }
+
+    /// Returns whether a point lies inside the rectangle or on its border.
+    ///
+    /// The start and end corner may be given in any order.
+    pub fn contains(&self, p: Vec2) -> bool {
+        let (min, max) = self.bounding_box();
+        (*min.x..=*max.x).contains(&*p.x) && (*min.y..=*max.y).contains(&*p.y)
+    }
+}
+
+impl<I> Wire<I> {
+    /// Returns the bounding box of the wire.
+    pub fn bounding_box(&self) -> BoundingBox {
+        (self.start.min(self.end), self.start.max(self.end))
+    }
+
+    /// Returns the euclidean distance of a point to the wire.
+    ///
+    /// Requires the `std` feature.
+    #[cfg(feature = "std")]
+    pub fn distance_to(&self, p: Vec2) -> f64 {
+        segment_distance(self.start, self.end, p)
+    }
+}
+
+impl<I> ObjectRef<'_, I> {
+    /// Returns the bounding box of the object.
+    ///
+    /// Texts and components only contribute their position, since their
+    /// extents depend on the font and the referenced symbol. Unknown objects
+    /// have no bounding box.
+    #[must_use]
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        match self {
+            ObjectRef::Arc(o) => Some(o.bounding_box()),
+            ObjectRef::Component(Component { position, .. })
+            | ObjectRef::Text(Text { position, .. }) => Some((*position, *position)),
+            ObjectRef::Line(o) => Some(o.bounding_box()),
+            ObjectRef::Polygon(o) => o.bounding_box(),
+            ObjectRef::Rectangle(o) => Some(o.bounding_box()),
+            ObjectRef::Wire(o) => Some(o.bounding_box()),
+            ObjectRef::Unknown(_) => None,
+        }
+    }
+}
+
+impl<I> Schematic<I> {
+    /// Returns the bounding box of all objects in the schematic as minimum and
+    /// maximum corner, or [`None`] if the schematic has no objects.
+    ///
+    /// See [`ObjectRef::bounding_box`] for the contribution of each object.
+    /// Embedded symbols are not taken into account.
+    pub fn bounding_box(&self) -> Option<BoundingBox> {
+        self.objects()
+            .filter_map(|o| o.bounding_box())
+            .reduce(union)
+    }
+
+    /// Moves all objects by an offset.
+    ///
+    /// Embedded symbols are in the coordinates of their component, so only
+    /// the position of a component moves and its embedded symbol is left
+    /// unchanged, like for [`Schematic::merge_offset`].
+    ///
+    /// ```
+    /// let input = "v {xschem version=3.4.5 file_version=1.2}\nN 0 0 10 0 {lab=A}";
+    /// let mut schematic = xschem_parser::from_str(input).unwrap();
+    /// schematic.translate((100.0, -50.0).try_into().unwrap());
+    ///
+    /// assert_eq!(schematic.wires[0].to_string(), "N 100 -50 110 -50 {lab=A}");
+    /// ```
+    pub fn translate(&mut self, offset: Vec2) {
+        translate_all(&mut self.arcs, offset);
+        translate_all(&mut self.components, offset);
+        translate_all(&mut self.lines, offset);
+        translate_all(&mut self.polygons, offset);
+        translate_all(&mut self.rectangles, offset);
+        translate_all(&mut self.texts, offset);
+        translate_all(&mut self.wires, offset);
+    }
+}
+
+/// Object that can be moved by an offset.
+///
+/// Components are moved by their position, so the coordinates of an embedded
+/// symbol are unchanged.
+trait Translate {
+    fn translate(&mut self, offset: Vec2);
+}
+
+impl<I> Translate for Arc<I> {
+    fn translate(&mut self, offset: Vec2) {
+        self.center = self.center + offset;
+    }
+}
+
+impl<I> Translate for Component<I> {
+    fn translate(&mut self, offset: Vec2) {
+        self.position = self.position + offset;
+    }
+}
+
+impl<I> Translate for Line<I> {
+    fn translate(&mut self, offset: Vec2) {
+        self.start = self.start + offset;
+        self.end = self.end + offset;
+    }
+}
+
+impl<I> Translate for Polygon<I> {
+    fn translate(&mut self, offset: Vec2) {
+        for p in self.points.iter_mut() {
+            *p = *p + offset;
+        }
+    }
+}
+
+impl<I> Translate for Rectangle<I> {
+    fn translate(&mut self, offset: Vec2) {
+        self.start = self.start + offset;
+        self.end = self.end + offset;
+    }
+}
+
+impl<I> Translate for Text<I> {
+    fn translate(&mut self, offset: Vec2) {
+        self.position = self.position + offset;
+    }
+}
+
+impl<I> Translate for Wire<I> {
+    fn translate(&mut self, offset: Vec2) {
+        self.start = self.start + offset;
+        self.end = self.end + offset;
+    }
+}
+
+/// Moves all objects by an offset.
+fn translate_all<T: Translate>(objects: &mut [T], offset: Vec2) {
+    for o in objects {
+        o.translate(offset);
+    }
+}
+
+/// Returns a copy of an object moved by an offset.
+fn translated<T: Translate + Clone>(object: &T, offset: Vec2) -> T {
+    let mut object = object.clone();
+    object.translate(offset);
+    object
+}
+
+impl<I: Clone> ObjectRef<'_, I> {
+    /// Returns a copy of the object translated by an offset.
+    ///
+    /// Components are translated by their position, so the coordinates of an
+    /// embedded symbol are unchanged. Unknown objects are copied unchanged.
+    fn translated(self, offset: Vec2) -> Object<I> {
+        match self {
+            ObjectRef::Arc(o) => Object::Arc(translated(o, offset)),
+            ObjectRef::Component(o) => Object::Component(translated(o, offset)),
+            ObjectRef::Line(o) => Object::Line(translated(o, offset)),
+            ObjectRef::Polygon(o) => Object::Polygon(translated(o, offset)),
+            ObjectRef::Rectangle(o) => Object::Rectangle(translated(o, offset)),
+            ObjectRef::Text(o) => Object::Text(translated(o, offset)),
+            ObjectRef::Wire(o) => Object::Wire(translated(o, offset)),
+            ObjectRef::Unknown(o) => Object::Unknown(o.clone()),
+        }
+    }
+}
+
+impl<I: Clone> Schematic<I> {
+    /// Appends copies of the objects of another schematic, translated by an
+    /// offset.
+    ///
+    /// Objects are appended in the input order of `other` and their properties
+    /// are copied unchanged. The version and global properties of `other` are
+    /// not merged.
+    pub fn merge_offset(&mut self, other: &Schematic<I>, offset: Vec2) {
+        for object in other.objects() {
+            self.add_object_mut(object.translated(offset));
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! Serialization to JSON without `serde`.
+//!
+//! The output has the same shape as the `serde::Serialize` implementations
+//! enabled by the `serde` feature, so tools can consume either:
+//!
+//! - A schematic is an object with the keys `version`, `vhdl_property`,
+//!   `symbol_property`, `verilog_property`, `spice_property`, `tedax_property`,
+//!   `texts`, `lines`, `rectangles`, `polygons`, `arcs`, `wires`, `components`,
+//!   `unknown_objects` and `order`. Global properties are `null` if missing.
+//! - A property is an object `{"prop": ..., "attrs": {...}}` with the full
+//!   property string and its attributes. Attributes are sorted by key.
+//! - Coordinates and sizes are objects `{"x": ..., "y": ...}`, polygon points
+//!   are an array of coordinates.
+//! - Rotations are numbers from 0 to 3 and flips are booleans.
+//! - Objects have the fields of their type in [`token`](crate::token), such as
+//!   `{"layer": ..., "start": ..., "end": ..., "property": ...}` for a line.
+//!   The `embedding` of a component is a schematic or `null`.
+//! - The `order` is an array of [`ObjectKind`] names, such as `"Wire"`.
+//!
+//! Strings are written as in the input, so quotes and escape sequences of
+//! Xschem are kept. Numbers are finite and written without an exponent.
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+use crate::token::{
+    Arc, Component, Flip, Line, ObjectKind, Polygon, Property, Rectangle, Schematic, Text,
+    UnknownObject, Vec2, Wire,
+};
+
+/// String with the characters reserved in JSON escaped, including quotes.
+struct Escaped<'a>(&'a str);
+
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                '\u{8}' => f.write_str("\\b")?,
+                '\u{c}' => f.write_str("\\f")?,
+                c if c < ' ' => write!(f, "\\u{:04x}", u32::from(c))?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+/// Writes the items of an array, using `item` to write each item.
+fn write_array<W: Write, T>(
+    w: &mut W,
+    items: impl IntoIterator<Item = T>,
+    mut item: impl FnMut(&mut W, T) -> fmt::Result,
+) -> fmt::Result {
+    w.write_char('[')?;
+    for (i, value) in items.into_iter().enumerate() {
+        if i > 0 {
+            w.write_char(',')?;
+        }
+        item(w, value)?;
+    }
+    w.write_char(']')
+}
+
+fn write_vec2<W: Write>(w: &mut W, v: Vec2) -> fmt::Result {
+    write!(w, r#"{{"x":{},"y":{}}}"#, v.x, v.y)
+}
+
+fn write_flip<W: Write>(w: &mut W, flip: Flip) -> fmt::Result {
+    write!(w, "{}", flip == Flip::Flipped)
+}
+
+fn write_property<W: Write, I: AsRef<str>>(w: &mut W, property: &Property<I>) -> fmt::Result {
+    write!(
+        w,
+        r#"{{"prop":{},"attrs":{{"#,
+        Escaped(property.prop.as_ref())
+    )?;
+    let mut attrs: Vec<_> = property
+        .attrs
+        .iter()
+        .map(|(k, v)| (k.as_ref(), v.as_ref()))
+        .collect();
+    attrs.sort_unstable();
+    for (i, (key, value)) in attrs.into_iter().enumerate() {
+        if i > 0 {
+            w.write_char(',')?;
+        }
+        write!(w, "{}:{}", Escaped(key), Escaped(value))?;
+    }
+    w.write_str("}}")
+}
+
+fn write_optional_property<W: Write, I: AsRef<str>>(
+    w: &mut W,
+    property: Option<&Property<I>>,
+) -> fmt::Result {
+    match property {
+        Some(property) => write_property(w, property),
+        None => w.write_str("null"),
+    }
+}
+
+fn write_text<W: Write, I: AsRef<str>>(w: &mut W, text: &Text<I>) -> fmt::Result {
+    write!(w, r#"{{"text":{},"position":"#, Escaped(text.text.as_ref()))?;
+    write_vec2(w, text.position)?;
+    write!(w, r#","rotation":{},"flip":"#, text.rotation as u8)?;
+    write_flip(w, text.flip)?;
+    w.write_str(r#","size":"#)?;
+    write_vec2(w, text.size)?;
+    w.write_str(r#","property":"#)?;
+    write_property(w, &text.property)?;
+    w.write_char('}')
+}
+
+/// Writes a line or rectangle, which have the same fields.
+fn write_segment<W: Write, I: AsRef<str>>(
+    w: &mut W,
+    layer: u64,
+    start: Vec2,
+    end: Vec2,
+    property: &Property<I>,
+) -> fmt::Result {
+    write!(w, r#"{{"layer":{layer},"start":"#)?;
+    write_vec2(w, start)?;
+    w.write_str(r#","end":"#)?;
+    write_vec2(w, end)?;
+    w.write_str(r#","property":"#)?;
+    write_property(w, property)?;
+    w.write_char('}')
+}
+
+fn write_polygon<W: Write, I: AsRef<str>>(w: &mut W, polygon: &Polygon<I>) -> fmt::Result {
+    write!(w, r#"{{"layer":{},"points":"#, polygon.layer)?;
+    write_array(w, polygon.points.iter(), |w, p| write_vec2(w, *p))?;
+    w.write_str(r#","property":"#)?;
+    write_property(w, &polygon.property)?;
+    w.write_char('}')
+}
+
+fn write_arc<W: Write, I: AsRef<str>>(w: &mut W, arc: &Arc<I>) -> fmt::Result {
+    write!(w, r#"{{"layer":{},"center":"#, arc.layer)?;
+    write_vec2(w, arc.center)?;
+    write!(
+        w,
+        r#","radius":{},"start_angle":{},"sweep_angle":{},"property":"#,
+        arc.radius, arc.start_angle, arc.sweep_angle,
+    )?;
+    write_property(w, &arc.property)?;
+    w.write_char('}')
+}
+
+fn write_wire<W: Write, I: AsRef<str>>(w: &mut W, wire: &Wire<I>) -> fmt::Result {
+    w.write_str(r#"{"start":"#)?;
+    write_vec2(w, wire.start)?;
+    w.write_str(r#","end":"#)?;
+    write_vec2(w, wire.end)?;
+    w.write_str(r#","property":"#)?;
+    write_property(w, &wire.property)?;
+    w.write_char('}')
+}
+
+fn write_component<W: Write, I: AsRef<str>>(w: &mut W, component: &Component<I>) -> fmt::Result {
+    write!(
+        w,
+        r#"{{"reference":{},"position":"#,
+        Escaped(component.reference.as_ref())
+    )?;
+    write_vec2(w, component.position)?;
+    write!(w, r#","rotation":{},"flip":"#, component.rotation as u8)?;
+    write_flip(w, component.flip)?;
+    w.write_str(r#","extra":"#)?;
+    write_array(w, &component.extra, |w, t| {
+        write!(w, "{}", Escaped(t.as_ref()))
+    })?;
+    w.write_str(r#","property":"#)?;
+    write_property(w, &component.property)?;
+    w.write_str(r#","embedding":"#)?;
+    match &component.embedding {
+        Some(embedding) => write_json(w, embedding)?,
+        None => w.write_str("null")?,
+    }
+    w.write_char('}')
+}
+
+fn write_unknown<W: Write, I: AsRef<str>>(w: &mut W, object: &UnknownObject<I>) -> fmt::Result {
+    write!(w, r#"{{"tag":{},"tokens":"#, Escaped(object.tag.as_ref()))?;
+    write_array(w, &object.tokens, |w, t| {
+        write!(w, "{}", Escaped(t.as_ref()))
+    })?;
+    w.write_str(r#","property":"#)?;
+    write_property(w, &object.property)?;
+    w.write_char('}')
+}
+
+fn kind_name(kind: ObjectKind) -> &'static str {
+    match kind {
+        ObjectKind::Arc => "Arc",
+        ObjectKind::Component => "Component",
+        ObjectKind::Line => "Line",
+        ObjectKind::Polygon => "Polygon",
+        ObjectKind::Rectangle => "Rectangle",
+        ObjectKind::Text => "Text",
+        ObjectKind::Wire => "Wire",
+        ObjectKind::Unknown => "Unknown",
+    }
+}
+
+/// Writes a schematic as JSON, see [`Schematic::to_json`].
+pub fn write_json<W: Write, I: AsRef<str>>(w: &mut W, schematic: &Schematic<I>) -> fmt::Result {
+    w.write_str(r#"{"version":"#)?;
+    write_property(w, &schematic.version.0)?;
+    w.write_str(r#","vhdl_property":"#)?;
+    write_optional_property(w, schematic.vhdl_property.as_ref().map(|p| &p.0))?;
+    w.write_str(r#","symbol_property":"#)?;
+    write_optional_property(w, schematic.symbol_property.as_ref().map(|p| &p.0))?;
+    w.write_str(r#","verilog_property":"#)?;
+    write_optional_property(w, schematic.verilog_property.as_ref().map(|p| &p.0))?;
+    w.write_str(r#","spice_property":"#)?;
+    write_optional_property(w, schematic.spice_property.as_ref().map(|p| &p.0))?;
+    w.write_str(r#","tedax_property":"#)?;
+    write_optional_property(w, schematic.tedax_property.as_ref().map(|p| &p.0))?;
+
+    w.write_str(r#","texts":"#)?;
+    write_array(w, schematic.texts.iter(), write_text)?;
+    w.write_str(r#","lines":"#)?;
+    write_array(w, schematic.lines.iter(), |w, l: &Line<I>| {
+        write_segment(w, l.layer, l.start, l.end, &l.property)
+    })?;
+    w.write_str(r#","rectangles":"#)?;
+    write_array(w, schematic.rectangles.iter(), |w, r: &Rectangle<I>| {
+        write_segment(w, r.layer, r.start, r.end, &r.property)
+    })?;
+    w.write_str(r#","polygons":"#)?;
+    write_array(w, schematic.polygons.iter(), write_polygon)?;
+    w.write_str(r#","arcs":"#)?;
+    write_array(w, schematic.arcs.iter(), write_arc)?;
+    w.write_str(r#","wires":"#)?;
+    write_array(w, schematic.wires.iter(), write_wire)?;
+    w.write_str(r#","components":"#)?;
+    write_array(w, schematic.components.iter(), write_component)?;
+    w.write_str(r#","unknown_objects":"#)?;
+    write_array(w, schematic.unknown_objects.iter(), write_unknown)?;
+    w.write_str(r#","order":"#)?;
+    write_array(w, &schematic.order, |w, kind| {
+        write!(w, r#""{}""#, kind_name(*kind))
+    })?;
+    w.write_char('}')
+}
+
+impl<I: AsRef<str>> Schematic<I> {
+    /// Serializes the schematic, including embedded symbols, as JSON.
+    ///
+    /// See the [`json`](crate::json) module for the schema. The output is
+    /// compact, without whitespace between tokens.
+    ///
+    /// ```
+    /// let schematic = xschem_parser::from_str("v {}\nN 0 0 20 0 {lab=\"A\"}").unwrap();
+    /// let json = schematic.to_json();
+    ///
+    /// assert!(json.contains(
+    ///     r#""wires":[{"start":{"x":0,"y":0},"end":{"x":20,"y":0},"property":{"prop":"lab=\"A\"","attrs":{"lab":"\"A\""}}}]"#
+    /// ));
+    /// ```
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let mut s = String::new();
+        // Writing to a `String` does not fail.
+        let _ = write_json(&mut s, self);
+        s
+    }
+}
@@ -9,8 +9,16 @@
 //! string or byte slice. The parser is zero-copy so the resulting data
 //! structure contains references to the input.
 //!
+//! Both `\n` and `\r\n` line endings are supported. Since spans reference the
+//! input, carriage returns within braces, e.g. in multi-line properties and
+//! texts, are preserved. A leading UTF-8 byte order mark is skipped.
+//!
+//! Use [`token::Schematic::to_xschem_string`] or [`write::write`] to
+//! serialize a schematic back into the Xschem file format.
+//!
 //! The parse error result [`Error`] implements [`std::fmt::Display`] to convert
-//! the error to a nice human readable format.
+//! the error to a nice human readable format. Use [`Error::to_plain_string`]
+//! to format the error without colors.
 //!
 //! # Examples
 //!
@@ -19,7 +27,9 @@
 //! ```
 //! use nom::Input;
 //! use xschem_parser::Span;
-//! use xschem_parser::token::{Flip, Objects, Property, Rotation, Schematic, Text, Version};
+//! use xschem_parser::token::{
+//!     Flip, ObjectKind, Objects, Property, Rotation, Schematic, Text, Version,
+//! };
 //!
 //! let input = "\
 //! v {xschem version=3.4.5 file_version=1.2}
@@ -67,6 +77,8 @@
 //!     arcs: Objects::default(),
 //!     wires: Objects::default(),
 //!     components: Objects::default(),
+//!     unknown_objects: Objects::default(),
+//!     order: vec![ObjectKind::Text],
 //! };
 //!
 //! let result = xschem_parser::from_str(input);
@@ -97,20 +109,19 @@
 //! let result = xschem_parser::from_str(input);
 //!
 //! assert!(result.is_err());
-//! assert_eq!(result.unwrap_err().to_string(), expected);
+//! assert_eq!(result.unwrap_err().to_plain_string(), expected);
 //! ```
 //!
 //! ## Parse from file
 //!
-//! Since a parsed schematic contains references to the input, this library
-//! cannot provide an implementation of parsing from file, since that would
-//! require copying the contents of the file contents to make the lifetimes work
-//! out.
+//! Since a parsed schematic contains references to the input, the contents
+//! of a file must outlive the parsed schematic when using [`from_str_file`].
 //!
 //! ```no_run
+//! # #[cfg(feature = "std")]
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
 //! use std::path::Path;
 //!
-//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
 //! let path = Path::new("test.sch");
 //! let contents = std::fs::read_to_string(path)?;
 //! match xschem_parser::from_str_file(&contents, path) {
@@ -119,56 +130,395 @@
 //! }
 //! # Ok(())
 //! # }
+//! # #[cfg(not(feature = "std"))]
+//! # fn main() {}
 //! ```
 //!
+//! Alternatively, [`from_path`] and [`from_reader`] copy the parsed input into
+//! an owned `Schematic<String>`.
+//!
+//! ```no_run
+//! # #[cfg(feature = "std")]
+//! match xschem_parser::from_path("test.sch") {
+//!     Ok(schematic) => println!("{schematic}"),
+//!     Err(e) => eprintln!("{e}"),
+//! }
+//! ```
+//!
+//! # Features
+//!
+//! - `std` (default): file and reader APIs such as [`from_path`], rendering to
+//!   SVG with [`svg`], and colored error formatting. Without it the crate is
+//!   `no_std` and only requires `alloc`. Errors are then always formatted
+//!   without colors.
+//! - `no-color`: disables colored formatting of errors.
+//! - `rayon`: parallel parsing with `parse_parallel`, implies `std`.
+//! - `serde`: implements `serde::Serialize` for the types in [`token`]. The
+//!   [`json`] module writes JSON of the same shape without `serde`.
+//!
 //! [Xschem]: https://xschem.sourceforge.io/stefan/index.html
 //! [developer info]: https://xschem.sourceforge.io/stefan/xschem_man/developer_info.html
 
-use std::path::Path;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::borrow::{Cow, ToOwned};
+use alloc::vec::Vec;
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use std::io::Read;
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
 
+use nom::error::{ErrorKind as NomErrorKind, FromExternalError};
+use nom::{AsChar, Compare, Finish, Input, Offset, ParseTo, Parser};
 use nom_locate::LocatedSpan;
 
-use crate::error::Error;
-use crate::token::Schematic;
+#[cfg(feature = "std")]
+use crate::error::ReadError;
+use crate::error::{Error, Location};
+use crate::options::ParseOptions;
+use crate::parse::ObjectStream;
+use crate::symbol::SchematicKind;
+use crate::token::{LosslessView, Property, Schematic, SchematicHeader, Version};
 
+pub mod color;
+pub mod diff;
 pub mod error;
+mod flatten;
+mod geometry;
+pub mod json;
+mod net;
+pub mod netlist;
+pub mod options;
+#[cfg(feature = "rayon")]
+mod parallel;
 pub mod parse;
+mod sort;
+#[cfg(feature = "std")]
+pub mod svg;
+pub mod symbol;
 pub mod token;
+pub mod validate;
+pub mod visit;
+pub mod write;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod test;
 
+/// Hash map of the attributes of a [`Property`](token::Property).
+///
+/// This is the map of `hashbrown` with or without the `std` feature, so the
+/// public type does not change with the enabled features.
+pub use hashbrown::HashMap;
+pub(crate) use hashbrown::HashSet;
+
 /// String reference with location.
 pub type Span<'a, X = ()> = LocatedSpan<&'a str, X>;
 /// String reference with location in file.
+#[cfg(feature = "std")]
 pub type FileSpan<'a, 'b> = Span<'a, &'b Path>;
 /// Bytes reference with location.
 pub type ByteSpan<'a, X = ()> = LocatedSpan<&'a [u8], X>;
 /// Bytes reference with location in file.
+#[cfg(feature = "std")]
 pub type ByteFileSpan<'a, 'b> = ByteSpan<'a, &'b Path>;
 
+/// Parse a [`Schematic`] from a span with [`ParseOptions`].
+///
+/// The input can be any of the span types, such as [`Span`] or [`ByteSpan`].
+///
+/// ```
+/// use xschem_parser::Span;
+/// use xschem_parser::options::ParseOptions;
+///
+/// let input = Span::new("v {xschem version=3.4.5 file_version=1.2}");
+/// let options = ParseOptions::new().max_file_version(Some((1, 1)));
+///
+/// assert!(xschem_parser::parse_with_options(input, &ParseOptions::new()).is_ok());
+/// assert!(xschem_parser::parse_with_options(input, &options).is_err());
+/// ```
+pub fn parse_with_options<I>(input: I, options: &ParseOptions) -> Result<Schematic<I>, Error<I>>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str>,
+    <I as Input>::Item: AsChar,
+{
+    parse::schematic_full_with_options(input, options)
+}
+
 /// Parse a [`Schematic`] from a [`str`].
 pub fn from_str(s: &str) -> Result<Schematic<Span<'_>>, Error<Span<'_>>> {
-    Schematic::parse_str(s)
+    parse_with_options(Span::new(s), &ParseOptions::default())
+}
+
+/// Parse a [`Schematic`] from a [`str`] without tracking locations.
+///
+/// Objects and errors reference plain substrings of the input, which is
+/// faster than [`from_str`] when lines and columns are not needed. Errors
+/// still carry the failing substring, but can only be formatted without a
+/// line of context.
+///
+/// ```
+/// let input = "v {xschem version=3.4.5 file_version=1.2}\nN 0 0 10 0 {lab=A}\n";
+/// let schematic = xschem_parser::from_str_plain(input).unwrap();
+///
+/// assert_eq!(schematic.wires[0].property.get("lab"), Some(&"A"));
+/// ```
+pub fn from_str_plain(s: &str) -> Result<Schematic<&str>, Error<&str>> {
+    parse_with_options(s, &ParseOptions::default())
+}
+
+/// Parse a [`Schematic`] from a [`str`], rejecting file versions newer than
+/// [`options::SUPPORTED_FILE_VERSION`].
+pub fn from_str_strict(s: &str) -> Result<Schematic<Span<'_>>, Error<Span<'_>>> {
+    parse_with_options(Span::new(s), &ParseOptions::strict())
+}
+
+/// Parse a symbol from a [`str`].
+///
+/// Fails with [`error::ErrorKind::UnexpectedKind`] if the input is not a
+/// symbol according to [`Schematic::kind`], pointing at the first component
+/// or wire.
+pub fn from_str_symbol(s: &str) -> Result<Schematic<Span<'_>>, Error<Span<'_>>> {
+    from_str_kind(s, SchematicKind::Symbol)
+}
+
+/// Parse a schematic from a [`str`].
+///
+/// Fails with [`error::ErrorKind::UnexpectedKind`] if the input is a symbol
+/// according to [`Schematic::kind`], pointing at the first pin or the symbol
+/// `type` attribute.
+pub fn from_str_schematic(s: &str) -> Result<Schematic<Span<'_>>, Error<Span<'_>>> {
+    from_str_kind(s, SchematicKind::Schematic)
+}
+
+fn from_str_kind(s: &str, expected: SchematicKind) -> Result<Schematic<Span<'_>>, Error<Span<'_>>> {
+    let schematic = from_str(s)?;
+    let (found, input) = schematic.classify();
+    if found == expected {
+        return Ok(schematic);
+    }
+
+    let input = Span::new(s).take_from(input.map_or(0, Span::location_offset));
+    Err(Error::from_external_error(
+        input,
+        NomErrorKind::Verify,
+        error::ErrorKind::UnexpectedKind { expected, found },
+    ))
+}
+
+/// Parse a [`Schematic`] from a [`str`], collecting all errors instead of
+/// failing on the first.
+///
+/// See [`parse::schematic_full_all_errors`] for how parsing recovers from
+/// malformed objects.
+///
+/// ```
+/// let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+///              N 0 0 x 0 {}\n\
+///              N 0 0 10 0 {}\n\
+///              L 4 0 0 {}\n";
+///
+/// let errors = xschem_parser::from_str_all_errors(input).unwrap_err();
+///
+/// assert_eq!(errors.len(), 2);
+/// ```
+pub fn from_str_all_errors(s: &str) -> Result<Schematic<Span<'_>>, Vec<Error<Span<'_>>>> {
+    parse::schematic_full_all_errors(Span::new(s), &ParseOptions::default())
+}
+
+/// Parse the version and global properties of a schematic from a [`str`].
+///
+/// Parsing stops before the first other object, which makes it cheap to
+/// index a library of symbols by their global properties. See
+/// [`parse::schematic_header`].
+///
+/// ```
+/// let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+///              K {type=regulator}\n\
+///              L 4 not a line {}\n";
+///
+/// let header = xschem_parser::parse_header(input).unwrap();
+///
+/// assert_eq!(header.symbol_property.unwrap().get_value("type"), Some("regulator"));
+/// ```
+pub fn parse_header(s: &str) -> Result<SchematicHeader<Span<'_>>, Error<Span<'_>>> {
+    parse::schematic_header(&ParseOptions::default())
+        .parse(Span::new(s))
+        .finish()
+        .map(|(_, header)| header)
+}
+
+/// Parse a read-only [`LosslessView`] from a [`str`], keeping the exact input
+/// text of each object and the whitespace between objects.
+///
+/// This allows minimal-diff editing: objects that are not changed can be
+/// written as in the input, while changed objects are displayed by the
+/// caller. See [`parse::schematic_full_lossless`].
+///
+/// ```
+/// let input = "v {xschem version=3.4.5 file_version=1.2}\r\n\
+///              N 0 0  10.0 0 {lab=A}\n\n";
+///
+/// let lossless = xschem_parser::from_str_lossless(input).unwrap();
+///
+/// assert_eq!(lossless.schematic().wires.len(), 1);
+/// assert_eq!(*lossless.objects()[1].1.fragment(), "N 0 0  10.0 0 {lab=A}");
+/// assert_eq!(lossless.to_string(), input);
+/// ```
+pub fn from_str_lossless(s: &str) -> Result<LosslessView<Span<'_>>, Error<Span<'_>>> {
+    parse::schematic_full_lossless(Span::new(s), &ParseOptions::default())
+}
+
+/// Parse a [`Schematic`] from a [`str`], returning the objects parsed before
+/// the first error along with the error.
+///
+/// The error is the same as the error returned by [`from_str`]. If the
+/// version object is malformed, the schematic has no objects and an empty
+/// version property at the start of the input.
+///
+/// ```
+/// let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+///              N 0 0 10 0 {lab=A}\n\
+///              N 0 0 x 0 {}\n";
+///
+/// let (schematic, error) = xschem_parser::parse_partial(input);
+///
+/// assert_eq!(schematic.wires.len(), 1);
+/// assert_eq!(error.unwrap().err.input.location_line(), 3);
+/// ```
+#[must_use]
+pub fn parse_partial(s: &str) -> (Schematic<Span<'_>>, Option<Error<Span<'_>>>) {
+    let objects = match parse_objects(s) {
+        Ok(objects) => objects,
+        Err(e) => {
+            let version = Version(Property {
+                prop: Span::new(s).take(0),
+                attrs: HashMap::new(),
+            });
+            return (Schematic::new(version), Some(e));
+        }
+    };
+
+    let mut schematic = Schematic::new(objects.version().clone());
+    for object in objects {
+        match object {
+            Ok(object) => schematic.add_object_mut(object),
+            Err(e) => return (schematic, Some(e)),
+        }
+    }
+    (schematic, None)
+}
+
+/// Parse the objects of a schematic from a [`str`] one at a time.
+///
+/// The version object is parsed immediately, the remaining objects are parsed
+/// while iterating. This avoids collecting all objects of large schematics.
+///
+/// ```
+/// let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+///              N 0 0 10 0 {lab=A}\n\
+///              N 10 0 10 10 {lab=A}\n";
+///
+/// let objects = xschem_parser::parse_objects(input).unwrap();
+///
+/// assert_eq!(objects.filter_map(Result::ok).count(), 2);
+/// ```
+pub fn parse_objects(s: &str) -> Result<ObjectStream<Span<'_>, Error<Span<'_>>>, Error<Span<'_>>> {
+    ObjectStream::new(Span::new(s), ParseOptions::default())
+}
+
+/// Parse a [`Schematic`] from a [`str`], parsing top-level objects in
+/// parallel.
+///
+/// The input is split into top-level objects, keeping multi-line properties
+/// and embedded symbols together, which are parsed on the [`rayon`] thread
+/// pool. The result, including any error, is the same as [`from_str`].
+/// Parallel parsing only pays off for large schematics.
+///
+/// Requires the `rayon` feature.
+#[cfg(feature = "rayon")]
+pub fn parse_parallel(s: &str) -> Result<Schematic<Span<'_>>, Error<Span<'_>>> {
+    parallel::schematic(Span::new(s), &ParseOptions::default())
 }
 
 /// Parse a [`Schematic`] from a byte slice.
+///
+/// Any input, including invalid UTF-8, results in a schematic or an error
+/// without panicking. This is checked by the fuzz target in `fuzz/`.
 pub fn from_slice(s: &[u8]) -> Result<Schematic<ByteSpan<'_>>, Error<ByteSpan<'_>>> {
-    Schematic::parse_slice(s)
+    parse_with_options(ByteSpan::new(s), &ParseOptions::default())
+}
+
+/// Parse a [`Schematic`] from borrowed or owned input.
+///
+/// Borrowed input is parsed without copying (see [`Schematic::into_cow`]),
+/// while owned input does not outlive the call, so the parsed strings are
+/// copied into owned strings.
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// let input = "v {xschem version=3.4.5 file_version=1.2}\nN 0 0 10 0 {lab=A}\n";
+///
+/// let borrowed = xschem_parser::from_cow(Cow::Borrowed(input)).unwrap();
+/// assert!(matches!(borrowed.wires[0].property.prop, Cow::Borrowed("lab=A")));
+///
+/// let owned = xschem_parser::from_cow(Cow::Owned(input.to_string())).unwrap();
+/// assert!(matches!(owned.wires[0].property.prop, Cow::Owned(_)));
+/// ```
+pub fn from_cow(s: Cow<'_, str>) -> Result<Schematic<Cow<'_, str>>, Error<Location>> {
+    match s {
+        Cow::Borrowed(s) => from_str(s)
+            .map(Schematic::into_cow)
+            .map_err(|e| e.map_input(Location::from)),
+        Cow::Owned(s) => from_str(&s)
+            .map(|schematic| schematic.map(|i| Cow::Owned((*i.fragment()).to_owned())))
+            .map_err(|e| e.map_input(Location::from)),
+    }
 }
 
 /// Parse a [`Schematic`] from a [`str`] with [`Path`] info.
+#[cfg(feature = "std")]
 pub fn from_str_file<'a, 'b>(
     s: &'a str,
     path: &'b Path,
 ) -> Result<Schematic<FileSpan<'a, 'b>>, Error<FileSpan<'a, 'b>>> {
-    Schematic::parse_str_with_extra(s, path)
+    parse_with_options(Span::new_extra(s, path), &ParseOptions::default())
 }
 
 /// Parse a [`Schematic`] from a byte slice with [`Path`] info.
+#[cfg(feature = "std")]
 pub fn from_slice_file<'a, 'b>(
     s: &'a [u8],
     path: &'b Path,
 ) -> Result<Schematic<ByteFileSpan<'a, 'b>>, Error<ByteFileSpan<'a, 'b>>> {
-    Schematic::parse_slice_with_extra(s, path)
+    parse_with_options(ByteSpan::new_extra(s, path), &ParseOptions::default())
+}
+
+/// Parse a [`Schematic`] from a reader into an owned schematic.
+///
+/// The whole stream is read into memory before parsing.
+#[cfg(feature = "std")]
+pub fn from_reader<R: Read>(mut reader: R) -> Result<Schematic<String>, ReadError> {
+    let mut s = String::new();
+    reader.read_to_string(&mut s)?;
+    from_str(&s)
+        .map(|schematic| schematic.map(|i| i.fragment().to_string()))
+        .map_err(|e| ReadError::Parse(e.map_input(Location::from)))
+}
+
+/// Parse a [`Schematic`] from a file into an owned schematic.
+///
+/// Parse errors refer to the given path.
+#[cfg(feature = "std")]
+pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Schematic<String>, ReadError<PathBuf>> {
+    let path = path.as_ref();
+    let s = std::fs::read_to_string(path)?;
+    from_str_file(&s, path)
+        .map(|schematic| schematic.map(|i| i.fragment().to_string()))
+        .map_err(|e| {
+            ReadError::Parse(e.map_input(|i| Location::from(i).map_extra(Path::to_path_buf)))
+        })
 }
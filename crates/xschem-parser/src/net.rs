@@ -0,0 +1,140 @@
+//! Net connectivity helpers.
+use alloc::vec::Vec;
+
+use crate::token::{Schematic, Vec2, Wire};
+use crate::{HashMap, HashSet};
+
+/// Disjoint set of indices.
+struct UnionFind(Vec<usize>);
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        Self((0..len).collect())
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.0[i] != i {
+            self.0[i] = self.0[self.0[i]];
+            i = self.0[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        // Keep the lowest index as root so groups are ordered by first member.
+        if a < b {
+            self.0[b] = a;
+        } else {
+            self.0[a] = b;
+        }
+    }
+}
+
+impl<I> Schematic<I> {
+    /// Returns an iterator over the start and end coordinate of all wires.
+    pub fn wire_segments(&self) -> impl Iterator<Item = (Vec2, Vec2)> {
+        self.wires.iter().map(|w| (w.start, w.end))
+    }
+
+    /// Returns groups of wires that are connected through shared endpoints.
+    ///
+    /// Wires are connected if an endpoint of one wire exactly equals an
+    /// endpoint of the other. Endpoints that touch the middle of another wire
+    /// do not connect. Groups are ordered by their first wire and wires within
+    /// a group are in input order.
+    pub fn connected_components(&self) -> Vec<Vec<&Wire<I>>> {
+        let mut sets = UnionFind::new(self.wires.len());
+        let mut endpoints = HashMap::new();
+        for (i, (start, end)) in self.wire_segments().enumerate() {
+            for p in [start, end] {
+                let first = *endpoints.entry(p).or_insert(i);
+                sets.union(first, i);
+            }
+        }
+
+        let mut groups: Vec<Vec<&Wire<I>>> = Vec::new();
+        let mut group_of_root = HashMap::new();
+        for (i, wire) in self.wires.iter().enumerate() {
+            let root = sets.find(i);
+            let group = *group_of_root.entry(root).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(wire);
+        }
+
+        groups
+    }
+}
+
+impl<I: AsRef<str>> Schematic<I> {
+    /// Returns the `lab` attribute of all labeled wires and components with
+    /// the coordinate of the label.
+    ///
+    /// Wires are labeled at their start coordinate and label components,
+    /// such as `lab_pin.sym`, at their position. Labels are as in the input,
+    /// including surrounding quotes. Wires are returned before components,
+    /// each in input order.
+    ///
+    /// ```
+    /// let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+    ///              N 0 0 10 0 {lab=IN}\n\
+    ///              C {lab_pin.sym} 10 0 0 0 {name=p1 lab=IN}\n";
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    ///
+    /// let labels: Vec<_> = schematic
+    ///     .net_labels()
+    ///     .map(|(label, p)| (*label.fragment(), *p.x, *p.y))
+    ///     .collect();
+    /// assert_eq!(labels, [("IN", 0.0, 0.0), ("IN", 10.0, 0.0)]);
+    /// ```
+    pub fn net_labels(&self) -> impl Iterator<Item = (&I, Vec2)> {
+        let wires = self
+            .wires
+            .iter()
+            .filter_map(|w| Some((w.property.get("lab")?, w.start)));
+        let components = self
+            .components
+            .iter()
+            .filter_map(|c| Some((c.property.get("lab")?, c.position)));
+        wires.chain(components)
+    }
+
+    /// Returns the net label of the wires touching a coordinate.
+    ///
+    /// The wire group (see [`Schematic::connected_components`]) with an
+    /// endpoint at `point` is searched for a label (see
+    /// [`Schematic::net_labels`]). A label component at `point` labels the net
+    /// even if no wire touches it. Use [`Component::transform`] to find the
+    /// schematic coordinate of a component pin. Returns [`None`] if the net is
+    /// unlabeled.
+    ///
+    /// [`Component::transform`]: crate::token::Component::transform
+    ///
+    /// ```
+    /// let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+    ///              N 0 0 10 0 {lab=IN}\n\
+    ///              N 10 0 10 20 {}\n";
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    ///
+    /// let net = schematic.net_at((10.0, 20.0).try_into().unwrap());
+    /// assert_eq!(net.map(|l| *l.fragment()), Some("IN"));
+    /// assert_eq!(schematic.net_at((5.0, 0.0).try_into().unwrap()), None);
+    /// ```
+    pub fn net_at(&self, point: Vec2) -> Option<&I> {
+        let mut points = HashSet::new();
+        points.insert(point);
+        if let Some(group) = self
+            .connected_components()
+            .into_iter()
+            .find(|g| g.iter().any(|w| w.start == point || w.end == point))
+        {
+            points.extend(group.iter().flat_map(|w| [w.start, w.end]));
+        }
+
+        self.net_labels()
+            .find(|(_, p)| points.contains(p))
+            .map(|(label, _)| label)
+    }
+}
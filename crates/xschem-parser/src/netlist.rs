@@ -0,0 +1,164 @@
+//! SPICE netlist extraction.
+//!
+//! This is a partial netlister: it resolves the nets connected to the pins of
+//! each component, but does not expand `format` templates. Nets are formed by
+//! wires sharing endpoints (see [`Schematic::connected_components`]) and pins
+//! at the same coordinate. Pins touching the middle of a wire are not
+//! connected.
+use alloc::borrow::ToOwned;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::HashMap;
+use crate::token::{Component, Schematic, Vec2};
+
+/// Netlist line of a component instance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetlistLine<I> {
+    /// Instance name from the `name` attribute, as in the input.
+    pub name: Option<I>,
+    /// Symbol reference of the component.
+    pub reference: I,
+    /// Net names connected to the symbol pins, in symbol pin order.
+    pub nets: Vec<String>,
+    /// `format` attribute of the symbol, without quotes.
+    pub format: Option<String>,
+    /// `template` attribute of the symbol, without quotes.
+    pub template: Option<String>,
+}
+
+/// Nets indexed by the coordinates of the connected wire endpoints and pins.
+#[derive(Default)]
+struct Nets {
+    names: Vec<Option<String>>,
+    by_point: HashMap<Vec2, usize>,
+    unnamed: usize,
+}
+
+impl Nets {
+    /// Returns the net at a coordinate, adding an unnamed net if there is none.
+    fn at(&mut self, point: Vec2) -> usize {
+        *self.by_point.entry(point).or_insert_with(|| {
+            self.names.push(None);
+            self.names.len() - 1
+        })
+    }
+
+    /// Names a net unless it already has a name.
+    fn label(&mut self, net: usize, name: &str) {
+        self.names[net].get_or_insert_with(|| name.to_owned());
+    }
+
+    /// Returns the name of a net, numbering unnamed nets in order of use.
+    fn name(&mut self, net: usize) -> String {
+        let unnamed = &mut self.unnamed;
+        self.names[net]
+            .get_or_insert_with(|| {
+                *unnamed += 1;
+                format!("#net{unnamed}")
+            })
+            .clone()
+    }
+}
+
+impl<I: AsRef<str> + Clone> Schematic<I> {
+    /// Returns a SPICE netlist line for each component.
+    ///
+    /// The `symbol` hook returns the symbol of a component, for example the
+    /// embedded symbol or a symbol file parsed by the caller. The pins of the
+    /// symbol (see [`Schematic::pins`]) are mapped into schematic coordinates
+    /// to resolve the connected nets, and the `format` and `template`
+    /// attributes are taken from its global symbol property.
+    ///
+    /// Nets are named by the `lab` attribute of one of their wires, or of a
+    /// label component with a `lab` attribute connected to them. Label
+    /// components do not produce a netlist line. Other nets are named `#net1`,
+    /// `#net2` and so on, in order of first use. Components for which the hook
+    /// returns `None` have no nets.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+    ///              N 0 -30 0 -60 {lab=IN}\n\
+    ///              C {res.sym} 0 0 0 0 {name=R1}\n";
+    /// let resistor = "v {xschem version=3.4.5 file_version=1.2}\n\
+    ///                 K {format=\"@name @pinlist @value\"}\n\
+    ///                 B 5 -2.5 -32.5 2.5 -27.5 {name=P dir=inout}\n\
+    ///                 B 5 -2.5 27.5 2.5 32.5 {name=M dir=inout}\n";
+    ///
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    /// let resistor = xschem_parser::from_str(resistor).unwrap();
+    ///
+    /// let netlist = schematic.to_spice_netlist(|_| Some(&resistor));
+    /// assert_eq!(netlist[0].nets, ["IN", "#net1"]);
+    /// assert_eq!(netlist[0].format.as_deref(), Some("@name @pinlist @value"));
+    /// ```
+    pub fn to_spice_netlist<'a, J>(
+        &'a self,
+        mut symbol: impl FnMut(&'a Component<I>) -> Option<&'a Schematic<J>>,
+    ) -> Vec<NetlistLine<I>>
+    where
+        J: AsRef<str> + Clone + 'a,
+    {
+        let mut nets = Nets::default();
+        for group in self.connected_components() {
+            let net = nets.names.len();
+            nets.names.push(
+                group
+                    .iter()
+                    .find_map(|w| w.property.get_value("lab"))
+                    .map(str::to_owned),
+            );
+            for wire in group {
+                for p in [wire.start, wire.end] {
+                    nets.by_point.insert(p, net);
+                }
+            }
+        }
+
+        let components: Vec<_> = self
+            .components
+            .iter()
+            .map(|c| {
+                let symbol = symbol(c);
+                let pins: Vec<_> = symbol
+                    .map(Schematic::pins)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|p| nets.at(c.transform(p.position)))
+                    .collect();
+                (c, symbol, pins)
+            })
+            .collect();
+
+        for (c, _, pins) in &components {
+            if let Some(label) = c.property.get_value("lab") {
+                for &net in pins {
+                    nets.label(net, label);
+                }
+            }
+        }
+
+        components
+            .into_iter()
+            .filter(|(c, _, _)| c.property.get("lab").is_none())
+            .map(|(c, symbol, pins)| {
+                let attribute = |key| {
+                    symbol
+                        .and_then(|s| s.symbol_property.as_ref())
+                        .and_then(|p| p.get_value(key))
+                        .map(str::to_owned)
+                };
+                NetlistLine {
+                    name: c.property.get("name").cloned(),
+                    reference: c.reference.clone(),
+                    nets: pins.into_iter().map(|net| nets.name(net)).collect(),
+                    format: attribute("format"),
+                    template: attribute("template"),
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,219 @@
+//! Parser options.
+use core::ops::{BitOr, BitOrAssign};
+
+use crate::token::{Object, ObjectKind};
+
+/// Latest Xschem file version supported by this library.
+pub const SUPPORTED_FILE_VERSION: (u32, u32) = (1, 2);
+/// Default maximum nesting depth of embedded symbols.
+///
+/// Each level of nesting takes a considerable amount of stack space, especially
+/// in debug builds. This default is safe for threads with a 2 MiB stack.
+pub const DEFAULT_MAX_EMBEDDING_DEPTH: usize = 8;
+
+/// Options to configure the parser.
+///
+/// Options are built by chaining setters on the default options:
+///
+/// ```
+/// use xschem_parser::options::ParseOptions;
+///
+/// let options = ParseOptions::new().max_file_version(Some((1, 1)));
+///
+/// assert_eq!(options.max_file_version, Some((1, 1)));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Maximum accepted `file_version` as major and minor.
+    ///
+    /// Parsing fails if the version object declares a newer file version.
+    /// Files without a (valid) `file_version` attribute are always accepted.
+    pub max_file_version: Option<(u32, u32)>,
+    /// Maximum nesting depth of embedded symbols.
+    ///
+    /// Parsing fails if embedded symbols are nested deeper, instead of
+    /// overflowing the stack. Defaults to [`DEFAULT_MAX_EMBEDDING_DEPTH`].
+    /// Setting this to [`None`] removes the limit.
+    pub max_embedding_depth: Option<usize>,
+    /// Kinds of objects kept in the parsed schematic.
+    ///
+    /// Other objects are parsed, so they must still be valid, but dropped
+    /// right away, including those of embedded symbols. This reduces the
+    /// memory of parsing large files compared to filtering afterwards. The
+    /// version and global properties are always kept. Defaults to
+    /// [`ObjectMask::ALL`].
+    pub keep: ObjectMask,
+    /// Whether to accept objects with an unknown tag.
+    ///
+    /// A line starting with a letter that is not a known object tag, followed
+    /// by whitespace separated tokens and a property, such as `Z 1 2 {a=b}`,
+    /// is parsed into an [`UnknownObject`] instead of failing. This allows
+    /// reading files with objects of newer Xschem versions. Defaults to
+    /// `false`.
+    ///
+    /// [`UnknownObject`]: crate::token::UnknownObject
+    pub unknown_objects: bool,
+    /// Whether to accept `,` as decimal separator in numbers.
+    ///
+    /// Some tools write files with the decimal separator of their locale,
+    /// such as `L 4 0 1,5 2,5 0 {}`. With this option, numbers of objects
+    /// may use `,` or `.`, and the parsed values are the same. The input
+    /// spans are unchanged, so properties and texts are not affected.
+    /// Written numbers always use `.`, independent of the locale. Defaults
+    /// to `false`.
+    pub decimal_comma: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            max_file_version: None,
+            max_embedding_depth: Some(DEFAULT_MAX_EMBEDDING_DEPTH),
+            keep: ObjectMask::ALL,
+            unknown_objects: false,
+            decimal_comma: false,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Creates default options.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum accepted `file_version`.
+    #[must_use]
+    pub fn max_file_version(mut self, max_file_version: Option<(u32, u32)>) -> Self {
+        self.max_file_version = max_file_version;
+        self
+    }
+
+    /// Sets the maximum nesting depth of embedded symbols.
+    #[must_use]
+    pub fn max_embedding_depth(mut self, max_embedding_depth: Option<usize>) -> Self {
+        self.max_embedding_depth = max_embedding_depth;
+        self
+    }
+
+    /// Sets the kinds of objects kept in the parsed schematic.
+    #[must_use]
+    pub fn keep(mut self, keep: ObjectMask) -> Self {
+        self.keep = keep;
+        self
+    }
+
+    /// Sets whether to accept objects with an unknown tag.
+    #[must_use]
+    pub fn unknown_objects(mut self, unknown_objects: bool) -> Self {
+        self.unknown_objects = unknown_objects;
+        self
+    }
+
+    /// Sets whether to accept `,` as decimal separator in numbers.
+    #[must_use]
+    pub fn decimal_comma(mut self, decimal_comma: bool) -> Self {
+        self.decimal_comma = decimal_comma;
+        self
+    }
+
+    /// Options that reject files newer than [`SUPPORTED_FILE_VERSION`].
+    #[must_use]
+    pub fn strict() -> Self {
+        Self::new().max_file_version(Some(SUPPORTED_FILE_VERSION))
+    }
+}
+
+/// Set of [`ObjectKind`]s, combined with `|`.
+///
+/// ```
+/// use xschem_parser::options::{ObjectMask, ParseOptions};
+/// use xschem_parser::token::ObjectKind;
+///
+/// let options = ParseOptions::new().keep(ObjectMask::WIRE | ObjectMask::COMPONENT);
+/// let input = "v {}\nN 0 0 10 0 {lab=A}\nL 4 0 0 10 0 {}";
+/// let schematic = xschem_parser::parse_with_options(input, &options).unwrap();
+///
+/// assert!(options.keep.contains(ObjectKind::Wire));
+/// assert_eq!((schematic.wires.len(), schematic.lines.len()), (1, 0));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ObjectMask(u8);
+
+impl ObjectMask {
+    pub const ARC: Self = Self(1);
+    pub const COMPONENT: Self = Self(1 << 1);
+    pub const LINE: Self = Self(1 << 2);
+    pub const POLYGON: Self = Self(1 << 3);
+    pub const RECTANGLE: Self = Self(1 << 4);
+    pub const TEXT: Self = Self(1 << 5);
+    pub const WIRE: Self = Self(1 << 6);
+    pub const UNKNOWN: Self = Self(1 << 7);
+    /// All kinds of objects.
+    pub const ALL: Self = Self(u8::MAX);
+    /// No objects.
+    pub const NONE: Self = Self(0);
+
+    /// Returns whether the mask contains a kind of object.
+    #[must_use]
+    pub fn contains(self, kind: ObjectKind) -> bool {
+        self.0 & Self::from(kind).0 != 0
+    }
+
+    /// Returns whether an object is kept, where global properties are
+    /// always kept.
+    pub(crate) fn keeps<I>(self, object: &Object<I>) -> bool {
+        let kind = match object {
+            Object::SpiceProperty(_)
+            | Object::VerilogProperty(_)
+            | Object::VhdlProperty(_)
+            | Object::TedaXProperty(_)
+            | Object::SymbolProperty(_) => return true,
+            Object::Arc(_) => ObjectKind::Arc,
+            Object::Component(_) => ObjectKind::Component,
+            Object::Line(_) => ObjectKind::Line,
+            Object::Polygon(_) => ObjectKind::Polygon,
+            Object::Rectangle(_) => ObjectKind::Rectangle,
+            Object::Text(_) => ObjectKind::Text,
+            Object::Wire(_) => ObjectKind::Wire,
+            Object::Unknown(_) => ObjectKind::Unknown,
+        };
+        self.contains(kind)
+    }
+}
+
+impl Default for ObjectMask {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl From<ObjectKind> for ObjectMask {
+    fn from(kind: ObjectKind) -> Self {
+        match kind {
+            ObjectKind::Arc => Self::ARC,
+            ObjectKind::Component => Self::COMPONENT,
+            ObjectKind::Line => Self::LINE,
+            ObjectKind::Polygon => Self::POLYGON,
+            ObjectKind::Rectangle => Self::RECTANGLE,
+            ObjectKind::Text => Self::TEXT,
+            ObjectKind::Wire => Self::WIRE,
+            ObjectKind::Unknown => Self::UNKNOWN,
+        }
+    }
+}
+
+impl BitOr for ObjectMask {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for ObjectMask {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
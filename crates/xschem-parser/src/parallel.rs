@@ -0,0 +1,183 @@
+//! Parallel parsing of top-level objects.
+use nom::character::complete::{multispace0, multispace1};
+use nom::combinator::eof;
+use nom::error::{ErrorKind, ParseError};
+use nom::multi::fold_many0;
+use nom::sequence::{preceded, terminated};
+use nom::{Err, Finish, Input, Parser};
+use rayon::prelude::*;
+
+use crate::Span;
+use crate::error::Error;
+use crate::options::ParseOptions;
+use crate::parse::{allows_extra_tokens, any_object_at_depth, header, keep_object};
+use crate::token::{Schematic, Version};
+
+/// Returns whether a byte is whitespace separating objects.
+fn is_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\r' | b'\n')
+}
+
+/// Minimum size in bytes of a chunk of objects parsed by a single task.
+pub(crate) const CHUNK_SIZE: usize = 16 * 1024;
+
+/// Splits the objects following the version object into chunks of at least
+/// `chunk_size` bytes, except for the last chunk.
+///
+/// An object ends at a newline outside of braces and embedded symbols, unless
+/// the next line does not start with an object tag, such as a line starting an
+/// embedded symbol or continuing the fields of the object. Leading whitespace
+/// is skipped, and trailing whitespace is part of the preceding chunk.
+fn split_objects(body: Span<'_>, chunk_size: usize) -> Vec<Span<'_>> {
+    let mut starts = Vec::new();
+    let mut in_braces = false;
+    let mut escaped = false;
+    let mut brackets = 0usize;
+    let mut new_line = true;
+
+    for (i, &b) in body.fragment().as_bytes().iter().enumerate() {
+        if in_braces {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'}' {
+                in_braces = false;
+            }
+            continue;
+        }
+
+        if new_line && !is_space(b) {
+            new_line = false;
+            let last = starts.last().copied();
+            if last.is_none()
+                || (b.is_ascii_alphabetic() && last.is_some_and(|s| i - s >= chunk_size))
+            {
+                starts.push(i);
+            }
+        }
+
+        match b {
+            b'{' => in_braces = true,
+            b'[' => brackets += 1,
+            b']' => brackets = brackets.saturating_sub(1),
+            b'\n' if brackets == 0 => new_line = true,
+            _ => {}
+        }
+    }
+
+    let mut chunks = Vec::with_capacity(starts.len());
+    let Some(&first) = starts.first() else {
+        return chunks;
+    };
+    let mut rest = body.take_from(first);
+    for len in starts.windows(2).map(|w| w[1] - w[0]) {
+        let (next, chunk) = rest.take_split(len);
+        chunks.push(chunk);
+        rest = next;
+    }
+    chunks.push(rest);
+    chunks
+}
+
+/// Parses the whitespace separated objects spanning the whole input into a
+/// schematic with the given version.
+fn objects<'a>(
+    input: Span<'a>,
+    version: Version<Span<'a>>,
+    options: &ParseOptions,
+) -> Result<Schematic<Span<'a>>, Error<Span<'a>>> {
+    let extra_tokens = allows_extra_tokens(&version);
+    let (rest, first) = match any_object_at_depth(options, 0, extra_tokens).parse(input) {
+        Ok(result) => result,
+        Err(Err::Failure(e)) => return Err(e),
+        Err(Err::Error(_) | Err::Incomplete(_)) => {
+            return Err(Error::from_error_kind(input, ErrorKind::Eof));
+        }
+    };
+
+    terminated(
+        fold_many0(
+            preceded(multispace1, any_object_at_depth(options, 0, extra_tokens)),
+            move || keep_object(options)(Schematic::new(version.clone()), first.clone()),
+            keep_object(options),
+        ),
+        preceded(multispace0, eof),
+    )
+    .parse(rest)
+    .finish()
+    .map(|(_, schematic)| schematic)
+}
+
+/// Appends the global properties and objects of `other` to `schematic`.
+fn append<I>(mut schematic: Schematic<I>, other: Schematic<I>) -> Schematic<I> {
+    let Schematic {
+        version: _,
+        vhdl_property,
+        symbol_property,
+        verilog_property,
+        spice_property,
+        tedax_property,
+        texts,
+        lines,
+        rectangles,
+        polygons,
+        arcs,
+        wires,
+        components,
+        unknown_objects,
+        order,
+    } = other;
+
+    // Later global properties replace earlier ones, like `Schematic::add_object`.
+    schematic.vhdl_property = vhdl_property.or(schematic.vhdl_property);
+    schematic.symbol_property = symbol_property.or(schematic.symbol_property);
+    schematic.verilog_property = verilog_property.or(schematic.verilog_property);
+    schematic.spice_property = spice_property.or(schematic.spice_property);
+    schematic.tedax_property = tedax_property.or(schematic.tedax_property);
+    schematic.texts.extend(texts.0);
+    schematic.lines.extend(lines.0);
+    schematic.rectangles.extend(rectangles.0);
+    schematic.polygons.extend(polygons.0);
+    schematic.arcs.extend(arcs.0);
+    schematic.wires.extend(wires.0);
+    schematic.components.extend(components.0);
+    schematic.unknown_objects.extend(unknown_objects.0);
+    schematic.order.extend(order);
+    schematic
+}
+
+/// Parses a schematic to the end of the input, parsing top-level objects in
+/// parallel.
+///
+/// The result, including any error, is the same as parsing serially.
+pub(crate) fn schematic<'a>(
+    input: Span<'a>,
+    options: &ParseOptions,
+) -> Result<Schematic<Span<'a>>, Error<Span<'a>>> {
+    schematic_in_chunks(input, options, CHUNK_SIZE)
+}
+
+/// Parses a schematic like [`schematic`], with chunks of objects of at least
+/// `chunk_size` bytes.
+pub(crate) fn schematic_in_chunks<'a>(
+    input: Span<'a>,
+    options: &ParseOptions,
+    chunk_size: usize,
+) -> Result<Schematic<Span<'a>>, Error<Span<'a>>> {
+    let (body, version) = header::<_, Error<_>>(options).parse(input).finish()?;
+    if body.fragment().bytes().next().is_some_and(|b| !is_space(b)) {
+        return Err(Error::from_error_kind(body, ErrorKind::Eof));
+    }
+
+    split_objects(body, chunk_size)
+        .into_par_iter()
+        .map(|chunk| objects(chunk, version.clone(), options))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .try_fold(Schematic::new(version.clone()), |schematic, chunk| {
+            Ok(append(schematic, chunk?))
+        })
+        // Errors of a chunk only span up to the end of the chunk.
+        .map_err(|e: Error<Span<'a>>| e.map_input(|i| input.take_from(i.location_offset())))
+}
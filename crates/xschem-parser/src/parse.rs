@@ -1,24 +1,52 @@
 //! Parser combinator functions.
-use std::collections::HashMap;
-use std::hash::Hash;
+//!
+//! The public parsers are generic over the input `I` and error `E`, so they
+//! compose with other [`nom`] parsers. Input is typically a `&str`, or a
+//! [`Span`](crate::Span) to track locations, and errors are an
+//! [`Error`](crate::error::Error) or any error implementing [`ParseError`],
+//! [`ContextError`] and, for objects with validated fields,
+//! [`FromExternalError`] of [`error::ErrorKind`].
+//!
+//! The object parsers parse a single object without surrounding whitespace,
+//! such as a line copied from a schematic:
+//!
+//! ```
+//! use nom::error::ErrorKind;
+//! use xschem_parser::parse::wire_object;
+//!
+//! let (rest, wire) = wire_object::<_, (&str, ErrorKind)>("N 0 0 10 0 {lab=out}").unwrap();
+//!
+//! assert_eq!(rest, "");
+//! assert_eq!(wire.property.get_value("lab"), Some("out"));
+//! ```
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::hash::Hash;
+use core::marker::PhantomData;
 
 use nom::branch::alt;
 use nom::bytes::complete::{tag, take_while, take_while1};
 use nom::character::complete::{
-    char, multispace0, multispace1, none_of, one_of, space1, u64, usize,
+    char, digit0, digit1, multispace0, multispace1, one_of, satisfy, space1, u32 as nom_u32, u64,
+    usize,
 };
-use nom::combinator::{consumed, cut, eof, opt, value as nom_value};
-use nom::error::{ContextError, ErrorKind, ParseError, context};
-use nom::multi::{fold_many0, length_count};
-use nom::number::complete::recognize_float;
-use nom::sequence::{preceded, separated_pair, terminated};
-use nom::{AsChar, Compare, Err, Finish, IResult, Input, Offset, ParseTo, Parser};
+use nom::combinator::{cond, consumed, cut, eof, opt, recognize, value as nom_value};
+use nom::error::{ContextError, ErrorKind, FromExternalError, ParseError, context};
+use nom::multi::{fold_many0, many0};
+use nom::sequence::{delimited, preceded, separated_pair, terminated};
+use nom::{AsChar, Compare, CompareResult, Err, Finish, IResult, Input, Offset, ParseTo, Parser};
 
+use crate::options::{ParseOptions, SUPPORTED_FILE_VERSION};
 use crate::token::{
-    Arc, Component, Coordinate, Embedding, FiniteDouble, Flip, Line, Object, Polygon, Property,
-    Rectangle, Rotation, Schematic, Size, SpiceProperty, SymbolProperty, TedaXProperty, Text, Vec2,
-    VerilogProperty, Version, VhdlProperty, Wire,
+    Arc, Component, Coordinate, Embedding, Field, FiniteDouble, Flip, Line, LosslessView, Object,
+    Polygon, Property, Rectangle, Rotation, Schematic, SchematicHeader, Size, Spanned,
+    SpiceProperty, SymbolProperty, TedaXProperty, Text, UnknownObject, Vec2, VerilogProperty,
+    Version, VhdlProperty, Wire,
 };
+use crate::{HashMap, error};
 
 /// Reserved escapable characters in property strings.
 pub const ESCAPED_CHARS: &str = r"\{}";
@@ -27,6 +55,79 @@ pub const ESCAPED_VALUE_CHARS: &str = r#"\""#;
 /// Escape character in property strings.
 pub const ESCAPE_CHAR: char = '\\';
 
+/// Returns `input` with each escape sequence of a character in `escapable`
+/// replaced by the character itself, borrowing the input if it has none.
+///
+/// Use [`ESCAPED_CHARS`] for property strings and texts. Attribute values
+/// are part of a property string, so a quoted value is unescaped with
+/// [`ESCAPED_CHARS`] first and then with [`ESCAPED_VALUE_CHARS`]. Escape
+/// characters not followed by an escapable character are kept.
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// use xschem_parser::parse::{ESCAPED_CHARS, ESCAPED_VALUE_CHARS, unescape};
+///
+/// assert_eq!(unescape(r"format=\{x\} \n", ESCAPED_CHARS), r"format={x} \n");
+/// assert_eq!(unescape(r#"say \"hi\""#, ESCAPED_VALUE_CHARS), r#"say "hi""#);
+/// assert!(matches!(unescape("plain", ESCAPED_CHARS), Cow::Borrowed("plain")));
+/// ```
+#[must_use]
+pub fn unescape<'a>(input: &'a str, escapable: &str) -> Cow<'a, str> {
+    let first = input.char_indices().find(|&(i, c)| {
+        c == ESCAPE_CHAR
+            && input[i + c.len_utf8()..]
+                .chars()
+                .next()
+                .is_some_and(|next| escapable.contains(next))
+    });
+    match first {
+        Some((i, _)) => {
+            let mut out = String::with_capacity(input.len());
+            out.push_str(&input[..i]);
+            unescape_into(&input[i..], escapable, &mut out);
+            Cow::Owned(out)
+        }
+        None => Cow::Borrowed(input),
+    }
+}
+
+/// Appends `input` to `out`, replacing each escape sequence of a character in
+/// `escapable` by the character itself, see [`unescape`].
+///
+/// Escape characters not followed by an escapable character are kept.
+pub fn unescape_into(input: &str, escapable: &str, out: &mut String) {
+    let mut chars = input.chars();
+    while let Some(c) = chars.next() {
+        if c == ESCAPE_CHAR {
+            match chars.clone().next() {
+                Some(next) if escapable.contains(next) => {
+                    out.push(next);
+                    chars.next();
+                }
+                _ => out.push(c),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+}
+
+/// Recognizes one or more characters that are not in `list`.
+///
+/// Unlike [`none_of`](nom::character::complete::none_of), this never splits the
+/// input inside a character, which panics for a byte input with a byte that is
+/// not ASCII, since such a byte is treated as a character of two bytes in
+/// UTF-8.
+fn none_of_chars<I, E>(list: &'static str) -> impl Parser<I, Output = I, Error = E>
+where
+    I: Input,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I>,
+{
+    take_while1(move |c: <I as Input>::Item| !list.contains(c.as_char()))
+}
+
 pub(crate) fn escaped0<'a, I, Error, F, G>(
     mut normal: F,
     control_char: char,
@@ -119,8 +220,15 @@ where
     }
 }
 
-fn is_key_char<C: AsChar>(c: C) -> bool {
-    c.is_alphanum() || c.as_char() == '_'
+/// Returns whether a character is part of an attribute key.
+///
+/// Like Xschem, a key is terminated by `=`, ASCII whitespace or control
+/// characters, so keys such as `sim.model` or `spice-ignore` are accepted.
+/// Quotes, braces and backslashes are excluded as well, since they delimit
+/// or escape values.
+pub(crate) fn is_key_char<C: AsChar>(c: C) -> bool {
+    let c = c.as_char();
+    !c.is_ascii_whitespace() && !c.is_ascii_control() && !matches!(c, '=' | '"' | '{' | '}' | '\\')
 }
 
 pub(crate) fn key<'a, I, E>(input: I) -> IResult<I, I, E>
@@ -132,8 +240,13 @@ where
     context("key", take_while1(is_key_char)).parse(input)
 }
 
+/// Returns whether a character is part of an unquoted attribute value.
+///
+/// Like Xschem, an unquoted value is terminated by ASCII whitespace or
+/// control characters only, so non-ASCII characters are part of the value.
 fn is_value_char<C: AsChar>(c: C) -> bool {
-    c.is_alphanum() || c.as_char().is_ascii_punctuation()
+    let c = c.as_char();
+    !c.is_ascii_whitespace() && !c.is_ascii_control()
 }
 
 pub(crate) fn value<'a, I, E>(input: I) -> IResult<I, I, E>
@@ -145,17 +258,17 @@ where
     context(
         "value",
         alt((
-            preceded(
+            recognize(preceded(
                 char('"'),
                 cut(terminated(
                     escaped0(
-                        none_of(ESCAPED_VALUE_CHARS),
+                        none_of_chars(ESCAPED_VALUE_CHARS),
                         ESCAPE_CHAR,
                         alt((tag(r#"\""#), tag(r"\"), tag(r"{"), tag(r"}"))),
                     ),
                     char('"'),
                 )),
-            ),
+            )),
             take_while1(is_value_char),
         )),
     )
@@ -193,6 +306,82 @@ where
     Ok((input, attrs))
 }
 
+/// Returns `input` without leading and trailing whitespace.
+pub(crate) fn trim<I>(input: &I) -> I
+where
+    I: Input,
+    <I as Input>::Item: AsChar,
+{
+    let mut range = None;
+    for (i, c) in input.iter_indices() {
+        if !c.as_char().is_ascii_whitespace() {
+            let end = i + c.len();
+            range = Some(range.map_or((i, end), |(start, _)| (start, end)));
+        }
+    }
+    let (start, end) = range.unwrap_or((0, 0));
+    input.take_from(start).take(end - start)
+}
+
+/// Returns the text of a property string outside of its attributes, split at
+/// each attribute and without surrounding whitespace.
+///
+/// This is the input skipped by [`attributes`], such as the `xschem` keyword
+/// and comments of a version property.
+pub(crate) fn free_text<I>(prop: &I) -> Vec<I>
+where
+    I: Offset + Input + for<'s> Compare<&'s str>,
+    <I as Input>::Item: AsChar,
+{
+    let mut texts = Vec::new();
+    let mut start = prop.clone();
+    let mut input = prop.clone();
+
+    while input.input_len() > 0 {
+        let rest = input.take_from(input.position(is_key_char).unwrap_or(input.input_len()));
+        input = match key_value::<I, (I, ErrorKind)>(rest.clone()) {
+            Ok((after, _)) => {
+                texts.push(trim(&start.take(start.offset(&rest))));
+                start = after.clone();
+                after
+            }
+            Err(Err::Error((rest, _))) => rest,
+            Err(_) => break,
+        };
+    }
+    texts.push(trim(&start));
+
+    texts.retain(|t| t.input_len() > 0);
+    texts
+}
+
+/// Returns the key and value of each attribute of a property string, in the
+/// order they appear in the input.
+///
+/// Attributes are found like by [`attributes`], but repeated keys are kept.
+pub(crate) fn attribute_spans<I>(prop: &I) -> Vec<(I, I)>
+where
+    I: Offset + Input + for<'s> Compare<&'s str>,
+    <I as Input>::Item: AsChar,
+{
+    let mut spans = Vec::new();
+    let mut input = prop.clone();
+
+    while input.input_len() > 0 {
+        let rest = input.take_from(input.position(is_key_char).unwrap_or(input.input_len()));
+        input = match key_value::<I, (I, ErrorKind)>(rest) {
+            Ok((after, key_value)) => {
+                spans.push(key_value);
+                after
+            }
+            Err(Err::Error((rest, _))) => rest,
+            Err(_) => break,
+        };
+    }
+
+    spans
+}
+
 pub(crate) fn brace_enclosed<'a, I, O, P, E>(parser: P) -> impl Parser<I, Output = O, Error = E>
 where
     I: Input + 'a,
@@ -209,7 +398,12 @@ where
     <I as Input>::Item: AsChar,
     E: ParseError<I> + ContextError<I>,
 {
-    escaped0(none_of(ESCAPED_CHARS), ESCAPE_CHAR, one_of(ESCAPED_CHARS)).parse(input)
+    escaped0(
+        none_of_chars(ESCAPED_CHARS),
+        ESCAPE_CHAR,
+        one_of(ESCAPED_CHARS),
+    )
+    .parse(input)
 }
 
 pub(crate) fn property<'a, I, E>(input: I) -> IResult<I, Property<I>, E>
@@ -256,13 +450,66 @@ where
     context(name, preceded(char(tag), cut(parser)))
 }
 
+/// Parses a layer index.
+///
+/// A layer index is a non-negative decimal integer without a sign that fits
+/// in a `u64`, like `4`. Anything else up to the next whitespace, like `+3`,
+/// `-1` or `3.0`, is rejected with [`error::ErrorKind::Layer`]. The index is
+/// not checked against the number of layers, since the layer palette of
+/// Xschem is configurable.
 pub(crate) fn layer<'a, I, E>(input: I) -> IResult<I, u64, E>
 where
     I: Input + 'a,
     <I as Input>::Item: AsChar,
+    E: ParseError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    let token = take_while1::<_, I, ()>(|c: <I as Input>::Item| {
+        let c = c.as_char();
+        !c.is_ascii_whitespace() && c != '{'
+    });
+    let layer = token.and_then(terminated(u64, eof)).parse(input.clone());
+
+    layer.map_err(|_| {
+        Err::Error(E::from_external_error(
+            input,
+            ErrorKind::Digit,
+            error::ErrorKind::Layer,
+        ))
+    })
+}
+
+/// Recognizes a number with decimal separators recognized by `separator`.
+///
+/// Like [`recognize_float`](nom::number::complete::recognize_float), but an
+/// exponent without digits, such as in `1e`, fails as a bad float instead of
+/// a bad integer.
+fn recognize_number<I, E>(
+    separator: fn(I) -> IResult<I, char, E>,
+) -> impl Parser<I, Output = I, Error = E>
+where
+    I: Input + Offset,
+    <I as Input>::Item: AsChar,
     E: ParseError<I>,
 {
-    u64(input)
+    recognize((
+        opt(one_of("+-")),
+        alt((
+            recognize((digit1, opt((separator, digit0)))),
+            recognize((separator, digit1)),
+        )),
+        opt((one_of("eE"), opt(one_of("+-")), exponent_digits)),
+    ))
+}
+
+/// Recognizes the digits of an exponent, failing with [`ErrorKind::Float`].
+fn exponent_digits<I, E>(input: I) -> IResult<I, I, E>
+where
+    I: Input,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I>,
+{
+    digit1::<_, ()>(input.clone())
+        .map_err(|_| Err::Failure(E::from_error_kind(input, ErrorKind::Float)))
 }
 
 pub(crate) fn finite_double<'a, I, E>(input: I) -> IResult<I, FiniteDouble, E>
@@ -271,40 +518,147 @@ where
     <I as Input>::Item: AsChar,
     E: ParseError<I>,
 {
-    let (i, s) = recognize_float(input)?;
-    match s.parse_to() {
-        // Safe to unwrap here cause recognize_float should only recognize
-        // finite numbers.
-        Some(f) => Ok((i, f.try_into().unwrap())),
-        None => Err(Err::Error(E::from_error_kind(i, ErrorKind::Float))),
+    let (i, s) = recognize_number(|i| char('.')(i)).parse(input.clone())?;
+    finite_number(input, i, s.parse_to())
+}
+
+/// Returns the number recognized from `input` up to `rest`.
+///
+/// A huge exponent, such as `1e400`, overflows to infinity, which fails at
+/// the start of the number in `input`, so that the error shows the rest of
+/// the line. A number that cannot be converted fails at `rest`.
+fn finite_number<I, E>(input: I, rest: I, number: Option<f64>) -> IResult<I, FiniteDouble, E>
+where
+    E: ParseError<I>,
+{
+    match number.map(FiniteDouble::new) {
+        Some(Some(f)) => Ok((rest, f)),
+        Some(None) => Err(Err::Error(E::from_error_kind(input, ErrorKind::Float))),
+        None => Err(Err::Error(E::from_error_kind(rest, ErrorKind::Float))),
+    }
+}
+
+/// Parses a number with `,` or `.` as decimal separator.
+fn decimal_comma_double<'a, I, E>(input: I) -> IResult<I, FiniteDouble, E>
+where
+    I: Input + Offset + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I>,
+{
+    let (i, s) = recognize_number(|i| one_of(".,")(i)).parse(input.clone())?;
+    let normalized: String = s
+        .iter_elements()
+        .map(|c| match c.as_char() {
+            ',' => '.',
+            c => c,
+        })
+        .collect();
+    finite_number(input, i, normalized.parse().ok())
+}
+
+/// Parses a number, with `,` also accepted as decimal separator if
+/// `decimal_comma` is set, see [`ParseOptions::decimal_comma`].
+pub(crate) fn finite_double_with<'a, I, E>(
+    decimal_comma: bool,
+) -> impl Parser<I, Output = FiniteDouble, Error = E>
+where
+    I: Input + Offset + ParseTo<f64> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I>,
+{
+    move |input: I| {
+        if decimal_comma {
+            decimal_comma_double(input)
+        } else {
+            finite_double(input)
+        }
+    }
+}
+
+/// Spanned fields recorded while parsing an object, see [`object_fields`].
+type Fields<'a, I> = Option<&'a RefCell<Vec<Spanned<I, Field<I>>>>>;
+
+/// Records the output of a field parser in `fields`, if any, with the span of
+/// the input it consumed.
+fn field<'a, I, O, E, P>(
+    fields: Fields<'a, I>,
+    f: fn(&O) -> Field<I>,
+    mut parser: P,
+) -> impl Parser<I, Output = O, Error = E>
+where
+    I: Input + Offset + 'a,
+    E: ParseError<I>,
+    P: Parser<I, Output = O, Error = E>,
+{
+    move |input: I| {
+        let (rest, value) = parser.parse(input.clone())?;
+        if let Some(fields) = fields {
+            let span = input.take(input.offset(&rest));
+            fields.borrow_mut().push(Spanned {
+                value: f(&value),
+                span,
+            });
+        }
+        Ok((rest, value))
     }
 }
 
-pub(crate) fn vec2<'a, I, E>(input: I) -> IResult<I, Vec2, E>
+/// Parses two numbers, recording them as [`Field::X`] and [`Field::Y`] once
+/// both are parsed.
+fn vec2<'a, I, E>(
+    decimal_comma: bool,
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Vec2, Error = E>
 where
     I: Input + Offset + ParseTo<f64> + 'a,
     <I as Input>::Item: AsChar,
     E: ParseError<I>,
 {
-    Parser::into(separated_pair(finite_double, multispace1, finite_double)).parse(input)
+    let mut pair = separated_pair(
+        consumed(finite_double_with(decimal_comma)),
+        multispace1,
+        consumed(finite_double_with(decimal_comma)),
+    );
+    move |input: I| {
+        let (rest, ((x_span, x), (y_span, y))) = pair.parse(input)?;
+        if let Some(fields) = fields {
+            fields.borrow_mut().extend([
+                Spanned {
+                    value: Field::X(x),
+                    span: x_span,
+                },
+                Spanned {
+                    value: Field::Y(y),
+                    span: y_span,
+                },
+            ]);
+        }
+        Ok((rest, Vec2 { x, y }))
+    }
 }
 
-pub(crate) fn coordinate<'a, I, E>(input: I) -> IResult<I, Coordinate, E>
+pub(crate) fn coordinate_with<'a, I, E>(
+    decimal_comma: bool,
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Coordinate, Error = E>
 where
     I: Input + Offset + ParseTo<f64> + 'a,
     <I as Input>::Item: AsChar,
     E: ParseError<I> + ContextError<I>,
 {
-    context("coordinate", vec2).parse(input)
+    context("coordinate", vec2(decimal_comma, fields))
 }
 
-pub(crate) fn size<'a, I, E>(input: I) -> IResult<I, Size, E>
+pub(crate) fn size_with<'a, I, E>(
+    decimal_comma: bool,
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Size, Error = E>
 where
     I: Input + Offset + ParseTo<f64> + 'a,
     <I as Input>::Item: AsChar,
     E: ParseError<I> + ContextError<I>,
 {
-    context("size", vec2).parse(input)
+    context("size", vec2(decimal_comma, fields))
 }
 
 pub(crate) fn rotation<'a, I, E>(input: I) -> IResult<I, Rotation, E>
@@ -341,61 +695,160 @@ where
     .parse(input)
 }
 
-pub(crate) fn embedding<'a, I, E>(input: I) -> IResult<I, Embedding<I>, E>
+/// Parses an embedded symbol of a component in a schematic at `depth`.
+pub(crate) fn embedding<'a, I, E>(
+    options: &'a ParseOptions,
+    depth: usize,
+) -> impl Parser<I, Output = Embedding<I>, Error = E>
 where
     I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> nom::Compare<&'s str> + 'a,
     <I as Input>::Item: AsChar,
-    E: ParseError<I> + ContextError<I>,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
 {
-    object(
-        "embedded symbol",
-        '[',
+    let depth = depth + 1;
+    let exceeded = options.max_embedding_depth.filter(|&max| depth > max);
+
+    object("embedded symbol", '[', move |input: I| {
+        if let Some(max) = exceeded {
+            return Err(Err::Failure(E::from_external_error(
+                input,
+                ErrorKind::TooLarge,
+                error::ErrorKind::EmbeddingDepth { max },
+            )));
+        }
+
         terminated(
-            preceded(multispace1, Parser::into(schematic)),
+            preceded(
+                multispace1,
+                Parser::into(schematic_at_depth(options, depth)),
+            ),
             preceded(multispace1, char(']')),
-        ),
-    )
-    .parse(input)
+        )
+        .parse(input)
+    })
 }
 
-pub(crate) fn version_object<'a, I, E>(input: I) -> IResult<I, Version<I>, E>
+/// Parses a [`Version`] object (`v`).
+pub fn version_object<'a, I, E>(input: I) -> IResult<I, Version<I>, E>
 where
     I: Eq + Hash + Input + Offset + for<'s> Compare<&'s str> + 'a,
     <I as Input>::Item: AsChar,
     E: ParseError<I> + ContextError<I>,
 {
-    object("version", 'v', preceded(multispace1, property))
-        .map(Version)
-        .parse(input)
+    version_object_with(None).parse(input)
+}
+
+/// Parses a version object, recording its fields in `fields`.
+fn version_object_with<'a, I, E>(
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Version<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I>,
+{
+    object("version", 'v', object_property(fields)).map(Version)
+}
+
+/// Parses the whitespace preceded property of an object, recording it as
+/// [`Field::Property`].
+fn object_property<'a, I, E>(
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Property<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I>,
+{
+    preceded(
+        multispace1,
+        field(
+            fields,
+            |p: &Property<I>| Field::Property(p.prop.clone()),
+            property,
+        ),
+    )
 }
 
 pub(crate) fn property_object<'a, I, E>(
     tag: char,
+    fields: Fields<'a, I>,
 ) -> impl Parser<I, Output = Property<I>, Error = E>
 where
     I: Eq + Hash + Input + Offset + for<'s> Compare<&'s str> + 'a,
     <I as Input>::Item: AsChar,
     E: ParseError<I> + ContextError<I>,
 {
-    object("global property", tag, preceded(multispace1, property))
+    object("global property", tag, object_property(fields))
 }
 
-pub(crate) fn arc_object<'a, I, E>(input: I) -> IResult<I, Arc<I>, E>
+/// Parses any global property object (`G`, `K`, `V`, `S` or `E`).
+fn global_property_object<'a, I, E>(
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Object<I>, Error = E>
 where
-    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    I: Eq + Hash + Input + Offset + for<'s> Compare<&'s str> + 'a,
     <I as Input>::Item: AsChar,
     E: ParseError<I> + ContextError<I>,
 {
+    alt((
+        Parser::into(Parser::into::<VhdlProperty<I>, E>(property_object(
+            'G', fields,
+        ))),
+        Parser::into(Parser::into::<SymbolProperty<I>, E>(property_object(
+            'K', fields,
+        ))),
+        Parser::into(Parser::into::<VerilogProperty<I>, E>(property_object(
+            'V', fields,
+        ))),
+        Parser::into(Parser::into::<SpiceProperty<I>, E>(property_object(
+            'S', fields,
+        ))),
+        Parser::into(Parser::into::<TedaXProperty<I>, E>(property_object(
+            'E', fields,
+        ))),
+    ))
+}
+
+/// Parses a [`Arc`] object (`A`).
+///
+/// The radius is not constrained, see [`Arc::radius`].
+pub fn arc_object<'a, I, E>(input: I) -> IResult<I, Arc<I>, E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    arc_object_with(false, None).parse(input)
+}
+
+/// Parses an arc, with decimal commas if `decimal_comma` is set, recording
+/// its fields in `fields`.
+pub(crate) fn arc_object_with<'a, I, E>(
+    decimal_comma: bool,
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Arc<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    let number = |f| {
+        preceded(
+            multispace1,
+            field(fields, f, finite_double_with(decimal_comma)),
+        )
+    };
     object(
         "arc",
         'A',
         (
-            preceded(multispace1, layer),
-            preceded(multispace1, coordinate),
-            preceded(multispace1, finite_double),
-            preceded(multispace1, finite_double),
-            preceded(multispace1, finite_double),
-            preceded(multispace1, property),
+            preceded(multispace1, layer_field(fields)),
+            preceded(multispace1, coordinate_with(decimal_comma, fields)),
+            number(|r| Field::Radius(*r)),
+            number(|a| Field::Angle(*a)),
+            number(|a| Field::Angle(*a)),
+            object_property(fields),
         ),
     )
     .map(
@@ -408,54 +861,157 @@ where
             property,
         },
     )
-    .parse(input)
 }
 
-pub(crate) fn component_instance<'a, I, E>(input: I) -> IResult<I, Component<I>, E>
+/// Returns whether a character is part of an unknown token of an object.
+fn is_token_char<C: AsChar>(c: C) -> bool {
+    let c = c.as_char();
+    !c.is_ascii_whitespace() && c != '{'
+}
+
+/// Parses an unknown token of an object, recording it as [`Field::Token`].
+fn token<'a, I, E>(fields: Fields<'a, I>) -> impl Parser<I, Output = I, Error = E>
+where
+    I: Input + Offset + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I>,
+{
+    field(
+        fields,
+        |t: &I| Field::Token(t.clone()),
+        take_while1(is_token_char),
+    )
+}
+
+/// Parses a layer index, recording it as [`Field::Layer`].
+fn layer_field<'a, I, E>(fields: Fields<'a, I>) -> impl Parser<I, Output = u64, Error = E>
+where
+    I: Input + Offset + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    field(fields, |l| Field::Layer(*l), layer)
+}
+
+/// Parses the unknown whitespace separated tokens preceding the property of
+/// an object, if `extra_tokens` is set, see [`allows_extra_tokens`].
+fn extra_tokens<'a, I, E>(
+    extra_tokens: bool,
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Vec<I>, Error = E>
+where
+    I: Input + Offset + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I>,
+{
+    let mut tokens = many0(preceded(multispace1, token(fields)));
+    move |input: I| {
+        if extra_tokens {
+            tokens.parse(input)
+        } else {
+            Ok((input, Vec::new()))
+        }
+    }
+}
+
+/// Parses a [`Component`] instance (`C`), including an embedded symbol.
+pub fn component_instance<'a, I, E>(input: I) -> IResult<I, Component<I>, E>
 where
     I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
     <I as Input>::Item: AsChar,
-    E: ParseError<I> + ContextError<I>,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
 {
-    object(
+    component_at_depth(&ParseOptions::default(), 0, false, None).parse(input)
+}
+
+/// Parses a component nested in `depth` embedded symbols, with unknown tokens
+/// if `extra` is set, recording its fields in `fields`.
+///
+/// The fields of the embedded symbol are not recorded.
+pub(crate) fn component_at_depth<'a, I, E>(
+    options: &'a ParseOptions,
+    depth: usize,
+    extra: bool,
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Component<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    let mut instance = object(
         "component",
         'C',
         (
-            preceded(multispace1, reference),
-            preceded(multispace1, coordinate),
-            preceded(multispace1, rotation),
-            preceded(multispace1, flip),
-            preceded(multispace1, property),
-            opt(preceded(multispace1, embedding)),
+            preceded(
+                multispace1,
+                field(fields, |r: &I| Field::Reference(r.clone()), reference),
+            ),
+            preceded(multispace1, coordinate_with(options.decimal_comma, fields)),
+            preceded(
+                multispace1,
+                field(fields, |r| Field::Rotation(*r), rotation),
+            ),
+            preceded(multispace1, field(fields, |f| Field::Flip(*f), flip)),
+            extra_tokens(extra, fields),
+            object_property(fields),
         ),
-    )
-    .map(
-        |(reference, position, rotation, flip, property, embedding)| Component {
-            reference,
-            position,
-            rotation,
-            flip,
-            property,
-            embedding,
-        },
-    )
-    .parse(input)
+    );
+    let mut embedding = opt(preceded(multispace1, embedding(options, depth)));
+
+    move |input: I| {
+        let (rest, (reference, position, rotation, flip, extra, property)) =
+            instance.parse(input.clone())?;
+        // Point errors in the embedded symbol at the reference of the component.
+        let (rest, embedding) = embedding.parse(rest).map_err(|e| {
+            let at_reference = input.take_from(input.offset(&reference));
+            e.map(|e| E::add_context(at_reference, "component", e))
+        })?;
+
+        Ok((
+            rest,
+            Component {
+                reference,
+                position,
+                rotation,
+                flip,
+                extra,
+                property,
+                embedding,
+            },
+        ))
+    }
 }
 
-pub(crate) fn line_object<'a, I, E>(input: I) -> IResult<I, Line<I>, E>
+/// Parses a [`Line`] object (`L`).
+pub fn line_object<'a, I, E>(input: I) -> IResult<I, Line<I>, E>
 where
     I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
     <I as Input>::Item: AsChar,
-    E: ParseError<I> + ContextError<I>,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    line_object_with(false, None).parse(input)
+}
+
+/// Parses a line, with decimal commas if `decimal_comma` is set, recording
+/// its fields in `fields`.
+pub(crate) fn line_object_with<'a, I, E>(
+    decimal_comma: bool,
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Line<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
 {
     object(
         "line",
         'L',
         (
-            preceded(multispace1, layer),
-            preceded(multispace1, coordinate),
-            preceded(multispace1, coordinate),
-            preceded(multispace1, property),
+            preceded(multispace1, layer_field(fields)),
+            preceded(multispace1, coordinate_with(decimal_comma, fields)),
+            preceded(multispace1, coordinate_with(decimal_comma, fields)),
+            object_property(fields),
         ),
     )
     .map(|(layer, start, end, property)| Line {
@@ -464,25 +1020,70 @@ where
         end,
         property,
     })
-    .parse(input)
 }
 
-pub(crate) fn polygon_object<'a, I, E>(input: I) -> IResult<I, Polygon<I>, E>
+/// Parses the point count of a polygon followed by its points.
+///
+/// A count that does not match the number of points fails with
+/// [`error::ErrorKind::PolygonPoints`] at the count.
+fn polygon_points<'a, I, E>(
+    decimal_comma: bool,
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Vec<Coordinate>, Error = E>
+where
+    I: Input + Offset + ParseTo<f64> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    let mut points = (
+        field(fields, |n| Field::PointCount(*n), usize),
+        many0(preceded(space1, coordinate_with(decimal_comma, fields))),
+    );
+    move |input: I| {
+        let (rest, (declared, points)) = points.parse(input.clone())?;
+        if points.len() == declared {
+            Ok((rest, points))
+        } else {
+            Err(Err::Error(E::from_external_error(
+                input,
+                ErrorKind::Count,
+                error::ErrorKind::PolygonPoints {
+                    declared,
+                    found: points.len(),
+                },
+            )))
+        }
+    }
+}
+
+/// Parses a [`Polygon`] object (`P`).
+pub fn polygon_object<'a, I, E>(input: I) -> IResult<I, Polygon<I>, E>
 where
     I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
     <I as Input>::Item: AsChar,
-    E: ParseError<I> + ContextError<I>,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    polygon_object_with(false, None).parse(input)
+}
+
+/// Parses a polygon, with decimal commas if `decimal_comma` is set, recording
+/// its fields in `fields`.
+pub(crate) fn polygon_object_with<'a, I, E>(
+    decimal_comma: bool,
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Polygon<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
 {
     object(
         "polygon",
         'P',
         (
-            preceded(multispace1, layer),
-            preceded(
-                multispace1,
-                length_count(usize, preceded(space1, coordinate)),
-            ),
-            preceded(multispace1, property),
+            preceded(multispace1, layer_field(fields)),
+            preceded(multispace1, polygon_points(decimal_comma, fields)),
+            object_property(fields),
         ),
     )
     .map(|(layer, points, property)| Polygon {
@@ -490,23 +1091,37 @@ where
         points: points.into(),
         property,
     })
-    .parse(input)
 }
 
-pub(crate) fn rectangle_object<'a, I, E>(input: I) -> IResult<I, Rectangle<I>, E>
+/// Parses a [`Rectangle`] object (`B`).
+pub fn rectangle_object<'a, I, E>(input: I) -> IResult<I, Rectangle<I>, E>
 where
     I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
     <I as Input>::Item: AsChar,
-    E: ParseError<I> + ContextError<I>,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    rectangle_object_with(false, None).parse(input)
+}
+
+/// Parses a rectangle, with decimal commas if `decimal_comma` is set,
+/// recording its fields in `fields`.
+pub(crate) fn rectangle_object_with<'a, I, E>(
+    decimal_comma: bool,
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Rectangle<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
 {
     object(
         "rectangle",
         'B',
         (
-            preceded(multispace1, layer),
-            preceded(multispace1, coordinate),
-            preceded(multispace1, coordinate),
-            preceded(multispace1, property),
+            preceded(multispace1, layer_field(fields)),
+            preceded(multispace1, coordinate_with(decimal_comma, fields)),
+            preceded(multispace1, coordinate_with(decimal_comma, fields)),
+            object_property(fields),
         ),
     )
     .map(|(layer, start, end, property)| Rectangle {
@@ -515,10 +1130,24 @@ where
         end,
         property,
     })
-    .parse(input)
 }
 
-pub(crate) fn text_object<'a, I, E>(input: I) -> IResult<I, Text<I>, E>
+/// Parses a [`Text`] object (`T`).
+pub fn text_object<'a, I, E>(input: I) -> IResult<I, Text<I>, E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I>,
+{
+    text_object_with(false, None).parse(input)
+}
+
+/// Parses a text, with decimal commas if `decimal_comma` is set, recording
+/// its fields in `fields`.
+pub(crate) fn text_object_with<'a, I, E>(
+    decimal_comma: bool,
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Text<I>, Error = E>
 where
     I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
     <I as Input>::Item: AsChar,
@@ -528,12 +1157,18 @@ where
         "text",
         'T',
         (
-            preceded(multispace1, text),
-            preceded(multispace1, coordinate),
-            preceded(multispace1, rotation),
-            preceded(multispace1, flip),
-            preceded(multispace1, size),
-            preceded(multispace1, property),
+            preceded(
+                multispace1,
+                field(fields, |t: &I| Field::Text(t.clone()), text),
+            ),
+            preceded(multispace1, coordinate_with(decimal_comma, fields)),
+            preceded(
+                multispace1,
+                field(fields, |r| Field::Rotation(*r), rotation),
+            ),
+            preceded(multispace1, field(fields, |f| Field::Flip(*f), flip)),
+            preceded(multispace1, size_with(decimal_comma, fields)),
+            object_property(fields),
         ),
     )
     .map(|(text, position, rotation, flip, size, property)| Text {
@@ -544,10 +1179,24 @@ where
         size,
         property,
     })
-    .parse(input)
 }
 
-pub(crate) fn wire_object<'a, I, E>(input: I) -> IResult<I, Wire<I>, E>
+/// Parses a [`Wire`] object (`N`).
+pub fn wire_object<'a, I, E>(input: I) -> IResult<I, Wire<I>, E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I>,
+{
+    wire_object_with(false, None).parse(input)
+}
+
+/// Parses a wire, with decimal commas if `decimal_comma` is set, recording
+/// its fields in `fields`.
+pub(crate) fn wire_object_with<'a, I, E>(
+    decimal_comma: bool,
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Wire<I>, Error = E>
 where
     I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
     <I as Input>::Item: AsChar,
@@ -557,9 +1206,9 @@ where
         "wire",
         'N',
         (
-            preceded(multispace1, coordinate),
-            preceded(multispace1, coordinate),
-            preceded(multispace1, property),
+            preceded(multispace1, coordinate_with(decimal_comma, fields)),
+            preceded(multispace1, coordinate_with(decimal_comma, fields)),
+            object_property(fields),
         ),
     )
     .map(|(start, end, property)| Wire {
@@ -567,30 +1216,262 @@ where
         end,
         property,
     })
-    .parse(input)
 }
 
-pub(crate) fn any_object<'a, I, E>(input: I) -> IResult<I, Object<I>, E>
+/// Tags of all known objects, including the version object.
+const OBJECT_TAGS: &str = "vGKVSEACLPBTN";
+
+/// Parses an [`UnknownObject`], whose tag is an ASCII letter that is not the
+/// tag of a known object.
+///
+/// The tokens between the tag and the property are separated by spaces or
+/// tabs and must not contain `{`. The tokens and the start of the property
+/// must be on the line of the tag, so a line without a property fails instead
+/// of taking the following objects as tokens.
+pub fn unknown_object<'a, I, E>(input: I) -> IResult<I, UnknownObject<I>, E>
 where
-    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    I: Eq + Hash + Input + Offset + for<'s> Compare<&'s str> + 'a,
     <I as Input>::Item: AsChar,
     E: ParseError<I> + ContextError<I>,
 {
-    alt((
-        Parser::into(Parser::into::<VhdlProperty<I>, E>(property_object('G'))),
-        Parser::into(Parser::into::<SymbolProperty<I>, E>(property_object('K'))),
-        Parser::into(Parser::into::<VerilogProperty<I>, E>(property_object('V'))),
-        Parser::into(Parser::into::<SpiceProperty<I>, E>(property_object('S'))),
-        Parser::into(Parser::into::<TedaXProperty<I>, E>(property_object('E'))),
-        Parser::into(arc_object),
-        Parser::into(component_instance),
-        Parser::into(line_object),
-        Parser::into(polygon_object),
-        Parser::into(rectangle_object),
-        Parser::into(text_object),
-        Parser::into(wire_object),
-    ))
-    .parse(input)
+    unknown_object_with(None).parse(input)
+}
+
+/// Parses an unknown object, recording its fields in `fields`.
+fn unknown_object_with<'a, I, E>(
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = UnknownObject<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I>,
+{
+    context(
+        "unknown object",
+        (
+            recognize(satisfy(|c| {
+                c.is_ascii_alphabetic() && !OBJECT_TAGS.contains(c)
+            })),
+            cut((
+                many0(preceded(space1, token(fields))),
+                preceded(
+                    space1,
+                    field(
+                        fields,
+                        |p: &Property<I>| Field::Property(p.prop.clone()),
+                        property,
+                    ),
+                ),
+            )),
+        ),
+    )
+    .map(|(tag, (tokens, property))| UnknownObject {
+        tag,
+        tokens,
+        property,
+    })
+}
+
+/// Parses any object other than a version object.
+///
+/// Unknown tokens of newer file versions are not accepted, since a lone
+/// object has no version object to declare its file version.
+pub fn any_object<'a, I, E>(input: I) -> IResult<I, Object<I>, E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    any_object_with_options(&ParseOptions::default()).parse(input)
+}
+
+/// Parses any object other than a version object with [`ParseOptions`], see
+/// [`any_object`].
+#[must_use]
+pub fn any_object_with_options<'a, I, E>(
+    options: &'a ParseOptions,
+) -> impl Parser<I, Output = Object<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    any_object_at_depth(options, 0, false)
+}
+
+/// Parses an object nested in `depth` embedded symbols, with unknown tokens
+/// if `extra_tokens` is set.
+pub(crate) fn any_object_at_depth<'a, I, E>(
+    options: &'a ParseOptions,
+    depth: usize,
+    extra_tokens: bool,
+) -> impl Parser<I, Output = Object<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    any_object_with_fields(options, depth, extra_tokens, None)
+}
+
+/// Parses an object like [`any_object_at_depth`], recording its fields in
+/// `fields`.
+fn any_object_with_fields<'a, I, E>(
+    options: &'a ParseOptions,
+    depth: usize,
+    extra_tokens: bool,
+    fields: Fields<'a, I>,
+) -> impl Parser<I, Output = Object<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    let decimal_comma = options.decimal_comma;
+    let mut known = alt((
+        global_property_object(fields),
+        Parser::into(arc_object_with(decimal_comma, fields)),
+        Parser::into(component_at_depth(options, depth, extra_tokens, fields)),
+        Parser::into(line_object_with(decimal_comma, fields)),
+        Parser::into(polygon_object_with(decimal_comma, fields)),
+        Parser::into(rectangle_object_with(decimal_comma, fields)),
+        Parser::into(text_object_with(decimal_comma, fields)),
+        Parser::into(wire_object_with(decimal_comma, fields)),
+    ));
+    let mut unknown = unknown_object_with(fields);
+
+    // Unknown objects are only tried after all known objects failed, so that
+    // errors of known objects are reported as without the option.
+    move |input: I| match known.parse(input.clone()) {
+        Err(Err::Error(e)) if options.unknown_objects => match unknown.parse(input) {
+            Ok((rest, o)) => Ok((rest, Object::Unknown(o))),
+            Err(Err::Error(_)) => Err(Err::Error(e)),
+            Err(e) => Err(e),
+        },
+        result => result,
+    }
+}
+
+/// Wraps the output of a parser with the span of the input it consumed.
+pub fn spanned<I, O, E, P>(parser: P) -> impl Parser<I, Output = Spanned<I, O>, Error = E>
+where
+    I: Input + Offset,
+    E: ParseError<I>,
+    P: Parser<I, Output = O, Error = E>,
+{
+    consumed(parser).map(|(span, value)| Spanned { value, span })
+}
+
+/// Parses the fields of any object, including the version object, with the
+/// span of each field in input order.
+///
+/// This complements the parsed objects, which only keep the location of
+/// fields of the input type, such as the reference of a component or the
+/// property string, with the location of every number, like the rotation of
+/// a component or each number of a coordinate. Spans of braced fields include
+/// the braces. The object is parsed like with [`any_object`], so unknown
+/// tokens of components are not accepted and the embedded symbol of a
+/// component is parsed, but its fields are not recorded. See
+/// [`object_fields_with_options`] to parse unknown objects.
+///
+/// ```
+/// use nom::error::ErrorKind;
+/// use xschem_parser::Span;
+/// use xschem_parser::parse::object_fields;
+/// use xschem_parser::token::{Field, Rotation};
+///
+/// let input = Span::new("C {res.sym} 10 20 2 0 {name=R1}");
+/// let (_, fields) = object_fields::<_, (Span, ErrorKind)>(input).unwrap();
+///
+/// assert_eq!(fields[3].value, Field::Rotation(Rotation::Two));
+/// assert_eq!(fields[3].span.get_column(), 19);
+/// ```
+pub fn object_fields<'a, I, E>(input: I) -> IResult<I, Vec<Spanned<I, Field<I>>>, E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    object_fields_with_options(&ParseOptions::default()).parse(input)
+}
+
+/// Parses the fields of any object with [`ParseOptions`], see
+/// [`object_fields`].
+#[must_use]
+pub fn object_fields_with_options<'a, I, E>(
+    options: &'a ParseOptions,
+) -> impl Parser<I, Output = Vec<Spanned<I, Field<I>>>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    move |input: I| {
+        let fields = RefCell::new(Vec::new());
+        let (rest, ()) = alt((
+            version_object_with(Some(&fields)).map(|_| ()),
+            any_object_with_fields(options, 0, false, Some(&fields)).map(|_| ()),
+        ))
+        .parse(input)?;
+        Ok((rest, fields.into_inner()))
+    }
+}
+
+/// Returns the value and the parsed major and minor version of a valid
+/// `file_version` attribute of a version object.
+fn file_version<I>(version: &Version<I>) -> Option<(&I, (u32, u32))>
+where
+    I: Clone + Input + for<'s> Compare<&'s str>,
+    <I as Input>::Item: AsChar,
+{
+    let value = version.0.attrs.iter().find_map(|(k, v)| {
+        (k.input_len() == "file_version".len() && k.compare("file_version") == CompareResult::Ok)
+            .then_some(v)
+    })?;
+
+    let version = || separated_pair(nom_u32::<_, ()>, char('.'), nom_u32);
+    terminated(
+        alt((delimited(char('"'), version(), char('"')), version())),
+        eof,
+    )
+    .parse(value.clone())
+    .finish()
+    .ok()
+    .map(|(_, found)| (value, found))
+}
+
+/// Checks the declared `file_version` of a version object parsed from `input`
+/// against a maximum.
+///
+/// The error is located at the value, with the rest of `input` so that the
+/// whole line of the version object is shown.
+fn check_file_version<I, E>(input: &I, version: &Version<I>, max: (u32, u32)) -> Result<(), Err<E>>
+where
+    I: Clone + Input + Offset + for<'s> Compare<&'s str>,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    match file_version(version) {
+        Some((value, found)) if found > max => Err(Err::Failure(E::from_external_error(
+            input.take_from(input.offset(value)),
+            ErrorKind::Verify,
+            error::ErrorKind::FileVersion {
+                version: found,
+                max,
+            },
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Returns whether objects may carry unknown tokens, which is the case for
+/// file versions newer than [`SUPPORTED_FILE_VERSION`].
+pub(crate) fn allows_extra_tokens<I>(version: &Version<I>) -> bool
+where
+    I: Clone + Input + for<'s> Compare<&'s str>,
+    <I as Input>::Item: AsChar,
+{
+    file_version(version).is_some_and(|(_, found)| found > SUPPORTED_FILE_VERSION)
 }
 
 /// Parse a [`Schematic`] from input.
@@ -598,19 +1479,169 @@ pub fn schematic<'a, I, E>(input: I) -> IResult<I, Schematic<I>, E>
 where
     I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
     <I as Input>::Item: AsChar,
-    E: ParseError<I> + ContextError<I>,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
 {
-    preceded(
-        multispace0,
-        version_object.flat_map(|version| {
-            fold_many0(
-                preceded(multispace1, any_object),
-                move || Schematic::new(version.clone()),
-                Schematic::add_object,
-            )
-        }),
-    )
-    .parse(input)
+    schematic_with_options(&ParseOptions::default()).parse(input)
+}
+
+/// Parse a [`Schematic`] from input with [`ParseOptions`].
+#[must_use]
+pub fn schematic_with_options<'a, I, E>(
+    options: &'a ParseOptions,
+) -> impl Parser<I, Output = Schematic<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    schematic_at_depth(options, 0)
+}
+
+/// UTF-8 byte order mark, which some editors prepend to files.
+const BYTE_ORDER_MARK: &str = "\u{feff}";
+
+/// Parses the version object of a top-level schematic and checks it against
+/// the options.
+///
+/// A leading UTF-8 byte order mark is skipped, so span offsets still refer to
+/// the full input. Input that does not start with a version object, such as a
+/// pasted fragment of objects, fails with [`error::ErrorKind::MissingVersion`].
+/// A version object with nothing following the `v` on its line fails with
+/// [`error::ErrorKind::MissingVersionProperty`] right after the `v`, while an
+/// empty property `v {}` is a valid version without attributes.
+pub(crate) fn header<'a, I, E>(
+    options: &'a ParseOptions,
+) -> impl Parser<I, Output = Version<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    header_at_depth(options, 0)
+}
+
+/// Parses the version object of a schematic nested in `depth` embedded
+/// symbols, see [`header`].
+///
+/// Only a top-level schematic may start with a byte order mark.
+fn header_at_depth<'a, I, E>(
+    options: &'a ParseOptions,
+    depth: usize,
+) -> impl Parser<I, Output = Version<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    move |input: I| {
+        let (input, _) = cond(depth == 0, opt(tag(BYTE_ORDER_MARK))).parse(input)?;
+        let (input, _) = multispace0(input)?;
+        if input
+            .iter_elements()
+            .next()
+            .is_none_or(|c| c.as_char() != 'v')
+        {
+            let e = E::from_external_error(
+                input.clone(),
+                ErrorKind::Char,
+                error::ErrorKind::MissingVersion,
+            );
+            return Err(Err::Error(E::add_context(input, "version", e)));
+        }
+        let after_tag = input.take_from(1);
+        // The property may follow on a later line, but a line starting with
+        // anything else is the next object.
+        let mut newline = false;
+        let next = after_tag
+            .iter_elements()
+            .map(AsChar::as_char)
+            .inspect(|&c| newline |= c == '\n')
+            .find(|c| !c.is_ascii_whitespace());
+        if next.is_none_or(|c| c != '{' && newline) {
+            let e = E::from_external_error(
+                after_tag,
+                ErrorKind::Char,
+                error::ErrorKind::MissingVersionProperty,
+            );
+            return Err(Err::Error(E::add_context(input, "version", e)));
+        }
+        let (rest, version) = version_object(input.clone())?;
+
+        if let Some(max) = options.max_file_version {
+            check_file_version(&input, &version, max)
+                .map_err(|e| e.map(|e| E::add_context(input, "version", e)))?;
+        }
+
+        Ok((rest, version))
+    }
+}
+
+/// Returns a fold function adding objects to a schematic, except for those
+/// dropped by [`ParseOptions::keep`].
+pub(crate) fn keep_object<I>(
+    options: &ParseOptions,
+) -> impl Fn(Schematic<I>, Object<I>) -> Schematic<I> + '_ {
+    |schematic, object| {
+        if options.keep.keeps(&object) {
+            schematic.add_object(object)
+        } else {
+            schematic
+        }
+    }
+}
+
+/// Parses a schematic nested in `depth` embedded symbols.
+fn schematic_at_depth<'a, I, E>(
+    options: &'a ParseOptions,
+    depth: usize,
+) -> impl Parser<I, Output = Schematic<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    move |input: I| {
+        let (rest, version) = header_at_depth(options, depth).parse(input)?;
+        let extra_tokens = allows_extra_tokens(&version);
+
+        fold_many0(
+            preceded(
+                multispace1,
+                any_object_at_depth(options, depth, extra_tokens),
+            ),
+            move || Schematic::new(version.clone()),
+            keep_object(options),
+        )
+        .parse(rest)
+    }
+}
+
+/// Parses the version object and the global properties following it with
+/// [`ParseOptions`].
+///
+/// Parsing stops before the first other object, so the rest of the input is
+/// not parsed and need not be valid. Global properties after the first other
+/// object are not included.
+#[must_use]
+pub fn schematic_header<'a, I, E>(
+    options: &'a ParseOptions,
+) -> impl Parser<I, Output = SchematicHeader<I>, Error = E>
+where
+    I: Eq + Hash + Input + Offset + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    move |input: I| {
+        let (rest, version) = header(options).parse(input)?;
+
+        fold_many0(
+            preceded(multispace1, global_property_object(None)),
+            move || Schematic::new(version.clone()),
+            Schematic::add_object,
+        )
+        .map(SchematicHeader::from)
+        .parse(rest)
+    }
 }
 
 /// Parses a schematic to the end of the input.
@@ -618,10 +1649,302 @@ pub fn schematic_full<'a, I, E>(input: I) -> Result<Schematic<I>, E>
 where
     I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
     <I as Input>::Item: AsChar,
-    E: ParseError<I> + ContextError<I>,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
 {
-    terminated(schematic, preceded(multispace0, eof))
+    schematic_full_with_options(input, &ParseOptions::default())
+}
+
+/// Parses a schematic to the end of the input with [`ParseOptions`].
+pub fn schematic_full_with_options<'a, I, E>(
+    input: I,
+    options: &ParseOptions,
+) -> Result<Schematic<I>, E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    terminated(schematic_with_options(options), preceded(multispace0, eof))
         .parse(input)
         .finish()
         .map(|r| r.1)
 }
+
+/// Skips leading whitespace, returning the rest and the number of skipped
+/// characters.
+fn skip_multispace<I>(input: &I) -> (I, usize)
+where
+    I: Input,
+    <I as Input>::Item: AsChar,
+{
+    let space = input
+        .position(|c| !matches!(c.as_char(), ' ' | '\t' | '\r' | '\n'))
+        .unwrap_or(input.input_len());
+    (input.take_from(space), space)
+}
+
+/// Parses a schematic to the end of the input with [`ParseOptions`],
+/// collecting all errors.
+///
+/// After a malformed object the error is recorded and parsing continues on
+/// the next line. The first error is the same as the error returned by
+/// [`schematic_full_with_options`]. A malformed version object cannot be
+/// recovered from. Since recovery is line based, errors within multi-line
+/// objects or embedded symbols may cause subsequent errors.
+pub fn schematic_full_all_errors<'a, I, E>(
+    input: I,
+    options: &ParseOptions,
+) -> Result<Schematic<I>, Vec<E>>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    let (mut input, version) = header(options).parse(input).finish().map_err(|e| vec![e])?;
+    let mut object = any_object_at_depth(options, 0, allows_extra_tokens(&version));
+    let mut schematic = Schematic::new(version);
+    let mut errors = Vec::new();
+
+    loop {
+        let space;
+        (input, space) = skip_multispace(&input);
+        if input.input_len() == 0 {
+            break;
+        }
+
+        let result = if space > 0 {
+            object.parse(input.clone())
+        } else {
+            Err(Err::Error(E::from_error_kind(
+                input.clone(),
+                ErrorKind::Eof,
+            )))
+        };
+        let e = match result {
+            Ok((rest, o)) => {
+                schematic = keep_object(options)(schematic, o);
+                input = rest;
+                continue;
+            }
+            Err(Err::Failure(e)) => e,
+            Err(Err::Error(_) | Err::Incomplete(_)) => {
+                E::from_error_kind(input.clone(), ErrorKind::Eof)
+            }
+        };
+        errors.push(e);
+
+        let line_end = input
+            .position(|c| c.as_char() == '\n')
+            .unwrap_or(input.input_len());
+        input = input.take_from(line_end);
+    }
+
+    if errors.is_empty() {
+        Ok(schematic)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Parses a schematic to the end of the input with [`ParseOptions`],
+/// keeping the exact input text of each object and the whitespace between
+/// them.
+///
+/// The parsed schematic and the first error are the same as of
+/// [`schematic_full_with_options`].
+/// Objects dropped by [`ParseOptions::keep`] are missing from the schematic,
+/// but their input text is kept.
+pub fn schematic_full_lossless<'a, I, E>(
+    input: I,
+    options: &ParseOptions,
+) -> Result<LosslessView<I>, E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str> + 'a,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    let (mut input, (version_text, version)) = consumed(header(options)).parse(input).finish()?;
+    // The header skips a byte order mark and whitespace before the `v` of
+    // the version object, which is kept as separator of the version object.
+    let start = version_text.position(|c| c.as_char() == 'v').unwrap_or(0);
+    let (version_text, leading) = version_text.take_split(start);
+    let mut object = consumed(any_object_at_depth(
+        options,
+        0,
+        allows_extra_tokens(&version),
+    ));
+    let mut schematic = Schematic::new(version);
+    let mut objects = vec![(leading, version_text)];
+
+    loop {
+        let (rest, space) = skip_multispace(&input);
+        let separator = input.take(space);
+        if rest.input_len() == 0 {
+            return Ok(LosslessView {
+                schematic,
+                objects,
+                trailing: separator,
+            });
+        }
+
+        let result = if space > 0 {
+            object.parse(rest.clone())
+        } else {
+            Err(Err::Error(E::from_error_kind(rest.clone(), ErrorKind::Eof)))
+        };
+        match result {
+            Ok((after, (text, o))) => {
+                if options.keep.keeps(&o) {
+                    schematic.add_object_mut(o);
+                }
+                objects.push((separator, text));
+                input = after;
+            }
+            Err(Err::Failure(e)) => return Err(e),
+            Err(Err::Error(_) | Err::Incomplete(_)) => {
+                return Err(E::from_error_kind(rest, ErrorKind::Eof));
+            }
+        }
+    }
+}
+
+/// Iterator over the objects of a schematic, parsed one at a time.
+///
+/// Objects are yielded in input order, including global properties, except
+/// for those dropped by [`ParseOptions::keep`]. Parsing stops after the first
+/// error. Created with [`ObjectStream::new`].
+#[derive(Clone, Debug)]
+pub struct ObjectStream<I, E> {
+    input: I,
+    version: Version<I>,
+    options: ParseOptions,
+    extra_tokens: bool,
+    done: bool,
+    error: PhantomData<E>,
+}
+
+impl<I, E> ObjectStream<I, E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str>,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    /// Parses the version object of the input and returns an iterator over the
+    /// remaining objects.
+    pub fn new(input: I, options: ParseOptions) -> Result<Self, E> {
+        let (input, version) = header(&options).parse(input).finish()?;
+        Ok(Self {
+            input,
+            extra_tokens: allows_extra_tokens(&version),
+            version,
+            options,
+            done: false,
+            error: PhantomData,
+        })
+    }
+
+    /// Returns the version object of the schematic.
+    pub fn version(&self) -> &Version<I> {
+        &self.version
+    }
+
+    /// Returns an iterator over the objects with the span of the input text
+    /// of each object.
+    ///
+    /// The span excludes the surrounding whitespace and includes the embedded
+    /// symbol of a component, so replacing it in the input replaces exactly
+    /// one object.
+    ///
+    /// ```
+    /// let input = "v {xschem version=3.4.5 file_version=1.2}\nN 0 0 10 0 {lab=A}\n";
+    /// let wire = xschem_parser::parse_objects(input)
+    ///     .unwrap()
+    ///     .spanned()
+    ///     .next()
+    ///     .unwrap()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(*wire.span.fragment(), "N 0 0 10 0 {lab=A}");
+    /// assert_eq!(wire.span.location_offset(), 42);
+    /// ```
+    pub fn spanned(self) -> SpannedObjectStream<I, E> {
+        SpannedObjectStream(self)
+    }
+
+    /// Parses the next object that is kept, with its span.
+    fn next_spanned(&mut self) -> Option<Result<Spanned<I, Object<I>>, E>> {
+        while !self.done {
+            let (input, space) = skip_multispace(&self.input);
+            if input.input_len() == 0 {
+                self.done = true;
+                return None;
+            }
+
+            let result = if space > 0 {
+                spanned(any_object_at_depth(&self.options, 0, self.extra_tokens))
+                    .parse(input.clone())
+            } else {
+                Err(Err::Error(E::from_error_kind(
+                    input.clone(),
+                    ErrorKind::Eof,
+                )))
+            };
+            match result {
+                Ok((rest, object)) => {
+                    self.input = rest;
+                    if self.options.keep.keeps(&object.value) {
+                        return Some(Ok(object));
+                    }
+                }
+                Err(Err::Failure(e)) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                Err(Err::Error(_) | Err::Incomplete(_)) => {
+                    self.done = true;
+                    return Some(Err(E::from_error_kind(input, ErrorKind::Eof)));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<I, E> Iterator for ObjectStream<I, E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str>,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    type Item = Result<Object<I>, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_spanned()
+            .map(|result| result.map(|object| object.value))
+    }
+}
+
+/// Iterator over the objects of a schematic with their spans, created with
+/// [`ObjectStream::spanned`].
+#[derive(Clone, Debug)]
+pub struct SpannedObjectStream<I, E>(ObjectStream<I, E>);
+
+impl<I, E> SpannedObjectStream<I, E> {
+    /// Returns the version object of the schematic.
+    pub fn version(&self) -> &Version<I> {
+        &self.0.version
+    }
+}
+
+impl<I, E> Iterator for SpannedObjectStream<I, E>
+where
+    I: Eq + Hash + Input + Offset + ParseTo<f64> + for<'s> Compare<&'s str>,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I> + ContextError<I> + FromExternalError<I, error::ErrorKind>,
+{
+    type Item = Result<Spanned<I, Object<I>>, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_spanned()
+    }
+}
@@ -0,0 +1,113 @@
+//! Canonical ordering of objects.
+use core::cmp::Ordering;
+
+use crate::token::{Arc, Component, Line, Polygon, Property, Rectangle, Schematic, Text, Wire};
+
+/// Compares values that are always comparable, such as finite doubles.
+fn partial<T: PartialOrd + ?Sized>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+/// Compares property strings.
+fn property<I: AsRef<str>>(a: &Property<I>, b: &Property<I>) -> Ordering {
+    a.prop.as_ref().cmp(b.prop.as_ref())
+}
+
+fn arc<I: AsRef<str>>(a: &Arc<I>, b: &Arc<I>) -> Ordering {
+    a.layer
+        .cmp(&b.layer)
+        .then_with(|| partial(&a.center, &b.center))
+        .then_with(|| partial(&a.radius, &b.radius))
+        .then_with(|| partial(&a.start_angle, &b.start_angle))
+        .then_with(|| partial(&a.sweep_angle, &b.sweep_angle))
+        .then_with(|| property(&a.property, &b.property))
+}
+
+fn component<I: AsRef<str>>(a: &Component<I>, b: &Component<I>) -> Ordering {
+    a.reference
+        .as_ref()
+        .cmp(b.reference.as_ref())
+        .then_with(|| partial(&a.position, &b.position))
+        .then_with(|| a.rotation.cmp(&b.rotation))
+        .then_with(|| a.flip.cmp(&b.flip))
+        .then_with(|| {
+            let b = b.extra.iter().map(AsRef::as_ref);
+            a.extra.iter().map(AsRef::as_ref).cmp(b)
+        })
+        .then_with(|| property(&a.property, &b.property))
+}
+
+fn line<I: AsRef<str>>(a: &Line<I>, b: &Line<I>) -> Ordering {
+    a.layer
+        .cmp(&b.layer)
+        .then_with(|| partial(&a.start, &b.start))
+        .then_with(|| partial(&a.end, &b.end))
+        .then_with(|| property(&a.property, &b.property))
+}
+
+fn polygon<I: AsRef<str>>(a: &Polygon<I>, b: &Polygon<I>) -> Ordering {
+    a.layer
+        .cmp(&b.layer)
+        .then_with(|| partial(a.points.as_slice(), b.points.as_slice()))
+        .then_with(|| property(&a.property, &b.property))
+}
+
+fn rectangle<I: AsRef<str>>(a: &Rectangle<I>, b: &Rectangle<I>) -> Ordering {
+    a.layer
+        .cmp(&b.layer)
+        .then_with(|| partial(&a.start, &b.start))
+        .then_with(|| partial(&a.end, &b.end))
+        .then_with(|| property(&a.property, &b.property))
+}
+
+fn text<I: AsRef<str>>(a: &Text<I>, b: &Text<I>) -> Ordering {
+    a.text
+        .as_ref()
+        .cmp(b.text.as_ref())
+        .then_with(|| partial(&a.position, &b.position))
+        .then_with(|| a.rotation.cmp(&b.rotation))
+        .then_with(|| a.flip.cmp(&b.flip))
+        .then_with(|| partial(&a.size, &b.size))
+        .then_with(|| property(&a.property, &b.property))
+}
+
+fn wire<I: AsRef<str>>(a: &Wire<I>, b: &Wire<I>) -> Ordering {
+    partial(&a.start, &b.start)
+        .then_with(|| partial(&a.end, &b.end))
+        .then_with(|| property(&a.property, &b.property))
+}
+
+impl<I: AsRef<str>> Schematic<I> {
+    /// Sorts the objects into a canonical order, for deterministic output.
+    ///
+    /// [`Schematic::order`] is cleared, so objects are displayed grouped by
+    /// type (see [`Schematic::objects`]). Within each type objects are sorted
+    /// by the following keys, comparing coordinates by x and then y, and
+    /// strings, such as references and property strings, as in the input:
+    ///
+    /// - texts: text, position, rotation, flip, size and property;
+    /// - lines and rectangles: layer, start, end and property;
+    /// - polygons: layer, points and property;
+    /// - arcs: layer, center, radius, start angle, sweep angle and property;
+    /// - wires: start, end and property;
+    /// - components: reference, position, rotation, flip, unknown tokens and
+    ///   property.
+    ///
+    /// Embedded symbols are sorted as well. Global properties are not
+    /// affected.
+    pub fn sort_canonical(&mut self) {
+        self.texts.sort_by(text);
+        self.lines.sort_by(line);
+        self.rectangles.sort_by(rectangle);
+        self.polygons.sort_by(polygon);
+        self.arcs.sort_by(arc);
+        self.wires.sort_by(wire);
+        self.components.sort_by(component);
+        for c in &mut self.components {
+            if let Some(embedding) = &mut c.embedding {
+                embedding.0.sort_canonical();
+            }
+        }
+        self.order.clear();
+    }
+}
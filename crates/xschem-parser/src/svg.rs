@@ -0,0 +1,282 @@
+//! Rendering to SVG.
+//!
+//! Only primitive geometry is rendered: lines, rectangles, polygons, arcs,
+//! wires and texts. Components and embedded symbols are not rendered, since
+//! their graphics are defined by the referenced symbols. Like Xschem, the
+//! y-axis points down, so coordinates map directly to SVG user units.
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::string::{String, ToString};
+
+use crate::token::{Arc, Line, Polygon, Rectangle, Schematic, TEXT_LAYER, Text, Vec2, Wire};
+
+/// Layer of wires in Xschem.
+pub const WIRE_LAYER: u64 = 1;
+
+/// Ratio of the SVG font size to the text size of a text object.
+///
+/// This approximates the character height of the Xschem vector font.
+const FONT_SCALE: f64 = 50.0;
+
+/// Options to configure SVG rendering.
+///
+/// Options are built by chaining setters on the default options:
+///
+/// ```
+/// use xschem_parser::svg::SvgOptions;
+///
+/// let options = SvgOptions::new().layer_color(1, "#0f0").stroke_width(2.0);
+///
+/// assert_eq!(options.colors[&1], "#0f0");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgOptions {
+    /// SVG colors of layers. Wires are drawn on [`WIRE_LAYER`]. See
+    /// [`color`](crate::color) for the default colors of Xschem.
+    pub colors: BTreeMap<u64, String>,
+    /// SVG color of layers without a color in [`SvgOptions::colors`].
+    pub default_color: String,
+    /// SVG color of the background, or [`None`] for a transparent background.
+    pub background: Option<String>,
+    /// Stroke width of lines and outlines.
+    pub stroke_width: f64,
+    /// Margin added around the bounding box of the schematic.
+    pub margin: f64,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            colors: BTreeMap::new(),
+            default_color: "black".to_string(),
+            background: None,
+            stroke_width: 1.0,
+            margin: 10.0,
+        }
+    }
+}
+
+impl SvgOptions {
+    /// Creates default options.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the SVG color of a layer.
+    #[must_use]
+    pub fn layer_color(mut self, layer: u64, color: impl Into<String>) -> Self {
+        self.colors.insert(layer, color.into());
+        self
+    }
+
+    /// Sets the SVG color of layers without a color.
+    #[must_use]
+    pub fn default_color(mut self, color: impl Into<String>) -> Self {
+        self.default_color = color.into();
+        self
+    }
+
+    /// Sets the SVG color of the background.
+    #[must_use]
+    pub fn background(mut self, background: Option<String>) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Sets the stroke width of lines and outlines.
+    #[must_use]
+    pub fn stroke_width(mut self, stroke_width: f64) -> Self {
+        self.stroke_width = stroke_width;
+        self
+    }
+
+    /// Sets the margin around the bounding box of the schematic.
+    #[must_use]
+    pub fn margin(mut self, margin: f64) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Returns the SVG color of a layer.
+    fn color(&self, layer: u64) -> &str {
+        self.colors.get(&layer).unwrap_or(&self.default_color)
+    }
+}
+
+/// Text with the characters reserved in XML escaped.
+struct Escaped<'a>(&'a str);
+
+impl fmt::Display for Escaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '<' => f.write_str("&lt;")?,
+                '>' => f.write_str("&gt;")?,
+                '&' => f.write_str("&amp;")?,
+                '"' => f.write_str("&quot;")?,
+                '\'' => f.write_str("&apos;")?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes an SVG line between two coordinates.
+fn write_line<W: Write>(w: &mut W, start: Vec2, end: Vec2, color: &str) -> fmt::Result {
+    writeln!(
+        w,
+        r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}"/>"#,
+        start.x,
+        start.y,
+        end.x,
+        end.y,
+        Escaped(color),
+    )
+}
+
+/// Writes an SVG circle or path of an arc, with the absolute radius like
+/// the geometry helpers.
+fn write_arc<W: Write, I>(w: &mut W, arc: &Arc<I>, color: &str) -> fmt::Result {
+    let color = Escaped(color);
+    if arc.sweep_angle.abs() >= 360.0 {
+        return writeln!(
+            w,
+            r#"<circle cx="{}" cy="{}" r="{}" stroke="{color}"/>"#,
+            arc.center.x,
+            arc.center.y,
+            arc.radius.abs(),
+        );
+    }
+
+    let Vec2 { x: x1, y: y1 } = arc.start_point();
+    let Vec2 { x: x2, y: y2 } = arc.end_point();
+    let large = u8::from(arc.sweep_angle.abs() > 180.0);
+    // A positive sweep is counterclockwise on screen, which is sweep flag 0.
+    let sweep = u8::from(*arc.sweep_angle < 0.0);
+    writeln!(
+        w,
+        r#"<path d="M {x1} {y1} A {r} {r} 0 {large} {sweep} {x2} {y2}" stroke="{color}"/>"#,
+        r = arc.radius.abs(),
+    )
+}
+
+/// Writes an SVG polyline of a polygon.
+fn write_polygon<W: Write, I>(w: &mut W, polygon: &Polygon<I>, color: &str) -> fmt::Result {
+    w.write_str(r#"<polyline points=""#)?;
+    for (i, point) in polygon.points.iter().enumerate() {
+        if i > 0 {
+            w.write_char(' ')?;
+        }
+        write!(w, "{},{}", point.x, point.y)?;
+    }
+    writeln!(w, r#"" stroke="{}"/>"#, Escaped(color))
+}
+
+/// Writes an SVG rectangle.
+fn write_rectangle<W: Write, I>(w: &mut W, rectangle: &Rectangle<I>, color: &str) -> fmt::Result {
+    let (min, max) = rectangle.bounding_box();
+    writeln!(
+        w,
+        r#"<rect x="{}" y="{}" width="{}" height="{}" stroke="{}"/>"#,
+        min.x,
+        min.y,
+        *max.x - *min.x,
+        *max.y - *min.y,
+        Escaped(color),
+    )
+}
+
+/// Writes an SVG text, rotated around its position.
+fn write_text<W: Write, I: AsRef<str>>(w: &mut W, text: &Text<I>, color: &str) -> fmt::Result {
+    let Vec2 { x, y } = text.position;
+    writeln!(
+        w,
+        r#"<text x="{x}" y="{y}" font-size="{}" transform="rotate({} {x} {y})" fill="{}" stroke="none" dominant-baseline="hanging">{}</text>"#,
+        *text.size.y * FONT_SCALE,
+        90 * text.rotation as u16,
+        Escaped(color),
+        Escaped(&text.unescaped_text()),
+    )
+}
+
+/// Writes a schematic as an SVG document, see [`Schematic::to_svg`].
+pub fn write_svg<W: Write, I: AsRef<str>>(
+    w: &mut W,
+    schematic: &Schematic<I>,
+    options: &SvgOptions,
+) -> fmt::Result {
+    w.write_str(r#"<svg xmlns="http://www.w3.org/2000/svg""#)?;
+    if let Some((min, max)) = schematic.bounding_box() {
+        let m = options.margin;
+        write!(
+            w,
+            r#" viewBox="{} {} {} {}""#,
+            *min.x - m,
+            *min.y - m,
+            *max.x - *min.x + 2.0 * m,
+            *max.y - *min.y + 2.0 * m,
+        )?;
+    }
+    writeln!(
+        w,
+        r#" fill="none" stroke-width="{}" stroke-linecap="round">"#,
+        options.stroke_width
+    )?;
+    if let Some(background) = &options.background {
+        writeln!(
+            w,
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            Escaped(background)
+        )?;
+    }
+
+    for Line {
+        layer, start, end, ..
+    } in &schematic.lines
+    {
+        write_line(w, *start, *end, options.color(*layer))?;
+    }
+    for rectangle in &schematic.rectangles {
+        write_rectangle(w, rectangle, options.color(rectangle.layer))?;
+    }
+    for polygon in &schematic.polygons {
+        write_polygon(w, polygon, options.color(polygon.layer))?;
+    }
+    for arc in &schematic.arcs {
+        write_arc(w, arc, options.color(arc.layer))?;
+    }
+    for Wire { start, end, .. } in &schematic.wires {
+        write_line(w, *start, *end, options.color(WIRE_LAYER))?;
+    }
+    for text in &schematic.texts {
+        write_text(w, text, options.color(text.layer().unwrap_or(TEXT_LAYER)))?;
+    }
+
+    w.write_str("</svg>\n")
+}
+
+impl<I: AsRef<str>> Schematic<I> {
+    /// Renders the primitive geometry of the schematic as an SVG document.
+    ///
+    /// The view box is the bounding box of the schematic (see
+    /// [`Schematic::bounding_box`]) with a margin. Texts are drawn on the
+    /// layer given by their `layer` attribute, defaulting to [`TEXT_LAYER`].
+    ///
+    /// ```
+    /// use xschem_parser::svg::SvgOptions;
+    ///
+    /// let schematic = xschem_parser::from_str("v {}\nN 0 0 20 0 {lab=A}").unwrap();
+    /// let svg = schematic.to_svg(&SvgOptions::new().layer_color(1, "green"));
+    ///
+    /// assert!(svg.contains(r#"<line x1="0" y1="0" x2="20" y2="0" stroke="green"/>"#));
+    /// ```
+    #[must_use]
+    pub fn to_svg(&self, options: &SvgOptions) -> String {
+        let mut s = String::new();
+        // Writing to a `String` does not fail.
+        let _ = write_svg(&mut s, self, options);
+        s
+    }
+}
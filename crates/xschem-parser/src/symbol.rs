@@ -0,0 +1,251 @@
+//! Symbol specific data structures.
+use alloc::vec::Vec;
+
+use derive_more::Display;
+
+use crate::token::{Component, Schematic, Vec2};
+
+/// Direction of a symbol pin.
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PinDirection {
+    #[display("in")]
+    In,
+    #[display("out")]
+    Out,
+    #[display("inout")]
+    Inout,
+}
+
+/// Kind of an Xschem file, see [`Schematic::kind`].
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash)]
+pub enum SchematicKind {
+    #[display("schematic")]
+    Schematic,
+    #[display("symbol")]
+    Symbol,
+}
+
+/// Symbol pin.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pin<I> {
+    /// Pin name from the `name` attribute, as in the input.
+    pub name: I,
+    /// Pin direction from the `dir` attribute.
+    pub dir: PinDirection,
+    /// Pin number from the `pinnumber` attribute, as in the input.
+    pub number: Option<I>,
+    /// Center of the pin rectangle.
+    pub position: Vec2,
+}
+
+/// Port of the interface of a subcircuit, see
+/// [`Schematic::subcircuit_ports`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Port<I> {
+    /// Port name, as in the input.
+    pub name: I,
+    /// Port direction.
+    pub dir: PinDirection,
+}
+
+/// Symbol reference stems of the label components of the Xschem `devices`
+/// library.
+pub const LABEL_STEMS: &[&str] = &["lab_pin", "lab_wire"];
+/// Symbol reference stems of the power and ground components of the Xschem
+/// `devices` library.
+pub const POWER_STEMS: &[&str] = &["gnd", "vdd"];
+/// Symbol reference stems of the pin components of the Xschem `devices`
+/// library.
+pub const PIN_STEMS: &[&str] = &["ipin", "opin", "iopin"];
+
+/// Sets of symbol reference stems classifying components.
+///
+/// Stems are file names of symbol references without directory and `.sym`
+/// extension, see [`Component::symbol_stem`]. The default sets are
+/// [`LABEL_STEMS`], [`POWER_STEMS`] and [`PIN_STEMS`], as used by
+/// [`Component::is_label`], [`Component::is_power`] and
+/// [`Component::is_pin`].
+///
+/// ```
+/// use xschem_parser::symbol::SymbolClasses;
+///
+/// let input = "v {}\nC {my_lab.sym} 0 0 0 0 {name=l1 lab=A}";
+/// let schematic = xschem_parser::from_str(input).unwrap();
+/// let classes = SymbolClasses {
+///     labels: &["my_lab"],
+///     ..SymbolClasses::default()
+/// };
+///
+/// assert!(!schematic.components[0].is_label());
+/// assert!(classes.is_label(&schematic.components[0]));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SymbolClasses<'a> {
+    pub labels: &'a [&'a str],
+    pub power: &'a [&'a str],
+    pub pins: &'a [&'a str],
+}
+
+impl Default for SymbolClasses<'_> {
+    fn default() -> Self {
+        Self {
+            labels: LABEL_STEMS,
+            power: POWER_STEMS,
+            pins: PIN_STEMS,
+        }
+    }
+}
+
+impl SymbolClasses<'_> {
+    /// Returns whether the symbol of a component is a label.
+    pub fn is_label<I: AsRef<str>>(&self, component: &Component<I>) -> bool {
+        self.labels.contains(&component.symbol_stem())
+    }
+
+    /// Returns whether the symbol of a component is a power or ground
+    /// connection.
+    pub fn is_power<I: AsRef<str>>(&self, component: &Component<I>) -> bool {
+        self.power.contains(&component.symbol_stem())
+    }
+
+    /// Returns whether the symbol of a component is a pin.
+    pub fn is_pin<I: AsRef<str>>(&self, component: &Component<I>) -> bool {
+        self.pins.contains(&component.symbol_stem())
+    }
+}
+
+impl<I: AsRef<str>> Component<I> {
+    /// Returns whether the symbol is a label in [`LABEL_STEMS`], such as
+    /// `lab_pin.sym`.
+    ///
+    /// Use [`SymbolClasses`] to classify with other symbols.
+    pub fn is_label(&self) -> bool {
+        SymbolClasses::default().is_label(self)
+    }
+
+    /// Returns whether the symbol is a power or ground connection in
+    /// [`POWER_STEMS`], such as `gnd.sym`.
+    pub fn is_power(&self) -> bool {
+        SymbolClasses::default().is_power(self)
+    }
+
+    /// Returns whether the symbol is a pin in [`PIN_STEMS`], such as
+    /// `ipin.sym`.
+    pub fn is_pin(&self) -> bool {
+        SymbolClasses::default().is_pin(self)
+    }
+}
+
+impl TryFrom<&str> for PinDirection {
+    type Error = &'static str;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "in" => Ok(Self::In),
+            "out" => Ok(Self::Out),
+            "inout" => Ok(Self::Inout),
+            _ => Err("unknown pin direction"),
+        }
+    }
+}
+
+impl<I: AsRef<str>> Schematic<I> {
+    /// Classifies the schematic as a schematic or a symbol.
+    ///
+    /// Components and wires only appear in schematics. Otherwise pins (see
+    /// [`Schematic::pins`]) or a `type` attribute in the global symbol
+    /// property make a symbol. Anything else, such as an empty schematic, is
+    /// classified as a schematic.
+    pub fn kind(&self) -> SchematicKind {
+        self.classify().0
+    }
+
+    /// Returns whether the global symbol property has the attribute
+    /// `type=subcircuit`, which marks the symbol of a hierarchical schematic.
+    ///
+    /// ```
+    /// let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+    ///              K {type=subcircuit\n\
+    ///              format=\"@name @pinlist @symname\"}\n";
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    ///
+    /// assert!(schematic.is_subcircuit());
+    /// ```
+    pub fn is_subcircuit(&self) -> bool {
+        self.symbol_property
+            .as_ref()
+            .and_then(|p| p.get_value("type"))
+            == Some("subcircuit")
+    }
+
+    /// Returns the kind of the schematic with the input of the first object
+    /// deciding the kind, if any.
+    pub(crate) fn classify(&self) -> (SchematicKind, Option<&I>) {
+        if let Some(component) = self.components.first() {
+            return (SchematicKind::Schematic, Some(&component.reference));
+        }
+        if let Some(wire) = self.wires.first() {
+            return (SchematicKind::Schematic, Some(&wire.property.prop));
+        }
+
+        let pin = self.rectangles.iter().find_map(|r| {
+            let dir = r.property.get_value("dir")?;
+            PinDirection::try_from(dir).ok()?;
+            r.property.get("name")
+        });
+        let symbol_type = self.symbol_property.as_ref().and_then(|p| p.get("type"));
+        match pin.or(symbol_type) {
+            Some(input) => (SchematicKind::Symbol, Some(input)),
+            None => (SchematicKind::Schematic, None),
+        }
+    }
+}
+
+impl<I: AsRef<str> + Clone> Schematic<I> {
+    /// Returns the pins of a symbol.
+    ///
+    /// Pins are rectangles with a `name` and a `dir` attribute, which Xschem
+    /// places on layer 5. Rectangles without these attributes or with an
+    /// unknown direction are skipped.
+    pub fn pins(&self) -> Vec<Pin<I>> {
+        self.rectangles
+            .iter()
+            .filter_map(|r| {
+                let name = r.property.get("name")?;
+                let dir = r.property.get_value("dir")?.try_into().ok()?;
+                Some(Pin {
+                    name: name.clone(),
+                    dir,
+                    number: r.property.get("pinnumber").cloned(),
+                    position: r.center(),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the ports of the interface of a subcircuit.
+    ///
+    /// The ports of a symbol are its pins (see [`Schematic::pins`]). The
+    /// ports of a schematic are the `lab` attribute of its `ipin.sym`,
+    /// `opin.sym` and `iopin.sym` components, with the direction of the
+    /// symbol. Pins are returned before components, each in input order.
+    pub fn subcircuit_ports(&self) -> Vec<Port<I>> {
+        let pins = self.pins().into_iter().map(|p| Port {
+            name: p.name,
+            dir: p.dir,
+        });
+        let components = self.components.iter().filter_map(|c| {
+            let dir = match c.symbol_stem() {
+                "ipin" => PinDirection::In,
+                "opin" => PinDirection::Out,
+                "iopin" => PinDirection::Inout,
+                _ => return None,
+            };
+            Some(Port {
+                name: c.property.get("lab")?.clone(),
+                dir,
+            })
+        });
+        pins.chain(components).collect()
+    }
+}
@@ -0,0 +1,16 @@
+use crate::color::{DARK_COLORS, LIGHT_COLORS, Rgb, layer_color};
+
+#[test]
+fn default_layer_colors() {
+    // Wires.
+    assert_eq!(layer_color(1), Some(Rgb::from_hex(0x00_ccee)));
+    // Symbol graphics.
+    assert_eq!(layer_color(4), Some(Rgb::new(0x88, 0xdd, 0x00)));
+    // Pins.
+    assert_eq!(layer_color(5).unwrap().to_string(), "#bb2200");
+    assert_eq!(layer_color(22), None);
+    assert_eq!(layer_color(u64::MAX), None);
+
+    assert_eq!(LIGHT_COLORS[0], Rgb::new(0xff, 0xff, 0xff));
+    assert_eq!(DARK_COLORS[0], Rgb::new(0, 0, 0));
+}
@@ -0,0 +1,66 @@
+use crate::diff::Change;
+use crate::token::{ObjectRef, Property, Schematic, Wire};
+
+const PCB_TEST1: &str = include_str!("../../../../assets/pcb_test1.sch");
+
+#[test]
+fn schematic_diff() {
+    let old: Schematic<&str> = crate::from_str(PCB_TEST1).unwrap().into();
+    assert!(old.diff(&old).is_empty());
+
+    let mut new = old.clone();
+    new.add_object_mut(
+        Wire {
+            start: (0.0, 0.0).try_into().unwrap(),
+            end: (10.0, 0.0).try_into().unwrap(),
+            property: Property::default(),
+        }
+        .into(),
+    );
+    let u1 = new
+        .components
+        .iter_mut()
+        .find(|c| c.property.get_value("name") == Some("U1:1"))
+        .unwrap();
+    u1.position = (800.0, -440.0).try_into().unwrap();
+
+    let diff = old.diff(&new);
+    assert_eq!(diff.counts(), (1, 0, 1));
+
+    let changes: Vec<_> = diff.iter().collect();
+    assert!(matches!(
+        changes[0],
+        Change::Modified {
+            old: ObjectRef::Component(old),
+            new: ObjectRef::Component(new),
+        } if old.position == (790.0, -440.0).try_into().unwrap()
+            && new.position == (800.0, -440.0).try_into().unwrap()
+    ));
+    assert!(matches!(changes[1], Change::Added(ObjectRef::Wire(_))));
+
+    let summary = diff.to_string();
+    assert!(summary.contains("- C {74ls00.sym} 790 -440 0 0"));
+    assert!(summary.contains("+ C {74ls00.sym} 800 -440 0 0"));
+    assert!(summary.contains("+ N 0 0 10 0 {}"));
+    assert!(summary.ends_with("1 added, 0 removed, 1 modified"));
+
+    let diff = new.diff(&old);
+    assert_eq!(diff.counts(), (0, 1, 1));
+    assert!(matches!(
+        diff.into_iter().next(),
+        Some(Change::Removed(ObjectRef::Wire(_)))
+    ));
+}
+
+#[test]
+fn schematic_diff_spans() {
+    let old = crate::from_str("v {}\nN 0 0 10 0 {lab=A}\nN 5 5 6 6 {lab=B}").unwrap();
+    let new = crate::from_str("v {}\nN 1 1 2 2 {}\nN 0 0 10 0 {lab=A}\nN 5 5 6 6 {lab=B}").unwrap();
+
+    let diff = old.diff(&new);
+    assert_eq!(diff.counts(), (1, 0, 0));
+    assert!(diff.to_string().starts_with("+ N 1 1 2 2 {}\n"));
+
+    let new = crate::from_str("v {}\n\nN 0 0 10 0 {lab=A}\nN 5 5 6 6 {lab=C}").unwrap();
+    assert_eq!(old.diff(&new).counts(), (0, 0, 1));
+}
@@ -0,0 +1,160 @@
+use crate::options::DEFAULT_MAX_EMBEDDING_DEPTH;
+use crate::token::{FiniteDouble, Flip, Rotation, Vec2};
+
+fn vec2(x: f64, y: f64) -> Vec2 {
+    (x, y).try_into().unwrap()
+}
+
+#[test]
+fn flatten_embedding_sch() {
+    let input = include_str!("../../../../assets/embedding.sch");
+    let schematic = crate::from_str(input).unwrap();
+    let flat = schematic.flatten();
+
+    assert!(!flat.has_embeddings());
+    assert_eq!(flat.components.len(), 1);
+    assert_eq!(
+        flat.components[0].property,
+        schematic.components[0].property
+    );
+    assert_eq!(flat.lines.len(), 10);
+    assert_eq!(flat.rectangles.len(), 4);
+    assert_eq!(flat.arcs.len(), 1);
+    assert_eq!(flat.texts.len(), 5);
+    assert!(flat.objects().count() > schematic.objects().count());
+    assert!(flat.symbol_property.is_none());
+
+    // L 4 5 20 20 20 {} placed at 620 -810.
+    assert_eq!(flat.lines[0].start, vec2(625.0, -790.0));
+    assert_eq!(flat.lines[0].end, vec2(640.0, -790.0));
+
+    // Pins end up at the connection points of the component.
+    let pins: Vec<_> = schematic.embedded_schematics().next().unwrap().pins();
+    for (pin, rectangle) in pins.iter().zip(flat.rectangles.iter()) {
+        assert_eq!(
+            rectangle.center(),
+            schematic.components[0].transform(pin.position)
+        );
+    }
+}
+
+#[test]
+fn flatten_orientation() {
+    let input = "\
+v {xschem version=3.4.5 file_version=1.2}
+C {sym.sym} 100 0 1 1 {name=x1 embed=true}
+[
+v {xschem version=3.4.5 file_version=1.2}
+B 5 10 0 20 10 {name=p dir=in}
+A 4 10 0 5 0 90 {}
+T {a} 0 0 1 0 0.2 0.2 {}
+T {b} 0 0 2 1 0.2 0.2 {}
+]
+";
+    let flat = crate::from_str(input).unwrap().flatten();
+
+    assert_eq!(flat.rectangles[0].start, vec2(90.0, -20.0));
+    assert_eq!(flat.rectangles[0].end, vec2(100.0, -10.0));
+
+    assert_eq!(flat.arcs[0].center, vec2(100.0, -10.0));
+    assert_eq!(flat.arcs[0].start_angle, FiniteDouble(0.0));
+    assert_eq!(flat.arcs[0].sweep_angle, FiniteDouble(90.0));
+
+    assert_eq!(flat.texts[0].rotation, Rotation::Zero);
+    assert_eq!(flat.texts[0].flip, Flip::Flipped);
+    assert_eq!(flat.texts[1].rotation, Rotation::Three);
+    assert_eq!(flat.texts[1].flip, Flip::Unflipped);
+}
+
+#[test]
+fn flatten_nested() {
+    let input = "\
+v {xschem version=3.4.5 file_version=1.2}
+N 0 0 10 0 {lab=A}
+C {outer.sym} 100 0 0 0 {name=x1 embed=true}
+[
+v {xschem version=3.4.5 file_version=1.2}
+C {inner.sym} 0 50 2 0 {name=x2 embed=true}
+[
+v {xschem version=3.4.5 file_version=1.2}
+L 4 0 0 10 0 {}
+]
+]
+N 10 0 20 0 {lab=B}
+";
+    let flat = crate::from_str(input).unwrap().flatten();
+
+    assert_eq!(flat.components.len(), 2);
+    assert_eq!(flat.components[1].position, vec2(100.0, 50.0));
+    assert_eq!(flat.components[1].rotation, Rotation::Two);
+    assert!(flat.components[1].embedding.is_none());
+    assert_eq!(flat.lines.len(), 1);
+    assert_eq!(flat.lines[0].start, vec2(100.0, 50.0));
+    assert_eq!(flat.lines[0].end, vec2(90.0, 50.0));
+
+    let kinds: Vec<_> = flat.objects().map(|o| o.to_string()).collect();
+    assert!(kinds[0].starts_with("N 0 0"));
+    assert!(kinds[1].starts_with("C {outer.sym}"));
+    assert!(kinds[2].starts_with("C {inner.sym}"));
+    assert!(kinds[3].starts_with("L 4"));
+    assert!(kinds[4].starts_with("N 10 0"));
+}
+
+#[test]
+fn resolve_references() {
+    let input = "\
+v {xschem version=3.4.5 file_version=1.2}
+C {capa.sym} 100 0 0 0 {name=C1}
+C {res.sym} 200 0 0 0 {name=R1}
+C {capa.sym} 300 0 0 0 {name=C2}
+";
+    let capa = "\
+v {xschem version=3.4.5 file_version=1.2}
+K {type=capacitor}
+B 5 -2.5 -32.5 2.5 -27.5 {name=p dir=inout}
+B 5 -2.5 27.5 2.5 32.5 {name=m dir=inout}
+";
+    let schematic = crate::from_str(input).unwrap();
+    let capa = crate::from_str(capa).unwrap();
+
+    let mut loaded = Vec::new();
+    let resolved = schematic.resolve_references(|path| {
+        loaded.push(path.to_owned());
+        (path == "capa.sym").then(|| capa.clone())
+    });
+
+    assert_eq!(loaded, ["capa.sym", "res.sym", "capa.sym"]);
+    assert!(!schematic.has_embeddings());
+    assert_eq!(resolved.embedded_schematics().count(), 2);
+    assert!(resolved.components[1].embedding.is_none());
+
+    let c1 = &resolved.components[0];
+    let symbol = &c1.embedding.as_ref().unwrap().0;
+    assert_eq!(symbol, &capa);
+    assert_eq!(symbol.pins().len(), 2);
+    assert_eq!(
+        resolved.flatten().rectangles[0].center(),
+        c1.transform(symbol.pins()[0].position)
+    );
+}
+
+#[test]
+fn resolve_recursive_references() {
+    let input = "v {}\nC {loop.sym} 0 0 0 0 {name=x1}";
+    let schematic = crate::from_str(input).unwrap();
+
+    let mut calls = 0;
+    let resolved = schematic.resolve_references(|_| {
+        calls += 1;
+        Some(schematic.clone())
+    });
+
+    assert_eq!(calls, DEFAULT_MAX_EMBEDDING_DEPTH);
+    let mut depth = 0;
+    let mut current = &resolved;
+    while let Some(embedding) = &current.components[0].embedding {
+        depth += 1;
+        current = &embedding.0;
+    }
+    assert_eq!(depth, DEFAULT_MAX_EMBEDDING_DEPTH);
+}
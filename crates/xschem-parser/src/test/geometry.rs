@@ -0,0 +1,382 @@
+use crate::token::{
+    Arc, Component, Flip, Line, Polygon, Property, Rectangle, Rotation, Schematic, Vec2, Version,
+    Wire,
+};
+
+/// Asserts that two coordinates are equal up to rounding of trigonometry.
+fn assert_near(a: Vec2, b: (f64, f64)) {
+    assert!(
+        (*a.x - b.0).abs() < 1e-9 && (*a.y - b.1).abs() < 1e-9,
+        "{a:?} != {b:?}"
+    );
+}
+
+fn arc(start_angle: f64, sweep_angle: f64) -> Arc<&'static str> {
+    Arc {
+        layer: 4,
+        center: (100.0, 50.0).try_into().unwrap(),
+        radius: 10.0.try_into().unwrap(),
+        start_angle: start_angle.try_into().unwrap(),
+        sweep_angle: sweep_angle.try_into().unwrap(),
+        property: Property::default(),
+    }
+}
+
+#[test]
+fn arc_points() {
+    let quarter = arc(0.0, 90.0);
+
+    assert_near(quarter.start_point(), (110.0, 50.0));
+    assert_near(quarter.end_point(), (100.0, 40.0));
+    assert_near(
+        quarter.point_at(0.5),
+        (100.0 + 50.0f64.sqrt(), 50.0 - 50.0f64.sqrt()),
+    );
+
+    let clockwise = arc(90.0, -180.0);
+    assert_near(clockwise.point_at(0.5), (110.0, 50.0));
+    assert_near(clockwise.end_point(), (100.0, 60.0));
+}
+
+#[test]
+fn arc_degenerate_radius() {
+    let mut negative = arc(0.0, 90.0);
+    negative.radius = (-10.0).try_into().unwrap();
+    let positive = arc(0.0, 90.0);
+    assert_near(negative.start_point(), (110.0, 50.0));
+    assert_near(negative.end_point(), (100.0, 40.0));
+    assert_eq!(negative.bounding_box(), positive.bounding_box());
+
+    let mut zero = arc(30.0, 90.0);
+    zero.radius = 0.0.try_into().unwrap();
+    assert_near(zero.point_at(0.5), (100.0, 50.0));
+    assert_eq!(zero.bounding_box(), (zero.center, zero.center));
+}
+
+#[test]
+fn arc_bounding_box() {
+    let (min, max) = arc(0.0, 90.0).bounding_box();
+    assert_near(min, (100.0, 40.0));
+    assert_near(max, (110.0, 50.0));
+
+    let (min, max) = arc(45.0, 90.0).bounding_box();
+    assert_near(min, (100.0 - 50.0f64.sqrt(), 40.0));
+    assert_near(max, (100.0 + 50.0f64.sqrt(), 50.0 - 50.0f64.sqrt()));
+
+    let (min, max) = arc(-45.0, -270.0).bounding_box();
+    assert_near(min, (90.0, 40.0));
+    assert_near(max, (100.0 + 50.0f64.sqrt(), 60.0));
+
+    let (min, max) = arc(30.0, 360.0).bounding_box();
+    assert_near(min, (90.0, 40.0));
+    assert_near(max, (110.0, 60.0));
+}
+
+#[test]
+fn rectangle_bounding_box() {
+    let rectangle = Rectangle {
+        layer: 4,
+        start: (10.0, -2.5).try_into().unwrap(),
+        end: (-5.0, 7.5).try_into().unwrap(),
+        property: Property::<&str>::default(),
+    };
+
+    assert_eq!(
+        rectangle.bounding_box(),
+        (
+            (-5.0, -2.5).try_into().unwrap(),
+            (10.0, 7.5).try_into().unwrap()
+        )
+    );
+}
+
+#[test]
+fn polygon_bounding_box() {
+    let polygon = Polygon {
+        layer: 3,
+        points: vec![(2450.0, -210.0), (2460.0, -170.0), (2510.0, -210.0)]
+            .try_into()
+            .unwrap(),
+        property: Property::<&str>::default(),
+    };
+
+    assert_eq!(
+        polygon.bounding_box(),
+        Some((
+            (2450.0, -210.0).try_into().unwrap(),
+            (2510.0, -170.0).try_into().unwrap()
+        ))
+    );
+    assert_eq!(Polygon::<&str>::default().bounding_box(), None);
+}
+
+#[test]
+fn rectangle_contains() {
+    let rectangle = Rectangle {
+        layer: 4,
+        start: (10.0, -2.5).try_into().unwrap(),
+        end: (-5.0, 7.5).try_into().unwrap(),
+        property: Property::<&str>::default(),
+    };
+    let contains = |p: (f64, f64)| rectangle.contains(p.try_into().unwrap());
+
+    assert!(contains((0.0, 0.0)));
+    assert!(contains((-5.0, 7.5)));
+    assert!(!contains((11.0, 0.0)));
+    assert!(!contains((0.0, -3.0)));
+}
+
+#[test]
+fn polygon_contains() {
+    let polygon = Polygon {
+        layer: 3,
+        points: vec![(0.0, 0.0), (10.0, 0.0), (0.0, 10.0)]
+            .try_into()
+            .unwrap(),
+        property: Property::<&str>::default(),
+    };
+    let contains = |p: (f64, f64)| polygon.contains(p.try_into().unwrap());
+
+    assert!(contains((2.0, 2.0)));
+    assert!(contains((0.5, 9.0)));
+    assert!(!contains((6.0, 6.0)));
+    assert!(!contains((-1.0, 5.0)));
+    assert!(!contains((5.0, 11.0)));
+    assert!(!Polygon::<&str>::default().contains((0.0, 0.0).try_into().unwrap()));
+}
+
+#[test]
+fn wire_distance_to() {
+    let wire = Wire {
+        start: (0.0, 0.0).try_into().unwrap(),
+        end: (20.0, 0.0).try_into().unwrap(),
+        property: Property::<&str>::default(),
+    };
+    let line = Line {
+        layer: 4,
+        start: wire.start,
+        end: wire.end,
+        property: Property::<&str>::default(),
+    };
+    let distance = |p: (f64, f64)| wire.distance_to(p.try_into().unwrap());
+
+    assert!(distance((10.0, 0.0)).abs() < f64::EPSILON);
+    assert!((distance((10.0, -3.0)) - 3.0).abs() < f64::EPSILON);
+    assert!((distance((-3.0, 4.0)) - 5.0).abs() < f64::EPSILON);
+    assert!((distance((24.0, 3.0)) - 5.0).abs() < f64::EPSILON);
+    assert!((line.distance_to((24.0, 3.0).try_into().unwrap()) - 5.0).abs() < f64::EPSILON);
+
+    let point = Wire {
+        end: wire.start,
+        ..wire
+    };
+    assert!((point.distance_to((3.0, 4.0).try_into().unwrap()) - 5.0).abs() < f64::EPSILON);
+}
+
+#[test]
+fn schematic_bounding_box() {
+    let schematic = Schematic::new(Version(Property::<&str>::default()));
+
+    assert_eq!(schematic.bounding_box(), None);
+
+    let schematic = schematic
+        .add_object(
+            Wire {
+                start: (0.0, 0.0).try_into().unwrap(),
+                end: (20.0, 0.0).try_into().unwrap(),
+                property: Property::default(),
+            }
+            .into(),
+        )
+        .add_object(
+            Rectangle {
+                layer: 4,
+                start: (-5.0, -5.0).try_into().unwrap(),
+                end: (5.0, 10.0).try_into().unwrap(),
+                property: Property::default(),
+            }
+            .into(),
+        );
+
+    assert_eq!(
+        schematic.bounding_box(),
+        Some((
+            Vec2::try_from((-5.0, -5.0)).unwrap(),
+            Vec2::try_from((20.0, 10.0)).unwrap()
+        ))
+    );
+}
+
+#[test]
+fn pcb_test1_bounding_box() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let schematic = crate::from_str(input).unwrap();
+
+    assert_eq!(
+        schematic.bounding_box(),
+        Some((
+            (160.0, -730.0).try_into().unwrap(),
+            (1050.0, -30.0).try_into().unwrap()
+        ))
+    );
+}
+
+#[test]
+fn rotation_apply() {
+    let v = Vec2::try_from((2.0, 1.0)).unwrap();
+
+    assert_eq!(Rotation::Zero.apply(v), (2.0, 1.0).try_into().unwrap());
+    assert_eq!(Rotation::One.apply(v), (-1.0, 2.0).try_into().unwrap());
+    assert_eq!(Rotation::Two.apply(v), (-2.0, -1.0).try_into().unwrap());
+    assert_eq!(Rotation::Three.apply(v), (1.0, -2.0).try_into().unwrap());
+}
+
+#[test]
+fn flip_apply() {
+    let v = Vec2::try_from((2.0, 1.0)).unwrap();
+
+    assert_eq!(Flip::Unflipped.apply(v), (2.0, 1.0).try_into().unwrap());
+    assert_eq!(Flip::Flipped.apply(v), (-2.0, 1.0).try_into().unwrap());
+}
+
+#[test]
+fn component_transform() {
+    let component = Component {
+        reference: "res.sym",
+        position: (100.0, -50.0).try_into().unwrap(),
+        rotation: Rotation::One,
+        flip: Flip::Flipped,
+        extra: Vec::new(),
+        property: Property::default(),
+        embedding: None,
+    };
+
+    // Flipped to (-20, 10), rotated to (-10, -20), translated to (90, -70).
+    assert_eq!(
+        component.transform((20.0, 10.0).try_into().unwrap()),
+        (90.0, -70.0).try_into().unwrap()
+    );
+
+    let component = Component {
+        rotation: Rotation::Zero,
+        flip: Flip::Unflipped,
+        ..component
+    };
+
+    assert_eq!(
+        component.transform((20.0, 10.0).try_into().unwrap()),
+        (120.0, -40.0).try_into().unwrap()
+    );
+}
+
+#[test]
+fn vec2_arithmetic() {
+    let a = Vec2::try_from((1.0, 2.0)).unwrap();
+    let b = Vec2::try_from((4.0, -2.0)).unwrap();
+
+    assert_eq!(a + b, (5.0, 0.0).try_into().unwrap());
+    assert_eq!(a - b, (-3.0, 4.0).try_into().unwrap());
+    assert_eq!(a * 2.5, (2.5, 5.0).try_into().unwrap());
+    assert_eq!(-a, (-1.0, -2.0).try_into().unwrap());
+    assert_eq!(a.midpoint(&b), (2.5, 0.0).try_into().unwrap());
+
+    let max = Vec2::try_from((f64::MAX, f64::MIN)).unwrap();
+    assert_eq!(max + max, max);
+    assert_eq!(max * 2.0, max);
+}
+
+#[test]
+fn vec2_distance() {
+    let a = Vec2::try_from((1.0, 2.0)).unwrap();
+    let b = Vec2::try_from((4.0, -2.0)).unwrap();
+
+    assert!((a.distance(&b) - 5.0).abs() < f64::EPSILON);
+    assert!((b.distance(&a) - 5.0).abs() < f64::EPSILON);
+    assert!(a.distance(&a).abs() < f64::EPSILON);
+}
+
+#[test]
+#[should_panic = "factor is not finite"]
+fn vec2_mul_not_finite() {
+    let _ = Vec2::try_from((1.0, 2.0)).unwrap() * f64::NAN;
+}
+
+#[test]
+fn schematic_merge_offset() {
+    let tile = crate::from_str(
+        "v {xschem version=3.4.5 file_version=1.2}
+N 0 0 10 0 {lab=A}
+N 10 0 10 -20 {lab=A}
+",
+    )
+    .unwrap();
+    let mut schematic = crate::from_str(
+        "v {xschem version=3.4.5 file_version=1.2}
+C {res.sym} 0 0 0 0 {name=R1}
+",
+    )
+    .unwrap();
+
+    schematic.merge_offset(&tile, (100.0, 0.0).try_into().unwrap());
+    schematic.merge_offset(&tile, (0.0, 50.0).try_into().unwrap());
+
+    assert_eq!(schematic.components.len(), 1);
+    assert_eq!(
+        schematic.components[0].position,
+        (0.0, 0.0).try_into().unwrap()
+    );
+    let wires: Vec<_> = schematic
+        .wires
+        .iter()
+        .map(|w| (w.start, w.end, *w.property.prop.fragment()))
+        .collect();
+    let wire = |start: (f64, f64), end: (f64, f64)| {
+        (start.try_into().unwrap(), end.try_into().unwrap(), "lab=A")
+    };
+    assert_eq!(
+        wires,
+        vec![
+            wire((100.0, 0.0), (110.0, 0.0)),
+            wire((110.0, 0.0), (110.0, -20.0)),
+            wire((0.0, 50.0), (10.0, 50.0)),
+            wire((10.0, 50.0), (10.0, 30.0)),
+        ]
+    );
+    assert_eq!(schematic.order.len(), 5);
+}
+
+#[test]
+fn schematic_translate() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}
+N 0 0 10 0 {lab=A}
+C {res.sym} 10 0 1 0 {name=R1 embed=true}
+[
+v {xschem version=3.4.5 file_version=1.2}
+L 4 0 -30 0 30 {}
+]
+P 4 3 0 0 10 0 0 10 {}
+T {label} 5 5 0 0 0.4 0.4 {}
+";
+    let original = crate::from_str(input).unwrap();
+    let mut schematic = original.clone();
+    let offset = (100.0, -20.0).try_into().unwrap();
+    schematic.translate(offset);
+
+    assert_eq!(schematic.wires[0].to_string(), "N 100 -20 110 -20 {lab=A}");
+    let component = &schematic.components[0];
+    assert_eq!(component.position, (110.0, -20.0).try_into().unwrap());
+    assert_eq!(component.rotation, Rotation::One);
+    // The embedded symbol is relative to the component.
+    assert_eq!(component.embedding, original.components[0].embedding);
+    assert_eq!(
+        schematic.texts[0].position,
+        (105.0, -15.0).try_into().unwrap()
+    );
+    assert_eq!(
+        schematic.polygons[0].points[2],
+        (100.0, -10.0).try_into().unwrap()
+    );
+
+    let (min, max) = original.bounding_box().unwrap();
+    assert_eq!(schematic.bounding_box(), Some((min + offset, max + offset)));
+}
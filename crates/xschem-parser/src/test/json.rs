@@ -0,0 +1,238 @@
+use crate::token::{Property, Schematic, Version, Wire};
+
+/// Parsed JSON value, just enough to check the output.
+#[derive(Debug, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn get(&self, key: &str) -> &Value {
+        let Value::Object(members) = self else {
+            panic!("expected object, found {self:?}");
+        };
+        members
+            .iter()
+            .find_map(|(k, v)| (k == key).then_some(v))
+            .unwrap_or_else(|| panic!("missing key {key}"))
+    }
+
+    fn items(&self) -> &[Value] {
+        let Value::Array(items) = self else {
+            panic!("expected array, found {self:?}");
+        };
+        items
+    }
+}
+
+/// Strict parser of a JSON value, panicking on malformed input.
+struct Parser<'a>(&'a [u8]);
+
+impl Parser<'_> {
+    fn peek(&mut self) -> u8 {
+        while let [b' ' | b'\n' | b'\r' | b'\t', rest @ ..] = self.0 {
+            self.0 = rest;
+        }
+        *self.0.first().expect("unexpected end of input")
+    }
+
+    fn eat(&mut self, token: &str) {
+        self.peek();
+        self.0 = self
+            .0
+            .strip_prefix(token.as_bytes())
+            .unwrap_or_else(|| panic!("expected {token}"));
+    }
+
+    fn sequence(&mut self, end: u8, mut item: impl FnMut(&mut Self)) {
+        if self.peek() == end {
+            self.0 = &self.0[1..];
+            return;
+        }
+        loop {
+            item(self);
+            match self.peek() {
+                b',' => self.eat(","),
+                c if c == end => break self.0 = &self.0[1..],
+                c => panic!("unexpected {}", c as char),
+            }
+        }
+    }
+
+    fn string(&mut self) -> String {
+        self.eat("\"");
+        let mut bytes = Vec::new();
+        loop {
+            let [c, rest @ ..] = self.0 else {
+                panic!("unterminated string");
+            };
+            self.0 = rest;
+            match c {
+                b'"' => break,
+                b'\\' => {
+                    let [e, rest @ ..] = self.0 else {
+                        panic!("unterminated escape");
+                    };
+                    self.0 = rest;
+                    let c = match e {
+                        b'"' | b'\\' | b'/' => char::from(*e),
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'u' => {
+                            let (hex, rest) = self.0.split_at(4);
+                            self.0 = rest;
+                            let hex = std::str::from_utf8(hex).unwrap();
+                            char::from_u32(u32::from_str_radix(hex, 16).unwrap()).unwrap()
+                        }
+                        e => panic!("invalid escape {}", *e as char),
+                    };
+                    bytes.extend(c.to_string().bytes());
+                }
+                c if *c < b' ' => panic!("unescaped control character"),
+                c => bytes.push(*c),
+            }
+        }
+        String::from_utf8(bytes).unwrap()
+    }
+
+    fn value(&mut self) -> Value {
+        match self.peek() {
+            b'n' => {
+                self.eat("null");
+                Value::Null
+            }
+            b't' => {
+                self.eat("true");
+                Value::Bool(true)
+            }
+            b'f' => {
+                self.eat("false");
+                Value::Bool(false)
+            }
+            b'"' => Value::String(self.string()),
+            b'[' => {
+                self.eat("[");
+                let mut items = Vec::new();
+                self.sequence(b']', |p| items.push(p.value()));
+                Value::Array(items)
+            }
+            b'{' => {
+                self.eat("{");
+                let mut members = Vec::new();
+                self.sequence(b'}', |p| {
+                    let key = p.string();
+                    p.eat(":");
+                    members.push((key, p.value()));
+                });
+                Value::Object(members)
+            }
+            _ => {
+                let len = self
+                    .0
+                    .iter()
+                    .position(|c| !matches!(c, b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9'))
+                    .unwrap_or(self.0.len());
+                let (number, rest) = self.0.split_at(len);
+                self.0 = rest;
+                Value::Number(std::str::from_utf8(number).unwrap().parse().unwrap())
+            }
+        }
+    }
+}
+
+fn parse_json(s: &str) -> Value {
+    let mut parser = Parser(s.as_bytes());
+    let value = parser.value();
+    assert!(
+        parser.0.iter().all(u8::is_ascii_whitespace),
+        "trailing input"
+    );
+    value
+}
+
+#[test]
+fn json_embedding() {
+    let input = include_str!("../../../../assets/embedding.sch");
+    let schematic = crate::from_str(input).unwrap();
+    let json = parse_json(&schematic.to_json());
+
+    for key in [
+        "version",
+        "vhdl_property",
+        "symbol_property",
+        "verilog_property",
+        "spice_property",
+        "tedax_property",
+        "texts",
+        "lines",
+        "rectangles",
+        "polygons",
+        "arcs",
+        "wires",
+        "components",
+        "unknown_objects",
+        "order",
+    ] {
+        json.get(key);
+    }
+    assert_eq!(json.get("wires").items().len(), schematic.wires.len());
+    assert_eq!(json.get("order").items().len(), schematic.order.len());
+
+    let component = &json.get("components").items()[0];
+    assert_eq!(
+        component.get("reference"),
+        &Value::String(schematic.components[0].reference.to_string())
+    );
+    let embedding = component.get("embedding");
+    let symbol = schematic.components[0].embedding.as_ref().unwrap();
+    assert_eq!(embedding.get("lines").items().len(), symbol.lines.len());
+    assert!(matches!(
+        embedding.get("symbol_property").get("attrs"),
+        Value::Object(_)
+    ));
+}
+
+#[test]
+fn json_escaping() {
+    let prop = "lab=\"a\\\\b\"\nname=\u{1}\t";
+    let schematic = Schematic::new(Version(Property::default())).add_object(
+        Wire {
+            start: (0.0, -0.5).try_into().unwrap(),
+            end: (1e-3, 20.0).try_into().unwrap(),
+            property: Property {
+                prop,
+                attrs: [("lab", "\"a\\\\b\"")].into(),
+            },
+        }
+        .into(),
+    );
+    let json = schematic.to_json();
+    assert!(json.contains(r#""prop":"lab=\"a\\\\b\"\nname=\u0001\t""#));
+
+    let json = parse_json(&json);
+    assert_eq!(
+        json.get("version").get("prop"),
+        &Value::String(String::new())
+    );
+    assert_eq!(json.get("vhdl_property"), &Value::Null);
+    let wire = &json.get("wires").items()[0];
+    assert_eq!(
+        wire.get("property").get("prop"),
+        &Value::String(prop.into())
+    );
+    assert_eq!(
+        wire.get("property").get("attrs").get("lab"),
+        &Value::String("\"a\\\\b\"".into())
+    );
+    assert_eq!(wire.get("start").get("y"), &Value::Number(-0.5));
+    assert_eq!(wire.get("end").get("x"), &Value::Number(1e-3));
+    assert_eq!(json.get("order").items(), [Value::String("Wire".into())]);
+}
@@ -1,2 +1,25 @@
+/// Example schematics and symbols of the repository.
+const ASSETS: [&str; 4] = [
+    include_str!("../../../../assets/7805.sym"),
+    include_str!("../../../../assets/embedding.sch"),
+    include_str!("../../../../assets/pcb_test1.sch"),
+    include_str!("../../../../assets/pmos.sym"),
+];
+
+mod color;
+mod diff;
+mod flatten;
+mod geometry;
+mod json;
+mod net;
+mod netlist;
+#[cfg(feature = "rayon")]
+mod parallel;
 mod parse;
+mod sort;
+mod svg;
+mod symbol;
 mod token;
+mod validate;
+mod visit;
+mod write;
@@ -0,0 +1,101 @@
+use crate::token::{Property, Schematic, Version, Wire};
+
+fn wire(start: (f64, f64), end: (f64, f64)) -> Wire<&'static str> {
+    Wire {
+        start: start.try_into().unwrap(),
+        end: end.try_into().unwrap(),
+        property: Property::default(),
+    }
+}
+
+#[test]
+fn schematic_connected_components() {
+    let schematic = Schematic::new(Version(Property::default()))
+        .add_object(wire((0.0, 0.0), (10.0, 0.0)).into())
+        .add_object(wire((50.0, 50.0), (60.0, 50.0)).into())
+        .add_object(wire((10.0, 0.0), (10.0, -0.0)).into())
+        .add_object(wire((10.0, 20.0), (10.0, 0.0)).into());
+
+    assert_eq!(schematic.wire_segments().count(), 4);
+    assert_eq!(
+        schematic.connected_components(),
+        vec![
+            vec![
+                &schematic.wires[0],
+                &schematic.wires[2],
+                &schematic.wires[3]
+            ],
+            vec![&schematic.wires[1]],
+        ]
+    );
+}
+
+#[test]
+fn pcb_test1_connected_components() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let schematic = crate::from_str(input).unwrap();
+
+    let groups = schematic.connected_components();
+    assert_eq!(groups.len(), 10);
+    assert_eq!(groups.iter().map(Vec::len).sum::<usize>(), 20);
+    // All wires in a group carry the same net label.
+    assert!(groups.iter().all(|g| {
+        g.iter().all(|w| {
+            w.property.get("lab").map(|l| *l.fragment())
+                == g[0].property.get("lab").map(|l| *l.fragment())
+        })
+    }));
+}
+
+#[test]
+fn schematic_net_labels() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}
+N 0 0 10 0 {lab=IN}
+N 10 0 10 20 {}
+N 50 50 60 50 {lab=\"OUT\"}
+C {res.sym} 10 20 0 0 {name=R1}
+";
+    let schematic = crate::from_str(input).unwrap();
+
+    let labels: Vec<_> = schematic
+        .net_labels()
+        .map(|(label, p)| (*label.fragment(), p))
+        .collect();
+    assert_eq!(
+        labels,
+        vec![
+            ("IN", (0.0, 0.0).try_into().unwrap()),
+            ("\"OUT\"", (50.0, 50.0).try_into().unwrap()),
+        ]
+    );
+}
+
+#[test]
+fn schematic_net_at_component_pin() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}
+N 0 0 0 -30 {}
+N 0 -30 0 -60 {lab=VDD}
+N 100 0 100 30 {}
+C {lab_pin.sym} 100 30 0 0 {name=p1 lab=OUT}
+C {res.sym} 30 0 1 0 {name=R1}
+";
+    let schematic = crate::from_str(input).unwrap();
+    let resistor = &schematic.components[1];
+
+    // Pins of `res.sym` are at (0, -30) and (0, 30) in symbol coordinates,
+    // rotating by 90 degrees maps them to (60, 0) and (0, 0).
+    let plus = resistor.transform((0.0, 30.0).try_into().unwrap());
+    assert_eq!(plus, (0.0, 0.0).try_into().unwrap());
+    let net = schematic.net_at(plus);
+    assert_eq!(net.map(|l| *l.fragment()), Some("VDD"));
+
+    let minus = resistor.transform((0.0, -30.0).try_into().unwrap());
+    assert_eq!(schematic.net_at(minus), None);
+
+    let net = schematic.net_at((100.0, 0.0).try_into().unwrap());
+    assert_eq!(net.map(|l| *l.fragment()), Some("OUT"));
+    let net = schematic.net_at((100.0, 30.0).try_into().unwrap());
+    assert_eq!(net.map(|l| *l.fragment()), Some("OUT"));
+    // Touching the middle of a wire does not connect.
+    assert_eq!(schematic.net_at((0.0, -10.0).try_into().unwrap()), None);
+}
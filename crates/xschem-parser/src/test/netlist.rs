@@ -0,0 +1,73 @@
+use crate::netlist::NetlistLine;
+
+const RESISTOR: &str = r#"v {xschem version=3.4.5 file_version=1.2}
+K {type=resistor
+format="@name @pinlist @value"
+template="name=R1 value=1k"}
+B 5 -2.5 -32.5 2.5 -27.5 {name=P dir=inout}
+B 5 -2.5 27.5 2.5 32.5 {name=M dir=inout}
+"#;
+
+const LABEL: &str = r"v {xschem version=3.4.5 file_version=1.2}
+K {type=label}
+B 5 -1.25 -1.25 1.25 1.25 {name=p dir=in}
+";
+
+#[test]
+fn two_resistors_netlist() {
+    let input = r"v {xschem version=3.4.5 file_version=1.2}
+N 0 -30 0 -60 {lab=VIN}
+N 0 30 0 60 {}
+N 0 60 100 60 {lab=MID}
+C {res.sym} 0 0 0 0 {name=R1 value=1k}
+C {res.sym} 100 90 0 0 {name=R2 value=2k}
+C {lab_pin.sym} 100 120 0 0 {name=l1 lab=GND}
+C {res.sym} 200 0 1 0 {name=R3 value=3k}
+";
+    let schematic = crate::from_str(input).unwrap();
+    let resistor = crate::from_str(RESISTOR).unwrap();
+    let label = crate::from_str(LABEL).unwrap();
+
+    let netlist: Vec<_> = schematic
+        .to_spice_netlist(|c| match *c.reference.fragment() {
+            "res.sym" => Some(&resistor),
+            "lab_pin.sym" => Some(&label),
+            _ => None,
+        })
+        .into_iter()
+        .map(|l| NetlistLine {
+            name: l.name.map(|n| *n.fragment()),
+            reference: *l.reference.fragment(),
+            nets: l.nets,
+            format: l.format,
+            template: l.template,
+        })
+        .collect();
+
+    let resistor = |name, nets: [&str; 2]| NetlistLine {
+        name: Some(name),
+        reference: "res.sym",
+        nets: nets.map(str::to_owned).to_vec(),
+        format: Some("@name @pinlist @value".to_owned()),
+        template: Some("name=R1 value=1k".to_owned()),
+    };
+    assert_eq!(
+        netlist,
+        vec![
+            resistor("R1", ["VIN", "MID"]),
+            resistor("R2", ["MID", "GND"]),
+            resistor("R3", ["#net1", "#net2"]),
+        ]
+    );
+}
+
+#[test]
+fn unknown_symbol_netlist() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}\nC {res.sym} 0 0 0 0 {name=R1}\n";
+    let schematic = crate::from_str(input).unwrap();
+
+    let netlist = schematic.to_spice_netlist(|_| None::<&crate::token::Schematic<&str>>);
+    assert_eq!(netlist.len(), 1);
+    assert!(netlist[0].nets.is_empty());
+    assert_eq!(netlist[0].format, None);
+}
@@ -0,0 +1,139 @@
+use crate::Span;
+use crate::options::{ObjectMask, ParseOptions};
+use crate::parallel::{CHUNK_SIZE, schematic_in_chunks};
+use crate::test::ASSETS;
+
+/// Chunk sizes to parse with, splitting every object or using the real size.
+const CHUNK_SIZES: [usize; 2] = [1, CHUNK_SIZE];
+
+#[test]
+fn parallel_assets() {
+    for input in ASSETS {
+        let expected = crate::from_str(input).unwrap();
+        let result = crate::parse_parallel(input).unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(result.order, expected.order);
+
+        let crlf_input = input.replace('\n', "\r\n");
+        let expected = crate::from_str(&crlf_input).unwrap();
+        let result = crate::parse_parallel(&crlf_input).unwrap();
+        assert_eq!(result, expected);
+    }
+}
+
+#[test]
+fn parallel_large() {
+    for input in ASSETS {
+        // Repeat the objects until there are several chunks of the real size.
+        let (header, body) = input.split_once('\n').unwrap();
+        let repeat = 4 * CHUNK_SIZE / body.len() + 1;
+        let input = format!("{header}\n{}", format!("{body}\n").repeat(repeat));
+
+        let expected = crate::from_str(&input).unwrap();
+        let result = crate::parse_parallel(&input).unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(result.order, expected.order);
+
+        let error_input = format!("{input}N 0 0 x 0 {{}}\n");
+        let expected = crate::from_str(&error_input).unwrap_err();
+        let result = crate::parse_parallel(&error_input).unwrap_err();
+        assert_eq!(result, expected);
+    }
+}
+
+#[test]
+fn parallel_grouping() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+                 K {type=subcircuit\n\
+                 format=\"@name @pinlist\"\n\
+                 L 4 0 0 10 0 \\{\\}}\n\
+                 T {multi\n\
+                 line} 0 0 0 0 0.2 0.2 {}\n\
+                 N 0 0 10 0 {} N 10 0 10 10 {}\n\
+                 C {sym.sym} 0 0 0 0 {name=x1}\n\
+                 [\n\
+                 v {xschem version=3.4.5 file_version=1.2}\n\
+                 C {inner.sym} 0 0 0 0 {}\n\
+                 [\n\
+                 v {}\n\
+                 B 5 0 0 1 1 {name=[a]}\n\
+                 ]\n\
+                 ]\n\
+                 \n\
+                 L 4 0 0 10 0 {}\n";
+    let expected = crate::from_str(input).unwrap();
+    for chunk_size in CHUNK_SIZES {
+        let result =
+            schematic_in_chunks(Span::new(input), &ParseOptions::default(), chunk_size).unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(result.order, expected.order);
+    }
+}
+
+#[test]
+fn parallel_continued_fields() {
+    let inputs = [
+        "v {}\nN 0 0\n10 0 {}",
+        "v {}\nC {res.sym}\n0 0 0 0 {name=R1}",
+        "v {}\nL 4 0 0 10 0\n{}",
+        "v {}\nT {a} 0 0 0 0 0.2 0.2\n{}",
+    ];
+    for input in inputs {
+        let expected = crate::from_str(input).unwrap();
+        for chunk_size in CHUNK_SIZES {
+            let result =
+                schematic_in_chunks(Span::new(input), &ParseOptions::default(), chunk_size)
+                    .unwrap();
+            assert_eq!(result, expected, "{input:?}");
+        }
+    }
+
+    // Continued objects following a full chunk, at every offset of the chunk end.
+    for padding in 0..40 {
+        let mut input = format!("v {{}}\n{}", "N 0 0 10 0 {}\n".repeat(CHUNK_SIZE / 14));
+        input.push_str(&" ".repeat(padding));
+        input.push_str(&"C {res.sym}\n0 0 0 0 {name=R1}\n".repeat(4));
+        let expected = crate::from_str(&input).unwrap();
+        let result = crate::parse_parallel(&input).unwrap();
+        assert_eq!(result, expected, "{padding}");
+    }
+}
+
+#[test]
+fn parallel_errors() {
+    let inputs = [
+        "v []",
+        "v {}N 0 0 10 0 {}",
+        "v {}\nN 0 0 10 0 {}\nN 0 0 x 0 {}\nN 0 0 y 0 {}",
+        "v {}\nN 0 0 10 0 {}X\nL 4 0 0 1 0 {}",
+        "v {}\nX unknown\nL 4 0 0 1 0 {}",
+        "v {}\nL 4 0 0 1 0 {}\n[\nv {}\n]",
+        "v {}\nL 4 0 0 1 0 {}\n]",
+        "v {}\n123 garbage\nN 0 0 1 1 {}",
+        "v {}\nC {sym.sym} 0 0 0 0 {}\n[\nv {}\nN 0 0 x 0 {}\n]",
+        "v {}\nT {unterminated 0 0 0 0 0.2 0.2 {}\nL 4 0 0 1 0 {}",
+        "v {}\nT {escaped \\",
+    ];
+    for input in inputs {
+        let expected = crate::from_str(input).unwrap_err();
+        for chunk_size in CHUNK_SIZES {
+            let result =
+                schematic_in_chunks(Span::new(input), &ParseOptions::default(), chunk_size)
+                    .unwrap_err();
+            assert_eq!(result, expected, "{input:?}");
+        }
+    }
+}
+
+#[test]
+fn parallel_keep() {
+    let options = ParseOptions::new().keep(ObjectMask::WIRE | ObjectMask::COMPONENT);
+    for input in ASSETS {
+        let expected = crate::parse_with_options(Span::new(input), &options).unwrap();
+        for chunk_size in CHUNK_SIZES {
+            let result = schematic_in_chunks(Span::new(input), &options, chunk_size).unwrap();
+            assert_eq!(result, expected);
+            assert_eq!(result.order, expected.order);
+        }
+    }
+}
@@ -1,15 +1,24 @@
+use std::borrow::Cow;
+use std::path::Path;
+
 use nom::character::complete::{alpha1, digit1};
 use nom::error::ErrorKind;
 use nom::sequence::preceded;
 use nom::{Err, Parser};
 
+use crate::options::{self, ObjectMask, ParseOptions};
 use crate::parse::{
-    arc_object, attributes, component_instance, key_value, line_object, polygon_object, property,
-    rectangle_object, schematic_full, text_object, try_skip, version_object, wire_object,
+    ESCAPED_CHARS, ESCAPED_VALUE_CHARS, ObjectStream, arc_object, attributes, component_instance,
+    key_value, line_object, object_fields, object_fields_with_options, polygon_object, property,
+    rectangle_object, schematic_full, text_object, try_skip, unescape, unescape_into,
+    version_object, wire_object,
 };
+use crate::test::ASSETS;
 use crate::token::{
-    Arc, Component, Line, Polygon, Property, Rectangle, Rotation, Text, Version, Wire,
+    Arc, Component, Field, FileSchematic, Flip, Line, Object, ObjectKind, Polygon, Property,
+    Rectangle, Rotation, Schematic, Text, UnknownObject, Version, Wire,
 };
+use crate::{Span, error};
 
 #[test]
 fn parse_try_skip() {
@@ -47,7 +56,7 @@ fn parse_key_value() {
     );
     assert_eq!(
         key_value::<&str, (&str, ErrorKind)>(r#"key="""#),
-        Ok(("", ("key", "")))
+        Ok(("", ("key", r#""""#)))
     );
     assert_eq!(
         key_value::<&str, (&str, ErrorKind)>("=val"),
@@ -59,15 +68,15 @@ fn parse_key_value() {
     );
     assert_eq!(
         key_value::<&str, (&str, ErrorKind)>(r#"key="\{val\}""#),
-        Ok(("", ("key", r"\{val\}")))
+        Ok(("", ("key", r#""\{val\}""#)))
     );
     assert_eq!(
         key_value::<&str, (&str, ErrorKind)>(r#"key="\\"val\\"""#),
-        Ok(("", ("key", r#"\\"val\\""#)))
+        Ok(("", ("key", r#""\\"val\\"""#)))
     );
     assert_eq!(
         key_value::<&str, (&str, ErrorKind)>(r#"key="\\val""#),
-        Ok(("", ("key", r"\\val")))
+        Ok(("", ("key", r#""\\val""#)))
     );
 }
 
@@ -77,6 +86,10 @@ fn parse_attributes() {
         attributes::<&str, (&str, ErrorKind)>("key=val"),
         Ok(("", [("key", "val")].into()))
     );
+    assert_eq!(
+        attributes::<&str, (&str, ErrorKind)>(r#"format="spice netlist" k=v"#),
+        Ok(("", [("format", r#""spice netlist""#), ("k", "v")].into()))
+    );
     assert_eq!(
         attributes::<&str, (&str, ErrorKind)>("key=val k=v"),
         Ok(("", [("key", "val"), ("k", "v")].into()))
@@ -85,6 +98,47 @@ fn parse_attributes() {
         attributes::<&str, (&str, ErrorKind)>("nokey k=v test"),
         Ok(("", [("k", "v")].into()))
     );
+    assert_eq!(
+        attributes::<&str, (&str, ErrorKind)>("name=Ωµ_1\tlab=ä-ñ\nk=v"),
+        Ok(("", [("name", "Ωµ_1"), ("lab", "ä-ñ"), ("k", "v")].into()))
+    );
+    assert_eq!(
+        attributes::<&str, (&str, ErrorKind)>("sim.model=foo spice-ignore=true"),
+        Ok(("", [("sim.model", "foo"), ("spice-ignore", "true")].into()))
+    );
+    assert_eq!(
+        attributes::<&[u8], (&[u8], ErrorKind)>("name=à1 k=v".as_bytes()),
+        Ok((
+            &b""[..],
+            [(&b"name"[..], "à1".as_bytes()), (&b"k"[..], &b"v"[..])].into()
+        ))
+    );
+}
+
+#[test]
+fn parse_multi_line_value() {
+    assert_eq!(
+        attributes::<&str, (&str, ErrorKind)>("type=subcircuit\nformat=\"line1\nline2\"\nk=v"),
+        Ok((
+            "",
+            [
+                ("type", "subcircuit"),
+                ("format", "\"line1\nline2\""),
+                ("k", "v")
+            ]
+            .into()
+        ))
+    );
+
+    let input = "v {xschem version=3.4.5 file_version=1.2}\r\n\
+                 K {verilog_format=\"assign \\\\\"a\\\\\" = b;\r\nassign c = d;\"\r\ntemplate=\"name=x1\"}";
+    let schematic = crate::from_str(input).unwrap();
+    let property = schematic.symbol_property.unwrap();
+    assert_eq!(
+        property.get_unescaped("verilog_format").as_deref(),
+        Some("assign \"a\" = b;\r\nassign c = d;")
+    );
+    assert_eq!(property.get_value("template"), Some("name=x1"));
 }
 
 #[test]
@@ -151,6 +205,46 @@ fn parse_version_object() {
     );
 }
 
+#[test]
+fn parse_property_comments() {
+    let (_, version) = version_object::<&str, (&str, ErrorKind)>(
+        "v {xschem version=3.4.5 file_version=1.2\n* copyright info\n}",
+    )
+    .unwrap();
+    assert_eq!(version.0.comments(), ["xschem", "* copyright info"]);
+
+    let (_, notes) =
+        property::<&str, (&str, ErrorKind)>(r#"{ a=1 -- note -- b="x y" c=}"#).unwrap();
+    assert_eq!(notes.comments(), ["-- note --", "c="]);
+
+    let (_, name) = property::<&str, (&str, ErrorKind)>("{name=R1}").unwrap();
+    assert!(name.comments().is_empty());
+}
+
+#[test]
+fn parse_attribute_spans() {
+    let input = Span::new("{name=R1 value=\"10 k\"\nname=R2 junk}");
+    let (_, property) = property::<Span, (Span, ErrorKind)>(input).unwrap();
+    let spans = property.attribute_spans();
+
+    let fragments: Vec<_> = spans
+        .iter()
+        .map(|(k, v)| (*k.fragment(), *v.fragment()))
+        .collect();
+    assert_eq!(
+        fragments,
+        [("name", "R1"), ("value", "\"10 k\""), ("name", "R2")]
+    );
+
+    let (key, value) = &spans[1];
+    assert_eq!((key.location_line(), key.get_column()), (1, 10));
+    assert_eq!((value.location_line(), value.get_column()), (1, 16));
+    assert_eq!(value.location_offset(), 15);
+
+    let (_, value) = &spans[2];
+    assert_eq!((value.location_line(), value.get_column()), (2, 6));
+}
+
 #[test]
 fn parse_text_object() {
     assert_eq!(
@@ -268,12 +362,37 @@ fn parse_polygon_object() {
     );
     assert_eq!(
         polygon_object::<&str, (&str, ErrorKind)>("P 3 2 0 0 {}",),
-        Err(Err::Failure(("{}", ErrorKind::Char))),
+        Err(Err::Failure(("2 0 0 {}", ErrorKind::Count))),
     );
     assert_eq!(
         polygon_object::<&str, (&str, ErrorKind)>("P 3 2 0 0 1 {}",),
-        Err(Err::Failure(("{}", ErrorKind::Char))),
+        Err(Err::Failure(("2 0 0 1 {}", ErrorKind::Count))),
+    );
+}
+
+#[test]
+fn parse_polygon_point_count() {
+    let error = crate::from_str("v {}\nP 3 3 0 0 10 0 {}").unwrap_err();
+    assert_eq!(
+        error.err.kind,
+        error::ErrorKind::PolygonPoints {
+            declared: 3,
+            found: 2
+        }
+    );
+    assert_eq!(
+        error.err.kind.to_string(),
+        "polygon declared 3 points but found 2"
     );
+    assert_eq!(error.byte_range(), 9..10);
+
+    let error = crate::from_str("v {}\nP 3 1 0 0 10 0 10 10 {}").unwrap_err();
+    assert_eq!(
+        error.err.kind.to_string(),
+        "polygon declared 1 points but found 3"
+    );
+    assert_eq!(error.err.kind.code(), "polygon-point-count");
+    assert_eq!(error.byte_range(), 9..10);
 }
 
 #[test]
@@ -297,7 +416,7 @@ fn parse_arc_object() {
 #[test]
 fn parse_component_instance() {
     assert_eq!(
-        component_instance::<&str, (&str, ErrorKind)>("C {capa.sym} 890 -160 0 0 {name=C4}",),
+        component_instance::<&str, (&str, ErrorKind)>("C {capa.sym} 890 -160 0 0 {name=C4}"),
         Ok((
             "",
             Component {
@@ -305,6 +424,7 @@ fn parse_component_instance() {
                 position: (890.0, -160.0).try_into().unwrap(),
                 rotation: Rotation::Zero,
                 flip: false.into(),
+                extra: Vec::new(),
                 property: Property {
                     prop: "name=C4",
                     attrs: [("name", "C4")].into()
@@ -315,6 +435,17 @@ fn parse_component_instance() {
     );
 }
 
+#[test]
+fn parse_component_extra_tokens() {
+    let input = "v {xschem version=3.5.0 file_version=1.3}\nC {res.sym} 0 0 0 0 foo 1 {name=R1}";
+    let schematic = schematic_full::<&str, (&str, ErrorKind)>(input).unwrap();
+    assert_eq!(schematic.components[0].extra, ["foo", "1"]);
+    assert_eq!(schematic.to_string(), input);
+
+    let input = "v {xschem version=3.4.5 file_version=1.2}\nC {res.sym} 0 0 0 0 foo {name=R1}";
+    assert!(schematic_full::<&str, (&str, ErrorKind)>(input).is_err());
+}
+
 #[test]
 fn parse_7805_sym() {
     let input = include_str!("../../../../assets/7805.sym");
@@ -342,3 +473,952 @@ fn parse_pmos_sym() {
     let result = schematic_full::<&str, (&str, ErrorKind)>(input);
     assert!(result.is_ok(), "parse error: {result:?}");
 }
+
+#[test]
+fn parse_strict_file_version() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}";
+    assert!(crate::from_str_strict(input).is_ok());
+
+    let input = "v {xschem version=3.4.5 file_version=1.10}\nN 0 0 1 1 {}";
+    let result = crate::from_str_strict(input);
+    let error = result.unwrap_err();
+    assert_eq!(
+        error.err.kind,
+        error::ErrorKind::FileVersion {
+            version: (1, 10),
+            max: (1, 2),
+        }
+    );
+    assert_eq!(error.err.input.location_offset(), 37);
+    assert_eq!(error.byte_range(), 37..41);
+    assert_eq!(error.context.len(), 1);
+    assert_eq!(error.context[0].name, "version");
+    let plain = error.to_plain_string();
+    assert!(
+        plain.contains(" 1 | v {xschem version=3.4.5 file_version=1.10}\n"),
+        "{plain}"
+    );
+
+    assert!(crate::from_str(input).is_ok());
+}
+
+#[test]
+fn parse_with_options() {
+    let input = "v {xschem version=3.4.5 file_version=1.1}";
+    let options = ParseOptions::new().max_file_version(Some((1, 0)));
+
+    assert!(crate::parse_with_options(Span::new(input), &ParseOptions::new()).is_ok());
+
+    let error = crate::parse_with_options(Span::new(input), &options).unwrap_err();
+    assert_eq!(
+        error.err.kind,
+        error::ErrorKind::FileVersion {
+            version: (1, 1),
+            max: (1, 0),
+        }
+    );
+}
+
+/// Returns a schematic with `depth` nested embedded symbols.
+fn nested_embeddings(depth: usize) -> String {
+    (0..depth).fold("v {}".to_string(), |inner, _| {
+        format!("v {{}}\nC {{a.sym}} 0 0 0 0 {{}}\n[\n{inner}\n]")
+    })
+}
+
+#[test]
+fn parse_max_embedding_depth() {
+    let input = nested_embeddings(3);
+    let options = ParseOptions::new().max_embedding_depth(Some(3));
+    assert!(crate::parse_with_options(Span::new(&input), &options).is_ok());
+
+    let options = ParseOptions::new().max_embedding_depth(Some(2));
+    let error = crate::parse_with_options(Span::new(&input), &options).unwrap_err();
+    assert_eq!(error.err.kind, error::ErrorKind::EmbeddingDepth { max: 2 });
+    assert_eq!(error.err.input.location_line(), 9);
+
+    let input = nested_embeddings(options::DEFAULT_MAX_EMBEDDING_DEPTH + 1);
+    let error = crate::from_str(&input).unwrap_err();
+    assert_eq!(
+        error.err.kind,
+        error::ErrorKind::EmbeddingDepth {
+            max: options::DEFAULT_MAX_EMBEDDING_DEPTH
+        }
+    );
+}
+
+#[test]
+fn parse_keep() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let options = ParseOptions::new().keep(ObjectMask::WIRE | ObjectMask::COMPONENT);
+    let schematic = crate::parse_with_options(Span::new(input), &options).unwrap();
+
+    assert_eq!(schematic.wires.len(), 20);
+    assert_eq!(schematic.components.len(), 24);
+    assert!(schematic.texts.is_empty());
+    assert!(schematic.lines.is_empty());
+    assert!(schematic.rectangles.is_empty());
+    assert!(schematic.polygons.is_empty());
+    assert!(schematic.arcs.is_empty());
+    assert_eq!(schematic.order.len(), 44);
+    assert_eq!(schematic.version, crate::from_str(input).unwrap().version);
+
+    let objects = ObjectStream::<_, crate::error::Error<_>>::new(Span::new(input), options.clone())
+        .unwrap()
+        .map(Result::unwrap)
+        .filter(|o| {
+            matches!(
+                o,
+                Object::Arc(_)
+                    | Object::Line(_)
+                    | Object::Polygon(_)
+                    | Object::Rectangle(_)
+                    | Object::Text(_)
+            )
+        })
+        .count();
+    assert_eq!(objects, 0);
+
+    // Dropped objects must still be valid.
+    let input = "v {}\nN 0 0 10 0 {}\nL 4 0 x 10 0 {}";
+    assert!(crate::parse_with_options(Span::new(input), &options).is_err());
+
+    let options = ParseOptions::new().keep(ObjectMask::NONE);
+    let input = input.replace('x', "0");
+    let schematic = crate::parse_with_options(Span::new(&input), &options).unwrap();
+    assert!(schematic.objects().next().is_none());
+}
+
+#[test]
+fn parse_unknown_objects() {
+    let input = "v {}\nN 0 0 10 0 {}\nZ {foo=bar}\nY 1 two {}\nL 4 0 0 10 0 {}";
+    assert!(crate::from_str(input).is_err());
+
+    let options = ParseOptions::new().unknown_objects(true);
+    let schematic = crate::parse_with_options(input, &options).unwrap();
+
+    assert_eq!(
+        schematic.unknown_objects.0,
+        [
+            UnknownObject {
+                tag: "Z",
+                tokens: vec![],
+                property: Property {
+                    prop: "foo=bar",
+                    attrs: [("foo", "bar")].into(),
+                },
+            },
+            UnknownObject {
+                tag: "Y",
+                tokens: vec!["1", "two"],
+                property: Property::default(),
+            },
+        ]
+    );
+    assert_eq!(
+        schematic.order,
+        [
+            ObjectKind::Wire,
+            ObjectKind::Unknown,
+            ObjectKind::Unknown,
+            ObjectKind::Line,
+        ]
+    );
+    assert_eq!(schematic.to_string(), input);
+
+    // Errors of known objects are not hidden by unknown objects.
+    let invalid = "v {}\nN 0 0 x 0 {}";
+    assert_eq!(
+        crate::parse_with_options(invalid, &options).unwrap_err(),
+        crate::parse_with_options(invalid, &ParseOptions::new()).unwrap_err(),
+    );
+    assert!(crate::parse_with_options("v {}\nZ 1 2", &options).is_err());
+    // A line without a property does not take the next object as tokens.
+    let lone = Span::new("v {}\nZ 1 2\nN 0 0 10 0 {lab=A}");
+    let error = crate::parse_with_options(lone, &options).unwrap_err();
+    assert_eq!(error.line_column(), (2, 6));
+    assert!(crate::parse_with_options("v {}\n1 {}", &options).is_err());
+
+    let options = options.keep(ObjectMask::WIRE | ObjectMask::LINE);
+    let schematic = crate::parse_with_options(input, &options).unwrap();
+    assert!(schematic.unknown_objects.is_empty());
+    assert_eq!(schematic.objects().count(), 2);
+}
+
+#[test]
+fn parse_decimal_comma() {
+    let input = "v {}\nL 0 1,5 2,5 0 0 {}";
+    assert!(crate::from_str(input).is_err());
+
+    let options = ParseOptions::new().decimal_comma(true);
+    let schematic = crate::parse_with_options(input, &options).unwrap();
+    assert_eq!(schematic.lines[0].start, (1.5, 2.5).try_into().unwrap());
+    assert_eq!(
+        schematic,
+        crate::parse_with_options("v {}\nL 0 1.5 2.5 0 0 {}", &ParseOptions::new()).unwrap()
+    );
+
+    let input = "v {}\n\
+                 A 4 -1,5 0 ,5 0 1,8e2 {}\n\
+                 B 5 0,25 0 1. 1 {name=a,b}\n\
+                 P 4 2 0 0,5 1,0 1 {}\n\
+                 T {1,5} 0 0,5 0 0 0,2 0,2 {}\n\
+                 N 0 0 1,5e1 0 {lab=A}\n\
+                 C {sym.sym} 0,5 0 0 0 {} [\n\
+                 v {}\n\
+                 N 0 0 0,5 0 {}\n\
+                 ]";
+    let expected = "v {}\n\
+                    A 4 -1.5 0 .5 0 1.8e2 {}\n\
+                    B 5 0.25 0 1. 1 {name=a,b}\n\
+                    P 4 2 0 0.5 1.0 1 {}\n\
+                    T {1,5} 0 0.5 0 0 0.2 0.2 {}\n\
+                    N 0 0 1.5e1 0 {lab=A}\n\
+                    C {sym.sym} 0.5 0 0 0 {} [\n\
+                    v {}\n\
+                    N 0 0 0.5 0 {}\n\
+                    ]";
+    assert_eq!(
+        crate::parse_with_options(input, &options).unwrap(),
+        crate::parse_with_options(expected, &ParseOptions::new()).unwrap()
+    );
+    assert_eq!(
+        crate::parse_with_options(expected, &options).unwrap(),
+        crate::parse_with_options(expected, &ParseOptions::new()).unwrap()
+    );
+
+    assert!(crate::parse_with_options("v {}\nN 0 0 1,,5 0 {}", &options).is_err());
+    assert!(crate::parse_with_options("v {}\nN 0 0 1e400 0 {}", &options).is_err());
+}
+
+#[test]
+fn parse_partial() {
+    let input = "\
+v {xschem version=3.4.5 file_version=1.2}
+N 0 0 10 0 {lab=A}
+C {res.sym} 0 0 0 0 {name=R1}
+L 4 0 0 {}
+N 10 0 20 0 {lab=B}";
+    let (schematic, error) = crate::parse_partial(input);
+    assert_eq!(schematic.wires.len(), 1);
+    assert_eq!(schematic.wires[0].property.get_value("lab"), Some("A"));
+    assert_eq!(schematic.components.len(), 1);
+    assert_eq!(error, crate::from_str(input).err());
+
+    let input = "v {xschem version=3.4.5 file_version=1.2}\nN 0 0 10 0 {lab=A}\n";
+    let (schematic, error) = crate::parse_partial(input);
+    assert_eq!(Ok(schematic), crate::from_str(input));
+    assert!(error.is_none());
+
+    let (schematic, error) = crate::parse_partial("N 0 0 10 0 {}");
+    assert!(schematic.objects().next().is_none());
+    assert_eq!(error.unwrap().err.kind, error::ErrorKind::MissingVersion);
+}
+
+#[test]
+fn parse_byte_order_mark() {
+    let input = "\u{feff}v {xschem version=3.4.5 file_version=1.2}\nN 0 0 10 0 {lab=A}\n";
+    let result = crate::from_str(input).unwrap();
+    assert_eq!(result.version.0.prop.location_offset(), 6);
+    assert_eq!(result.wires.len(), 1);
+
+    let result = crate::from_slice(input.as_bytes()).unwrap();
+    assert_eq!(result.version.0.prop.location_offset(), 6);
+    assert_eq!(result.wires.len(), 1);
+
+    let objects = crate::parse_objects(input).unwrap();
+    assert_eq!(objects.count(), 1);
+
+    let error = crate::from_str("\u{feff}v {}\nL 4 0 x 10 0 {}").unwrap_err();
+    assert_eq!(error.offset(), 14);
+    assert_eq!(error.line_column(), (2, 7));
+
+    let error = crate::from_str("\u{feff}N 0 0 10 0 {}").unwrap_err();
+    assert_eq!(error.err.kind, error::ErrorKind::MissingVersion);
+    assert_eq!(error.offset(), 3);
+
+    let input = "v {}\nC {a.sym} 0 0 0 0 {}\n[\n\u{feff}v {}\n]";
+    assert!(crate::from_str(input).is_err());
+    assert!(crate::from_str(&input.replace('\u{feff}', "")).is_ok());
+}
+
+#[test]
+fn parse_object_fields() {
+    let input = Span::new("C {res.sym} 10 20 2 1 {name=R1}\n[\nv {}\n]");
+    let (rest, fields) = object_fields::<_, (Span, ErrorKind)>(input).unwrap();
+    assert_eq!(*rest.fragment(), "");
+
+    let values: Vec<_> = fields
+        .iter()
+        .map(|f| f.value.map(|s| *s.fragment()))
+        .collect();
+    assert_eq!(
+        values,
+        [
+            Field::Reference("res.sym"),
+            Field::X(10.0.try_into().unwrap()),
+            Field::Y(20.0.try_into().unwrap()),
+            Field::Rotation(Rotation::Two),
+            Field::Flip(Flip::Flipped),
+            Field::Property("name=R1"),
+        ]
+    );
+    let spans: Vec<_> = fields
+        .iter()
+        .map(|f| (f.span.get_column(), *f.span.fragment()))
+        .collect();
+    assert_eq!(
+        spans,
+        [
+            (3, "{res.sym}"),
+            (13, "10"),
+            (16, "20"),
+            (19, "2"),
+            (21, "1"),
+            (23, "{name=R1}"),
+        ]
+    );
+
+    let (_, fields) = object_fields::<_, (&str, ErrorKind)>("P 3 3 0 0 10 0 0 0 {}").unwrap();
+    assert_eq!(fields.len(), 9);
+    assert_eq!(fields[1].value, Field::PointCount(3));
+    assert_eq!(fields[4].span, "10");
+    assert_eq!(fields[5].span, "0");
+
+    // The point count is checked like when parsing the polygon.
+    assert!(object_fields::<_, (&str, ErrorKind)>("P 3 2 0 0 10 0 0 0 {}").is_err());
+
+    let (_, fields) = object_fields::<_, (&str, ErrorKind)>("v {xschem version=3.4.5}").unwrap();
+    assert_eq!(
+        fields.iter().map(|f| f.value.clone()).collect::<Vec<_>>(),
+        [Field::Property("xschem version=3.4.5")]
+    );
+
+    let input = "Z 1 two {a=b}";
+    assert!(object_fields::<_, (&str, ErrorKind)>(input).is_err());
+    let options = ParseOptions::new().unknown_objects(true);
+    let (_, fields) = object_fields_with_options::<_, (&str, ErrorKind)>(&options)
+        .parse(input)
+        .unwrap();
+    assert_eq!(
+        fields.iter().map(|f| f.value.clone()).collect::<Vec<_>>(),
+        [
+            Field::Token("1"),
+            Field::Token("two"),
+            Field::Property("a=b")
+        ]
+    );
+
+    // Errors point at the invalid field.
+    assert_eq!(
+        object_fields::<_, (&str, ErrorKind)>("C {res.sym} 0 0 5 0 {}"),
+        Err(Err::Failure(("5 0 {}", ErrorKind::Char)))
+    );
+    assert_eq!(
+        object_fields::<_, (&str, ErrorKind)>("N 0 0 1x 0 {}"),
+        Err(Err::Failure(("x 0 {}", ErrorKind::MultiSpace)))
+    );
+
+    for input in ASSETS {
+        for (_, object) in crate::from_str_lossless(input).unwrap().objects() {
+            let (rest, _) = object_fields::<_, (Span, ErrorKind)>(*object).unwrap();
+            assert_eq!(*rest.fragment(), "");
+        }
+    }
+}
+
+#[test]
+fn parse_lossless() {
+    for input in ASSETS {
+        let lossless = crate::from_str_lossless(input).unwrap();
+        assert_eq!(lossless.to_string(), input);
+        assert_eq!(lossless.schematic(), &crate::from_str(input).unwrap());
+    }
+
+    let input = "\u{feff} v {xschem version=3.4.5 file_version=1.2}\r\n\tN 0  0 10 0.0 {lab=A}  ";
+    let lossless = crate::from_str_lossless(input).unwrap();
+    let objects: Vec<_> = lossless
+        .objects()
+        .iter()
+        .map(|(space, text)| (*space.fragment(), *text.fragment()))
+        .collect();
+    assert_eq!(
+        objects,
+        vec![
+            ("\u{feff} ", "v {xschem version=3.4.5 file_version=1.2}"),
+            ("\r\n\t", "N 0  0 10 0.0 {lab=A}"),
+        ]
+    );
+    assert_eq!(*lossless.trailing().fragment(), "  ");
+    assert_eq!(lossless.to_string(), input);
+
+    for input in [
+        "v {}\nL 4 0 x 10 0 {}",
+        "v {}N 0 0 10 0 {}",
+        "v {}\nX",
+        "N 0 0 10 0 {}",
+    ] {
+        let error = crate::from_str_lossless(input).unwrap_err();
+        assert_eq!(error, crate::from_str(input).unwrap_err());
+    }
+}
+
+#[test]
+fn parse_header() {
+    let input = include_str!("../../../../assets/7805.sym");
+    let header = crate::parse_header(input).unwrap();
+    let property = header.symbol_property.as_ref().unwrap();
+    assert_eq!(property.get_value("type"), Some("regulator"));
+    assert!(header.vhdl_property.is_some() && header.tedax_property.is_some());
+    assert_eq!(
+        Schematic::from(header).kind(),
+        crate::symbol::SchematicKind::Symbol
+    );
+
+    // The objects after the global properties are not parsed.
+    let (properties, _) = input.split_at(input.find("\nL ").unwrap());
+    let invalid = format!("{properties}\nL 4 not a line {{}}\nX");
+    let header = crate::parse_header(&invalid).unwrap();
+    assert_eq!(
+        header.symbol_property.unwrap().get_value("type"),
+        Some("regulator")
+    );
+    assert!(crate::from_str(&invalid).is_err());
+
+    assert!(crate::parse_header("K {type=regulator}").is_err());
+}
+
+#[test]
+fn parse_crlf() {
+    let input = "v {xschem version=3.4.5 file_version=1.2\r\n* copyright info}\r\n";
+    let result = crate::from_str(input).unwrap();
+    assert_eq!(
+        *result.version.0.prop.fragment(),
+        "xschem version=3.4.5 file_version=1.2\r\n* copyright info"
+    );
+    assert_eq!(result.version.info().unwrap().file_version, Some((1, 2)));
+
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let crlf_input = input.replace('\n', "\r\n");
+    let expected = crate::from_str(input).unwrap();
+    let result = crate::from_str(&crlf_input).unwrap();
+    assert_eq!(result.objects().count(), expected.objects().count());
+    assert_eq!(
+        result.to_string().replace("\r\n", "\n"),
+        expected.to_string()
+    );
+
+    let input = "v {xschem version=3.4.5 file_version=1.2}\r\nN 0 0 x 1 {}\r\n";
+    let error = crate::from_str(input).unwrap_err();
+    assert_eq!(error.err.input.location_line(), 2);
+    assert_eq!(error.err.input.get_utf8_column(), 7);
+    assert!(!error.to_string().contains('\r'));
+}
+
+#[test]
+fn parse_from_path() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets/7805.sym");
+    let input = include_str!("../../../../assets/7805.sym");
+    let expected = crate::from_str(input).unwrap();
+    let result = crate::from_path(path).unwrap();
+    assert_eq!(result.to_string(), expected.to_string());
+    assert_eq!(result.order, expected.order);
+    assert_eq!(
+        result
+            .symbol_property
+            .unwrap()
+            .get("type")
+            .map(String::as_str),
+        Some("regulator")
+    );
+
+    let result = crate::from_path("does/not/exist.sch");
+    assert!(matches!(result, Err(error::ReadError::Io(_))));
+}
+
+#[test]
+fn parse_from_file() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets/pmos.sym");
+    let expected = crate::from_path(path).unwrap();
+    let result = Schematic::from_file(path).unwrap();
+    assert_eq!(result.path, Path::new(path));
+    assert_eq!(result.schematic, expected);
+    assert_eq!(result.lines.len(), expected.lines.len());
+
+    let path = std::env::temp_dir().join("xschem-parser-parse-from-file.sch");
+    std::fs::write(&path, "v {}\nN 0 0 x 1 {}\n").unwrap();
+    let error = Schematic::from_file(&path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(error.path, path);
+    let error::ReadError::Parse(parse_error) = &*error.error else {
+        panic!("expected parse error");
+    };
+    assert_eq!(parse_error.err.input.extra, path);
+    assert_eq!(parse_error.line_column(), (2, 7));
+    assert!(
+        error
+            .to_string()
+            .contains(&format!("--> {}:2:7", path.display()))
+    );
+
+    let error = FileSchematic::try_from(Path::new("does/not/exist.sch")).unwrap_err();
+    assert!(matches!(*error.error, error::ReadError::Io(_)));
+    assert!(format!("{error:#}").ends_with("\n  --> does/not/exist.sch"));
+}
+
+#[test]
+fn parse_from_reader() {
+    let input = include_str!("../../../../assets/embedding.sch");
+    let expected = crate::from_str(input).unwrap();
+    let result = crate::from_reader(input.as_bytes()).unwrap();
+    assert_eq!(result.to_string(), expected.to_string());
+
+    let input = "v {xschem version=3.4.5 file_version=1.2}\nN 0 0 x 1 {}\n";
+    let expected = crate::from_str(input).unwrap_err().to_plain_string();
+    let Err(error::ReadError::Parse(error)) = crate::from_reader(input.as_bytes()) else {
+        panic!("expected parse error");
+    };
+    assert_eq!(error.err.input.line, 2);
+    assert_eq!(error.err.input.column, 7);
+    assert_eq!(error.err.input.line_text, "N 0 0 x 1 {}");
+    assert_eq!(error.to_plain_string(), expected);
+}
+
+#[test]
+fn parse_all_errors() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let result = crate::from_str_all_errors(input).unwrap();
+    assert_eq!(result, crate::from_str(input).unwrap());
+
+    let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+                 N 0 0 x 0 {lab=A}\n\
+                 T {text} 0 0 0 0 0.2 0.2 {}\n\
+                 X unknown object\n\
+                 N 0 0 10 0 {lab=B}{}\n\
+                 L 4 0 0 10 0 {}\n";
+    let expected = crate::from_str(input).unwrap_err();
+    let errors = crate::from_str_all_errors(input).unwrap_err();
+    assert_eq!(errors.len(), 3);
+    assert_eq!(errors[0], expected);
+    assert_eq!(
+        errors
+            .iter()
+            .map(|e| e.err.input.location_line())
+            .collect::<Vec<_>>(),
+        [2, 4, 5]
+    );
+    assert_eq!(errors[1].err.kind, error::ErrorKind::Nom(ErrorKind::Eof));
+    assert_eq!(errors[2].err.input.get_utf8_column(), 19);
+
+    let input = "v []\nN 0 0 x 0 {}\n";
+    let errors = crate::from_str_all_errors(input).unwrap_err();
+    assert_eq!(errors, [crate::from_str(input).unwrap_err()]);
+}
+
+#[test]
+fn parse_embedding_error_context() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+                 C {res.sym} 0 0 0 0 {name=R1}\n\
+                 C {capa.sym} 10 0 0 0 {name=C1}\n\
+                 [\n\
+                 v {xschem version=3.4.5 file_version=1.2}\n\
+                 L 4 0 x 10 0 {}\n\
+                 ]\n";
+    let error = crate::from_str(input).unwrap_err();
+
+    let context = error.context.last().unwrap();
+    assert_eq!(context.name, "component");
+    assert!(context.input.fragment().starts_with("capa.sym}"));
+    assert_eq!(context.input.location_line(), 3);
+    assert_eq!(context.input.get_utf8_column(), 4);
+
+    let expected = "\
+in embedded symbol
+  --> :4:1
+   |
+ 4 | [
+   | ^
+   |
+in component
+  --> :3:4
+   |
+ 3 | C {capa.sym} 10 0 0 0 {name=C1}
+   |    ^
+   |";
+    assert!(error.to_plain_string().ends_with(expected));
+
+    let input = "v {xschem version=3.4.5 file_version=1.2}\nC {capa.sym} x 0 0 0 {}\n";
+    let error = crate::from_str(input).unwrap_err();
+    let context = error.context.last().unwrap();
+    assert_eq!(context.name, "component");
+    assert_eq!(context.input.get_utf8_column(), 1);
+}
+
+#[test]
+fn error_codes() {
+    let error = crate::from_str("v []").unwrap_err();
+    assert_eq!(error.err.kind, error::ErrorKind::Char('{'));
+    assert_eq!(error.err.kind.code(), "expected-char");
+    assert_eq!(error.byte_range(), 2..4);
+
+    assert_eq!(error::ErrorKind::Nom(ErrorKind::Float).code(), "bad-float");
+
+    let input = "v {}\nL 4 0 0 10 0 {}\nL 4 0 0 1e 0 {}";
+    let error = crate::from_str(input).unwrap_err();
+    assert_eq!(error.err.kind.code(), "bad-float");
+    assert_eq!(error.byte_range(), 31..31);
+
+    let error = crate::from_str("v {}\nL x 0 0 1 0 {}").unwrap_err();
+    assert_eq!(error.err.kind.code(), "bad-layer");
+    assert_eq!(error.byte_range(), 7..8);
+}
+
+#[test]
+fn parse_invalid_layer() {
+    for (layer, range) in [("3.0", 7..10), ("-1", 7..9), ("+3", 7..9), ("1e2", 7..10)] {
+        let input = format!("v {{}}\nL {layer} 0 0 10 0 {{}}");
+        let error = crate::from_str(&input).unwrap_err();
+        assert_eq!(error.err.kind, error::ErrorKind::Layer, "{layer}");
+        assert_eq!(error.byte_range(), range, "{layer}");
+        assert_eq!(
+            error.err.kind.to_string(),
+            "expected layer index (non-negative integer)"
+        );
+        assert_eq!(error.context_spans().next(), Some(("line", 5)));
+    }
+
+    let error = crate::from_str("v {}\nB 99999999999999999999 0 0 1 1 {}").unwrap_err();
+    assert_eq!(error.err.kind, error::ErrorKind::Layer);
+}
+
+#[test]
+fn parse_non_finite_double() {
+    let error = crate::from_str("v {}\nL 0 1e400 0 0 0 {}").unwrap_err();
+    assert_eq!(error.err.kind, error::ErrorKind::Nom(ErrorKind::Float));
+    assert_eq!(error.offset(), 9);
+    assert_eq!(error.context_spans().next(), Some(("coordinate", 9)));
+
+    let error = crate::from_str("v {}\nA 4 0 0 10 -1e999 90 {}").unwrap_err();
+    assert_eq!(error.err.kind.code(), "bad-float");
+    assert_eq!(error.offset(), 16);
+    assert!(
+        error
+            .to_plain_string()
+            .contains("2 | A 4 0 0 10 -1e999 90 {}\n"),
+        "{error:#}"
+    );
+
+    let options = ParseOptions::new().decimal_comma(true);
+    let error =
+        crate::parse_with_options(Span::new("v {}\nN 0 0 1e400 0 {}"), &options).unwrap_err();
+    assert_eq!(error.offset(), 11);
+    assert!(
+        error.to_plain_string().contains("2 | N 0 0 1e400 0 {}\n"),
+        "{error:#}"
+    );
+}
+
+#[test]
+fn error_to_plain_string() {
+    colored::control::set_override(true);
+    let input = "v {xschem version=3.4.5 file_version=1.2}\nL 4 0 x 10 0 {}";
+    let error = crate::from_str(input).unwrap_err();
+    let plain = error.to_plain_string();
+    assert_eq!(
+        error.to_string().contains('\x1b'),
+        cfg!(not(feature = "no-color"))
+    );
+    assert!(!plain.contains('\x1b'));
+    assert!(plain.starts_with("error: expected '.'\n"));
+    assert!(plain.contains("\nin line\n"));
+
+    let error = crate::from_reader(input.as_bytes()).unwrap_err();
+    assert_eq!(format!("{error:#}"), plain);
+    colored::control::unset_override();
+}
+
+#[test]
+fn error_from_slice_display() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}\nL 4 0 x 10 0 {}";
+    let expected = crate::from_str(input).unwrap_err();
+    let error = crate::from_slice(input.as_bytes()).unwrap_err();
+    assert_eq!(error.to_plain_string(), expected.to_plain_string());
+    assert_eq!(format!("{error:#}"), format!("{expected:#}"));
+
+    let path = Path::new("test.sch");
+    let expected = crate::from_str_file(input, path).unwrap_err();
+    let error = crate::from_slice_file(input.as_bytes(), path).unwrap_err();
+    assert_eq!(error.to_plain_string(), expected.to_plain_string());
+    assert!(error.to_plain_string().contains("--> test.sch:2:7"));
+}
+
+#[test]
+fn error_offsets() {
+    let error = crate::from_str("v []").unwrap_err();
+    assert_eq!(error.offset(), 2);
+    assert_eq!(error.line_column(), (1, 3));
+    assert_eq!(error.context_spans().collect::<Vec<_>>(), [("version", 0)]);
+
+    let path = std::path::Path::new("test.sch");
+    let error = crate::from_str_file("v {}\nN 0 0 x 0 {}", path).unwrap_err();
+    assert_eq!(error.offset(), 11);
+    assert_eq!(error.line_column(), (2, 7));
+    assert_eq!(
+        error.context_spans().collect::<Vec<_>>(),
+        [("coordinate", 11), ("wire", 5)]
+    );
+
+    let Err(error::ReadError::Parse(error)) = crate::from_reader("v []".as_bytes()) else {
+        panic!("expected parse error");
+    };
+    assert_eq!(error.offset(), 2);
+    assert_eq!(error.line_column(), (1, 3));
+    assert_eq!(error.context_spans().collect::<Vec<_>>(), [("version", 0)]);
+}
+
+#[test]
+fn parse_object_stream() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let expected = crate::from_str(input).unwrap();
+    let objects = crate::parse_objects(input).unwrap();
+    assert_eq!(objects.version(), &expected.version);
+
+    let (mut texts, mut lines, mut rectangles, mut polygons) = (0, 0, 0, 0);
+    let (mut arcs, mut wires, mut components, mut properties) = (0, 0, 0, 0);
+    for object in objects {
+        match object.unwrap() {
+            Object::Text(_) => texts += 1,
+            Object::Line(_) => lines += 1,
+            Object::Rectangle(_) => rectangles += 1,
+            Object::Polygon(_) => polygons += 1,
+            Object::Arc(_) => arcs += 1,
+            Object::Wire(_) => wires += 1,
+            Object::Component(_) => components += 1,
+            _ => properties += 1,
+        }
+    }
+    assert_eq!(texts, expected.texts.len());
+    assert_eq!(lines, expected.lines.len());
+    assert_eq!(rectangles, expected.rectangles.len());
+    assert_eq!(polygons, expected.polygons.len());
+    assert_eq!(arcs, expected.arcs.len());
+    assert_eq!(wires, expected.wires.len());
+    assert_eq!(components, expected.components.len());
+    assert_eq!(properties, 5);
+
+    let input = "v {}\nN 0 0 10 0 {}\nN 0 0 x 0 {}\nN 0 0 10 0 {}";
+    let expected = crate::from_str(input).unwrap_err();
+    let results = crate::parse_objects(input).unwrap().collect::<Vec<_>>();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert_eq!(results[1].as_ref().unwrap_err(), &expected);
+
+    assert_eq!(
+        crate::parse_objects("v []").unwrap_err(),
+        crate::from_str("v []").unwrap_err()
+    );
+}
+
+#[test]
+fn parse_object_spans() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let objects = crate::parse_objects(input)
+        .unwrap()
+        .spanned()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let mut wires = 0;
+    for object in &objects {
+        let span = object.span;
+        assert_eq!(
+            &input[span.location_offset()..span.location_offset() + span.len()],
+            *span.fragment()
+        );
+        if let Object::Wire(wire) = &object.value {
+            let line = input.lines().nth(span.location_line() as usize - 1);
+            assert_eq!(Some(*span.fragment()), line);
+            assert_eq!(span.fragment().to_string(), wire.to_string());
+            wires += 1;
+        }
+    }
+    assert_eq!(wires, crate::from_str(input).unwrap().wires.len());
+
+    let input = "v {}\nC {sym.sym} 0 0 0 0 {}\n[\nv {}\n]  L 4 0 0 1 0 {}\n";
+    let spans = crate::parse_objects(input)
+        .unwrap()
+        .spanned()
+        .map(|o| *o.unwrap().span.fragment())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        spans,
+        ["C {sym.sym} 0 0 0 0 {}\n[\nv {}\n]", "L 4 0 0 1 0 {}"]
+    );
+}
+
+#[test]
+fn parse_missing_version() {
+    let input = "N 0 0 10 0 {lab=A}\nC {res.sym} 0 0 0 0 {name=R1}\n";
+    let error = crate::from_str(input).unwrap_err();
+    assert_eq!(error.err.kind, error::ErrorKind::MissingVersion);
+    assert_eq!(error.err.kind.code(), "missing-version");
+    assert_eq!(error.offset(), 0);
+    assert_eq!(
+        error.err.kind.to_string(),
+        "schematic must begin with a version line (v { ... })"
+    );
+    assert!(
+        error
+            .to_plain_string()
+            .starts_with("error: schematic must begin with a version line (v { ... })\n")
+    );
+
+    let error = crate::from_str("\n\n").unwrap_err();
+    assert_eq!(error.err.kind, error::ErrorKind::MissingVersion);
+
+    let error = crate::from_str("v []").unwrap_err();
+    assert_eq!(error.err.kind, error::ErrorKind::Char('{'));
+}
+
+#[test]
+fn parse_empty_version() {
+    for input in ["v {}", "v {}\n", "v\t{}\r\n", "\u{feff}v {}"] {
+        let schematic = crate::from_str(input).unwrap();
+        assert_eq!(*schematic.version.0.prop.fragment(), "");
+        assert!(schematic.version.0.attrs.is_empty());
+        assert_eq!(schematic.version.file_version_str(), None);
+        assert_eq!(schematic.objects().count(), 0);
+    }
+
+    let schematic = crate::from_str("v {}\nN 0 0 10 0 {lab=A}").unwrap();
+    assert!(schematic.version.0.attrs.is_empty());
+    assert_eq!(schematic.wires.len(), 1);
+
+    for input in ["v\n{}", "v \r\n\n  {xschem version=3.4.5 file_version=1.2}"] {
+        let schematic = crate::from_str(input).unwrap();
+        assert_eq!(schematic.objects().count(), 0);
+    }
+}
+
+#[test]
+fn parse_version_without_property() {
+    for input in [
+        "v",
+        "v\n",
+        "v  \t",
+        "v \r\nN 0 0 10 0 {lab=A}",
+        "\n v\nv {}",
+    ] {
+        let error = crate::from_str(input).unwrap_err();
+        assert_eq!(error.err.kind, error::ErrorKind::MissingVersionProperty);
+        assert_eq!(error.err.kind.code(), "missing-version-property");
+        assert_eq!(error.offset(), input.find('v').unwrap() + 1);
+        assert_eq!(error.context_spans().next().unwrap().0, "version");
+    }
+
+    let error = crate::from_str("v\nN 0 0 10 0 {lab=A}").unwrap_err();
+    assert_eq!(error.line_column(), (1, 2));
+    assert_eq!(
+        error.err.kind.to_string(),
+        "version line has no property, expected `v {}` or `v { ... }`"
+    );
+}
+
+#[test]
+fn parse_plain() {
+    for input in ASSETS {
+        let plain = crate::from_str_plain(input).unwrap();
+        let span = crate::from_str(input).unwrap().map(|s| *s.fragment());
+        assert_eq!(plain, span);
+        assert_eq!(plain.order, span.order);
+    }
+
+    let error = crate::from_str_plain("v {}\nL 4 0 0 1e 0 {}").unwrap_err();
+    let span_error = crate::from_str("v {}\nL 4 0 0 1e 0 {}").unwrap_err();
+    assert_eq!(error.err.kind, span_error.err.kind);
+    assert_eq!(error.err.input, *span_error.err.input.fragment());
+}
+
+#[test]
+fn unescape_property() {
+    assert!(matches!(
+        unescape("type=subcircuit", ESCAPED_CHARS),
+        Cow::Borrowed("type=subcircuit")
+    ));
+    assert_eq!(unescape(r"\{@name\}", ESCAPED_CHARS), "{@name}");
+    assert_eq!(unescape(r"a\\b \n \\\}", ESCAPED_CHARS), r"a\b \n \}");
+    assert_eq!(unescape(r"trailing \", ESCAPED_CHARS), r"trailing \");
+    assert_eq!(unescape(r#"\"kept\""#, ESCAPED_CHARS), r#"\"kept\""#);
+    assert_eq!(unescape(r"ä\}ö", ESCAPED_CHARS), "ä}ö");
+
+    let mut out = String::from("T ");
+    unescape_into(r"\{x\}", ESCAPED_CHARS, &mut out);
+    assert_eq!(out, "T {x}");
+}
+
+#[test]
+fn unescape_value() {
+    assert_eq!(
+        unescape(r#"say \"hi\" \\ \{"#, ESCAPED_VALUE_CHARS),
+        r#"say "hi" \ \{"#
+    );
+
+    // Quoted values are unescaped as part of the property string first.
+    let (_, property) =
+        property::<_, (&str, ErrorKind)>(r#"{format="\\"@name\\" \{x\}"}"#).unwrap();
+    let value = property.get_value("format").unwrap();
+    let expected = unescape(&unescape(value, ESCAPED_CHARS), ESCAPED_VALUE_CHARS).into_owned();
+    assert_eq!(expected, r#""@name" {x}"#);
+    assert_eq!(property.get_unescaped("format"), Some(expected));
+}
+
+#[test]
+fn parse_slice_non_ascii() {
+    // Bytes that are not ASCII used to be split as characters of two bytes.
+    let result = crate::from_slice(b"v {\xc3\xa9}\nN 0 0 10 0 {lab=\"\xff\" name=\xfe}").unwrap();
+    assert_eq!(*result.version.0.prop.fragment(), b"\xc3\xa9");
+    assert_eq!(
+        *result.wires[0].property.prop.fragment(),
+        b"lab=\"\xff\" name=\xfe"
+    );
+
+    for (input, line_column, kind) in [
+        (&b"v {xs\xff"[..], (1, 7), error::ErrorKind::Char('}')),
+        (b"v {\xff\\", (1, 4), ErrorKind::Escaped.into()),
+        (b"v {}\nN 0 0 10 0 {lab=\"\xff", (2, 19), '}'.into()),
+        (b"v {}\nC {\xff} 0 0 0 0 {", (2, 16), '}'.into()),
+        (b"v {}\nL 4 \xff 0 10 0 {}", (2, 5), '.'.into()),
+        (b"\xff\xfe", (1, 1), error::ErrorKind::MissingVersion),
+    ] {
+        let error = crate::from_slice(input).unwrap_err();
+        assert_eq!(error.line_column(), line_column);
+        assert_eq!(error.err.kind, kind);
+        assert!(!format!("{error:#}").is_empty());
+    }
+
+    let error = crate::from_slice(b"v {}\nL 4 \xff 0 10 0 {}").unwrap_err();
+    assert!(format!("{error:#}").contains("L 4 \u{fffd} 0 10 0 {}"));
+}
+
+#[test]
+fn error_tab_alignment() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}\n\tL 4\t0 x 10 0 {}";
+    let error = crate::from_str(input).unwrap_err();
+    assert_eq!(error.line_column(), (2, 8));
+
+    // Tabs are expanded so the caret is below the `x`.
+    let plain = error.to_plain_string();
+    assert!(plain.contains("\n 2 |     L 4 0 x 10 0 {}\n   |           ^\n"));
+    assert!(!plain.contains('\t'));
+
+    let wide = format!("{:#}", error.display_with_tab_width(8));
+    assert!(wide.contains("\n 2 |         L 4     0 x 10 0 {}\n   |                   ^\n"));
+    // The context lines use the same tab width.
+    assert!(wide.ends_with("\n 2 |         L 4     0 x 10 0 {}\n   |         ^\n   |"));
+
+    // The width of the format pads nothing and keeps the default tab width.
+    assert_eq!(format!("{error:#20}"), plain);
+
+    let error = error.map_input(error::Location::from);
+    assert_eq!(error.to_plain_string(), plain);
+    assert_eq!(format!("{:#}", error.display_with_tab_width(8)), wide);
+}
@@ -0,0 +1,43 @@
+#[test]
+fn sort_canonical_input_order() {
+    let a = "\
+v {xschem version=3.4.5 file_version=1.2}
+C {res.sym} 20 0 0 0 {name=R2}
+N 0 0 10 0 {lab=b}
+T {b} 0 0 0 0 0.4 0.4 {}
+N -10 0 0 0 {lab=a}
+C {res.sym} 0 0 0 0 {name=R1}
+L 4 0 0 10 10 {}
+T {a} 0 0 0 0 0.4 0.4 {}
+L 3 0 0 10 10 {}";
+    let b = "\
+v {xschem version=3.4.5 file_version=1.2}
+L 4 0 0 10 10 {}
+T {a} 0 0 0 0 0.4 0.4 {}
+C {res.sym} 0 0 0 0 {name=R1}
+N 0 0 10 0 {lab=b}
+L 3 0 0 10 10 {}
+C {res.sym} 20 0 0 0 {name=R2}
+N -10 0 0 0 {lab=a}
+T {b} 0 0 0 0 0.4 0.4 {}";
+    let mut a = crate::from_str(a).unwrap();
+    let mut b = crate::from_str(b).unwrap();
+    assert_ne!(a.to_string(), b.to_string());
+
+    a.sort_canonical();
+    b.sort_canonical();
+    assert_eq!(a.to_string(), b.to_string());
+    assert_eq!(
+        a.to_string(),
+        "\
+v {xschem version=3.4.5 file_version=1.2}
+T {a} 0 0 0 0 0.4 0.4 {}
+T {b} 0 0 0 0 0.4 0.4 {}
+L 3 0 0 10 10 {}
+L 4 0 0 10 10 {}
+N -10 0 0 0 {lab=a}
+N 0 0 10 0 {lab=b}
+C {res.sym} 0 0 0 0 {name=R1}
+C {res.sym} 20 0 0 0 {name=R2}"
+    );
+}
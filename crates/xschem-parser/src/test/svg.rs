@@ -0,0 +1,47 @@
+use crate::svg::SvgOptions;
+use crate::token::TEXT_LAYER;
+
+#[test]
+fn svg_wire_line() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let schematic = crate::from_str(input).unwrap();
+    let svg = schematic.to_svg(&SvgOptions::new().layer_color(1, "#00ccee"));
+
+    assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox=""#));
+    assert!(svg.ends_with("</svg>\n"));
+    // N 890 -130 890 -110 {lab=ANALOG_GND}
+    assert!(svg.contains(r##"<line x1="890" y1="-130" x2="890" y2="-110" stroke="#00ccee"/>"##));
+}
+
+#[test]
+fn svg_primitives() {
+    let input = "\
+v {xschem version=3.4.5 file_version=1.2}
+B 5 -10 -10 10 10 {}
+P 3 3 0 0 10 0 10 10 {}
+A 4 0 0 10 0 90 {}
+A 4 0 0 5 0 360 {}
+T {a<b} 0 0 1 0 0.4 0.4 {layer=8}";
+    let schematic = crate::from_str(input).unwrap();
+    let svg = schematic.to_svg(&SvgOptions::new().margin(0.0));
+
+    assert!(svg.contains(r#"viewBox="-10 -10 20 20""#));
+    assert!(svg.contains(r#"<rect x="-10" y="-10" width="20" height="20" stroke="black"/>"#));
+    assert!(svg.contains(r#"<polyline points="0,0 10,0 10,10" stroke="black"/>"#));
+    assert!(svg.contains(r#"<circle cx="0" cy="0" r="5" stroke="black"/>"#));
+    assert!(svg.contains(r#"transform="rotate(90 0 0)""#));
+    assert!(svg.contains(">a&lt;b</text>"));
+}
+
+#[test]
+fn svg_text_layer() {
+    let input = "v {}\nT {a} 0 0 0 0 0.4 0.4 {}\nT {b} 0 0 0 0 0.4 0.4 {layer=8}";
+    let schematic = crate::from_str(input).unwrap();
+    let options = SvgOptions::new()
+        .layer_color(TEXT_LAYER, "#ccc")
+        .layer_color(8, "#f00");
+    let svg = schematic.to_svg(&options);
+
+    assert!(svg.contains(r##"fill="#ccc" stroke="none" dominant-baseline="hanging">a</text>"##));
+    assert!(svg.contains(r##"fill="#f00" stroke="none" dominant-baseline="hanging">b</text>"##));
+}
@@ -0,0 +1,203 @@
+use crate::error::ErrorKind;
+use crate::symbol::{Pin, PinDirection, Port, SchematicKind, SymbolClasses};
+
+#[test]
+fn symbol_pins() {
+    let input = include_str!("../../../../assets/7805.sym");
+    let schematic = crate::from_str(input).unwrap();
+
+    let pins: Vec<_> = schematic
+        .pins()
+        .into_iter()
+        .map(|p| Pin {
+            name: *p.name.fragment(),
+            dir: p.dir,
+            number: p.number.map(|n| *n.fragment()),
+            position: p.position,
+        })
+        .collect();
+
+    assert_eq!(
+        pins,
+        vec![
+            Pin {
+                name: "IN",
+                dir: PinDirection::In,
+                number: Some("1"),
+                position: (-60.0, 0.0).try_into().unwrap(),
+            },
+            Pin {
+                name: "GND",
+                dir: PinDirection::Inout,
+                number: Some("2"),
+                position: (0.0, 30.0).try_into().unwrap(),
+            },
+            Pin {
+                name: "OUT",
+                dir: PinDirection::Out,
+                number: Some("3"),
+                position: (60.0, 0.0).try_into().unwrap(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn schematic_without_pins() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let schematic = crate::from_str(input).unwrap();
+
+    assert!(schematic.pins().is_empty());
+}
+
+#[test]
+fn schematic_kind() {
+    let symbol = crate::from_str(include_str!("../../../../assets/7805.sym")).unwrap();
+    assert_eq!(symbol.kind(), SchematicKind::Symbol);
+
+    let schematic = crate::from_str(include_str!("../../../../assets/pcb_test1.sch")).unwrap();
+    assert_eq!(schematic.kind(), SchematicKind::Schematic);
+
+    let schematic = crate::from_str(include_str!("../../../../assets/embedding.sch")).unwrap();
+    assert_eq!(schematic.kind(), SchematicKind::Schematic);
+    let embedding = schematic.components[0].embedding.as_ref().unwrap();
+    assert_eq!(embedding.0.kind(), SchematicKind::Symbol);
+
+    let empty = crate::from_str("v {xschem version=3.4.5 file_version=1.2}").unwrap();
+    assert_eq!(empty.kind(), SchematicKind::Schematic);
+}
+
+#[test]
+fn parse_expected_kind() {
+    let symbol = include_str!("../../../../assets/7805.sym");
+    let schematic = include_str!("../../../../assets/pcb_test1.sch");
+
+    assert!(crate::from_str_symbol(symbol).is_ok());
+    assert!(crate::from_str_schematic(schematic).is_ok());
+
+    let error = crate::from_str_schematic(symbol).unwrap_err();
+    assert_eq!(
+        error.err.kind,
+        ErrorKind::UnexpectedKind {
+            expected: SchematicKind::Schematic,
+            found: SchematicKind::Symbol,
+        }
+    );
+    assert_eq!(
+        error.err.kind.to_string(),
+        "expected a schematic, found a symbol"
+    );
+    // Points at the name of the first pin.
+    assert!(error.err.input.fragment().starts_with("IN dir=in"));
+
+    let error = crate::from_str_symbol(schematic).unwrap_err();
+    assert_eq!(
+        error.err.kind.to_string(),
+        "expected a symbol, found a schematic"
+    );
+    assert!(error.err.input.fragment().starts_with("title.sym}"));
+}
+
+#[test]
+fn symbol_subcircuit() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+                 K {type=subcircuit\n\
+                 format=\"@name @pinlist @symname\"\n\
+                 template=\"name=x1\"}\n\
+                 B 5 -42.5 -2.5 -37.5 2.5 {name=A dir=in}\n\
+                 B 5 37.5 -2.5 42.5 2.5 {name=Y dir=out}\n";
+    let symbol = crate::from_str_plain(input).unwrap();
+    assert!(symbol.is_subcircuit());
+    assert_eq!(
+        symbol.subcircuit_ports(),
+        [
+            Port {
+                name: "A",
+                dir: PinDirection::In,
+            },
+            Port {
+                name: "Y",
+                dir: PinDirection::Out,
+            },
+        ]
+    );
+
+    let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+                 K {type=\"subcircuit\"}\n\
+                 C {devices/ipin.sym} 0 0 0 0 {name=p1 lab=A}\n\
+                 C {devices/lab_pin.sym} 10 0 0 0 {name=p2 lab=B}\n\
+                 C {iopin.sym} 20 0 0 0 {name=p3 lab=VDD}\n\
+                 C {opin.sym} 30 0 0 0 {name=p4}\n";
+    let schematic = crate::from_str_plain(input).unwrap();
+    assert!(schematic.is_subcircuit());
+    assert_eq!(
+        schematic.subcircuit_ports(),
+        [
+            Port {
+                name: "A",
+                dir: PinDirection::In,
+            },
+            Port {
+                name: "VDD",
+                dir: PinDirection::Inout,
+            },
+        ]
+    );
+
+    let regulator = crate::from_str(include_str!("../../../../assets/7805.sym")).unwrap();
+    assert!(!regulator.is_subcircuit());
+    assert_eq!(regulator.subcircuit_ports().len(), 3);
+    assert!(!crate::from_str("v {}").unwrap().is_subcircuit());
+}
+
+#[test]
+fn component_classes() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+                 C {lab_pin.sym} 0 0 0 0 {name=l1 lab=A}\n\
+                 C {devices/gnd.sym} 0 0 0 0 {name=l2 lab=GND}\n\
+                 C { ipin.sym } 0 0 0 0 {name=p1 lab=IN}\n\
+                 C {res.sym} 0 0 0 0 {name=R1}\n\
+                 C {my_lab.sym} 0 0 0 0 {name=l3 lab=B}\n";
+    let schematic = crate::from_str(input).unwrap();
+    let classify =
+        |classes: &SymbolClasses, c| (classes.is_label(c), classes.is_power(c), classes.is_pin(c));
+
+    let classes: Vec<_> = schematic
+        .components
+        .iter()
+        .map(|c| (c.is_label(), c.is_power(), c.is_pin()))
+        .collect();
+    assert_eq!(
+        classes,
+        [
+            (true, false, false),
+            (false, true, false),
+            (false, false, true),
+            (false, false, false),
+            (false, false, false),
+        ]
+    );
+    let default = SymbolClasses::default();
+    assert!(
+        schematic
+            .components
+            .iter()
+            .zip(&classes)
+            .all(|(c, &class)| classify(&default, c) == class)
+    );
+
+    let labels = ["lab_pin", "my_lab"];
+    let custom = SymbolClasses {
+        labels: &labels,
+        power: &[],
+        ..SymbolClasses::default()
+    };
+    assert_eq!(
+        classify(&custom, &schematic.components[4]),
+        (true, false, false)
+    );
+    assert_eq!(
+        classify(&custom, &schematic.components[1]),
+        (false, false, false)
+    );
+}
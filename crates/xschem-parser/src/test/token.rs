@@ -1,5 +1,11 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+use crate::Span;
+use crate::options::{ObjectMask, ParseOptions};
 use crate::token::{
-    Component, Flip, Objects, Polygon, Property, Rotation, Schematic, Text, Version, Wire,
+    Arc, Component, FiniteDouble, Flip, ObjectKind, ObjectRef, Objects, Polygon, Property,
+    Rotation, Schematic, SpiceProperty, Text, UnknownObject, Vec2, Version, VersionInfo, Wire,
 };
 
 #[test]
@@ -14,6 +20,25 @@ fn version_to_string() {
     assert_eq!(version.to_string(), expected);
 }
 
+#[test]
+fn object_constructors() {
+    let wire = Wire::<&str>::new(
+        (0.0, 0.0).try_into().unwrap(),
+        (10.0, -5.0).try_into().unwrap(),
+    );
+    assert_eq!(wire.to_string(), "N 0 0 10 -5 {}");
+
+    let text = Text::new(
+        "hello",
+        (10.0, 20.0).try_into().unwrap(),
+        (0.4, 0.2).try_into().unwrap(),
+    );
+    assert_eq!(text.to_string(), "T {hello} 10 20 0 0 0.4 0.2 {}");
+
+    let component = Component::new("res.sym", (0.0, 30.0).try_into().unwrap());
+    assert_eq!(component.to_string(), "C {res.sym} 0 30 0 0 {}");
+}
+
 #[test]
 #[allow(clippy::too_many_lines)]
 fn schematic_to_string() {
@@ -78,6 +103,7 @@ fn schematic_to_string() {
                 position: (3.0, 3.0).try_into().unwrap(),
                 rotation: Rotation::Zero,
                 flip: Flip::Flipped,
+                extra: Vec::new(),
                 property: Property {
                     prop: "name=pin\n",
                     attrs: [("name", "pin")].into(),
@@ -89,6 +115,7 @@ fn schematic_to_string() {
                 position: (1.0, 1.0).try_into().unwrap(),
                 rotation: Rotation::Two,
                 flip: Flip::Unflipped,
+                extra: Vec::new(),
                 property: Property {
                     prop: "name=p",
                     attrs: [("name", "p")].into(),
@@ -103,6 +130,16 @@ fn schematic_to_string() {
             },
         ]
         .into(),
+        unknown_objects: vec![UnknownObject {
+            tag: "Z",
+            tokens: vec!["1", "2"],
+            property: Property {
+                prop: "foo=bar",
+                attrs: [("foo", "bar")].into(),
+            },
+        }]
+        .into(),
+        order: Vec::default(),
     };
 
     let expected = "\
@@ -118,8 +155,640 @@ fn schematic_to_string() {
         C {pmos.sym} 1 1 2 0 {name=p}\n\
         [\n\
         v {xschem version=3.4.5 file_version=1.2}\n\
-        ]\
+        ]\n\
+        Z 1 2 {foo=bar}\
     ";
 
     assert_eq!(schematic.to_string(), expected);
 }
+
+#[test]
+fn schematic_objects() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let schematic = crate::from_str(input).unwrap();
+
+    assert_eq!(schematic.objects().count(), 47);
+    assert_eq!(
+        schematic
+            .objects()
+            .filter(|o| matches!(o, ObjectRef::Wire(_)))
+            .count(),
+        20
+    );
+}
+
+#[test]
+fn schematic_to_string_input_order() {
+    let input = include_str!("../../../../assets/embedding.sch");
+    let schematic = crate::from_str(input).unwrap();
+
+    assert_eq!(schematic.to_string(), input.trim_end());
+}
+
+#[test]
+fn schematic_embeddings() {
+    let schematic = crate::from_str(include_str!("../../../../assets/embedding.sch")).unwrap();
+    assert!(schematic.has_embeddings());
+    assert_eq!(schematic.embedded_schematics().count(), 1);
+
+    let embedded = schematic.embedded_schematics().next().unwrap();
+    assert_eq!(embedded.rectangles.len(), 4);
+    assert!(!embedded.has_embeddings());
+
+    let schematic = crate::from_str(include_str!("../../../../assets/pcb_test1.sch")).unwrap();
+    assert!(!schematic.has_embeddings());
+    assert_eq!(schematic.embedded_schematics().count(), 0);
+}
+
+#[test]
+fn schematic_into_cow() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let mut schematic = crate::from_str(input).unwrap().into_cow();
+    assert!(matches!(schematic.texts[0].text, Cow::Borrowed(_)));
+
+    let text = format!("{}!", schematic.texts[0].text);
+    schematic.texts[0].text = Cow::Owned(text.clone());
+    assert!(matches!(schematic.texts[0].text, Cow::Owned(_)));
+    assert!(matches!(schematic.texts[1].text, Cow::Borrowed(_)));
+
+    let owned = schematic.into_owned();
+    assert_eq!(owned.texts[0].text, text);
+    assert!(owned.to_string().contains(&format!("T {{{text}}}")));
+
+    let parsed = crate::from_cow(Cow::Owned(input.to_string())).unwrap();
+    assert!(matches!(parsed.texts[0].text, Cow::Owned(_)));
+    assert_eq!(parsed.to_string(), input.trim_end());
+    assert!(crate::from_cow(Cow::Borrowed("v {")).is_err());
+}
+
+#[test]
+fn schematic_from_span() {
+    for input in [
+        include_str!("../../../../assets/embedding.sch"),
+        include_str!("../../../../assets/pcb_test1.sch"),
+    ] {
+        let schematic = Schematic::<&str>::from(crate::from_str(input).unwrap());
+        assert_eq!(schematic, crate::from_str_plain(input).unwrap());
+        assert_eq!(
+            schematic.to_string(),
+            crate::from_str(input).unwrap().to_string()
+        );
+    }
+}
+
+#[test]
+fn schematic_content_eq() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let schematic = crate::from_str(input).unwrap();
+    let moved_input = input.replacen('\n', "\n\n\n", 2);
+    let moved = crate::from_str(&moved_input).unwrap();
+    assert_ne!(schematic, moved);
+    assert!(schematic.content_eq(&moved));
+    assert!(moved.content_eq(&schematic));
+
+    let bytes = crate::from_slice(input.as_bytes()).unwrap();
+    let moved_bytes = crate::from_slice(moved_input.as_bytes()).unwrap();
+    assert!(bytes.content_eq(&moved_bytes));
+
+    let changed_input = moved_input.replacen("lab=", "lab=X", 1);
+    let changed = crate::from_str(&changed_input).unwrap();
+    assert!(!schematic.content_eq(&changed));
+}
+
+#[test]
+fn objects_index() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let mut schematic = crate::from_str(input).unwrap();
+
+    let wire = &schematic.wires[0];
+    assert_eq!(schematic.wires.first(), Some(wire));
+    assert_eq!(schematic.wires[..2].len(), 2);
+    assert_eq!(&schematic.wires[1..3][0], &schematic.wires[1]);
+    assert_eq!(schematic.wires.last(), Some(&schematic.wires[19]));
+    assert_eq!(Objects::<Wire<&str>>::default().first(), None);
+
+    let start = schematic.wires[1].start;
+    schematic.wires[0].start = start;
+    assert_eq!(schematic.wires[0].start, start);
+}
+
+#[test]
+fn version_str() {
+    let version = crate::from_str("v {xschem version=3.4.5 file_version=1.2}")
+        .unwrap()
+        .version;
+    let app_version = version.app_version_str().unwrap();
+    assert_eq!(*app_version.fragment(), "3.4.5");
+    assert_eq!(app_version.location_offset(), 18);
+    assert_eq!(*version.file_version_str().unwrap().fragment(), "1.2");
+
+    let version = Version(Property {
+        prop: "file_version=\"1.0\"",
+        attrs: [("file_version", "\"1.0\"")].into(),
+    });
+    assert_eq!(version.app_version_str(), None);
+    assert_eq!(version.file_version_str(), Some(&"\"1.0\""));
+}
+
+#[test]
+fn spice_property_code() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}
+S {.subckt inv a y vdd=vdd
+M1 y a vdd vdd pmos w=2u l=0.15u
+.ends
+.control
+let x = \\{1, 2\\}
+.endc}
+V {assign y = ~a;}
+";
+    let schematic = crate::from_str(input).unwrap();
+    let spice = schematic.spice_property.unwrap();
+    assert_eq!(
+        *spice.code().fragment(),
+        ".subckt inv a y vdd=vdd\nM1 y a vdd vdd pmos w=2u l=0.15u\n.ends\n.control\nlet x = \\{1, 2\\}\n.endc"
+    );
+    assert_eq!(spice.code().location_line(), 2);
+    assert!(spice.code_unescaped().ends_with("let x = {1, 2}\n.endc"));
+
+    let verilog = schematic.verilog_property.unwrap();
+    assert_eq!(verilog.code_unescaped(), "assign y = ~a;");
+    assert!(schematic.vhdl_property.is_none());
+}
+
+#[test]
+fn version_info() {
+    let version = crate::from_str("v {xschem version=3.4.5 file_version=1.2}")
+        .unwrap()
+        .version;
+
+    assert_eq!(
+        version.info(),
+        Some(VersionInfo {
+            xschem: Some((3, 4, 5)),
+            file_version: Some((1, 2)),
+        })
+    );
+
+    let version = Version(Property {
+        prop: "xschem version=3.4 file_version=1.2.3",
+        attrs: [("version", "3.4"), ("file_version", "1.2.3")].into(),
+    });
+
+    assert_eq!(
+        version.info(),
+        Some(VersionInfo {
+            xschem: None,
+            file_version: None,
+        })
+    );
+
+    let version = Version(Property {
+        prop: "file_version=1.0",
+        attrs: [("file_version", "1.0")].into(),
+    });
+
+    assert_eq!(
+        version.info(),
+        Some(VersionInfo {
+            xschem: None,
+            file_version: Some((1, 0)),
+        })
+    );
+    assert_eq!(Version::<&str>::default().info(), None);
+}
+
+#[test]
+fn unescaped() {
+    let property = Property {
+        prop: r"\\\}",
+        attrs: [].into(),
+    };
+    assert_eq!(property.unescaped(), r"\}");
+
+    let property = Property {
+        prop: r"a\b \{c\}",
+        attrs: [].into(),
+    };
+    assert_eq!(property.unescaped(), r"a\b {c}");
+
+    let text = Text {
+        text: r"\{@name\}\\",
+        ..Default::default()
+    };
+    assert_eq!(text.unescaped_text(), r"{@name}\");
+
+    let schematic = crate::from_str(r#"v {key="a \\"b\\" \{c\}" k=\\\\val}"#).unwrap();
+    let property = schematic.version.0;
+    assert_eq!(
+        property.get_unescaped("key").as_deref(),
+        Some(r#"a "b" {c}"#)
+    );
+    assert_eq!(property.get_unescaped("k").as_deref(), Some(r"\\val"));
+    assert_eq!(property.get_unescaped("missing"), None);
+}
+
+#[test]
+fn prop_normalized() {
+    let schematic =
+        crate::from_str("v {xschem version=3.4.5 file_version=1.2\r\n* copyright\r\n}\r\nT {a\r\nb} 0 0 0 0 0.2 0.2 {}").unwrap();
+    let property = &schematic.version.0;
+    assert_eq!(
+        property.prop_normalized(),
+        "xschem version=3.4.5 file_version=1.2\n* copyright\n"
+    );
+    assert!(property.prop.fragment().contains('\r'));
+    assert_eq!(schematic.texts[0].text_normalized(), "a\nb");
+
+    let property = Property {
+        prop: "a=1\nb=2",
+        attrs: [].into(),
+    };
+    assert!(matches!(
+        property.prop_normalized(),
+        Cow::Borrowed("a=1\nb=2")
+    ));
+}
+
+#[test]
+fn text_resolve() {
+    let input = "\
+v {xschem version=3.4.5 file_version=1.2}
+C {nand.sym} 0 0 0 0 {name=U1 model=\"74\\\\\"HC\\\\\"00\"}";
+    let schematic = crate::from_str(input).unwrap();
+    let property = &schematic.components[0].property;
+    let text = |text| Text {
+        text,
+        ..Default::default()
+    };
+
+    assert_eq!(text("@name").resolve(property), "U1");
+    assert_eq!(
+        text("\\{@name\\}: @model").resolve(property),
+        r#"{U1}: 74"HC"00"#
+    );
+    assert_eq!(
+        text("@names @#0:net_name @").resolve(property),
+        "@names @#0:net_name @"
+    );
+    assert_eq!(text("a@name.b").resolve(property), "aU1.b");
+}
+
+#[test]
+fn objects_hash_set() {
+    let input = "\
+v {xschem version=3.4.5 file_version=1.2}
+N 0 0 10 0 {lab=A}
+N 0 -0 10 0 {lab=A}
+N 0 0 10 0 {lab=B}
+C {res.sym} 0 0 0 0 {name=R1}
+C {res.sym} 0 0 0 0 {name=R1}";
+    let schematic = crate::from_str_plain(input).unwrap();
+
+    let wires: HashSet<_> = schematic.wires.iter().collect();
+    assert_eq!(wires.len(), 2);
+    let equal: HashSet<_> = schematic.wires.iter().take(2).collect();
+    assert_eq!(equal.len(), 1);
+    let components: HashSet<_> = schematic.components.iter().collect();
+    assert_eq!(components.len(), 1);
+}
+
+#[test]
+fn quoted_values() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+                 K {type=subcircuit\nformat=\"@name @pinlist @symname\"\nempty=\"\"}\n";
+    let schematic = crate::from_str(input).unwrap();
+    let property = &schematic.symbol_property.as_ref().unwrap().0;
+
+    assert_eq!(
+        property.get("format").map(|v| *v.fragment()),
+        Some(r#""@name @pinlist @symname""#)
+    );
+    assert_eq!(
+        property.get_value("format"),
+        Some("@name @pinlist @symname")
+    );
+    assert_eq!(property.is_quoted("format"), Some(true));
+    assert_eq!(property.get_value("type"), Some("subcircuit"));
+    assert_eq!(property.is_quoted("type"), Some(false));
+    assert_eq!(property.get_value("empty"), Some(""));
+    assert_eq!(property.is_quoted("missing"), None);
+
+    let output = schematic.to_xschem_string();
+    assert_eq!(output, input);
+    let result = crate::from_str(&output).unwrap();
+    assert_eq!(
+        result.map(|s| *s.fragment()),
+        schematic.map(|s| *s.fragment())
+    );
+}
+
+#[test]
+fn property_get_list() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+                 K {pinseq=1,2,3 pinnumber=\" A, B ,,C \" empty=\"\"\n\
+                 format=\"@name\n@pinlist  @symname\"\n\
+                 names=\"a\\\\\"b\\\\\", c\" braces=a\\{b\\},c}\n";
+    let schematic = crate::from_str(input).unwrap();
+    let property = &schematic.symbol_property.as_ref().unwrap().0;
+
+    assert_eq!(property.get_list("pinseq", ',').unwrap(), ["1", "2", "3"]);
+    assert_eq!(
+        property.get_list("pinnumber", ',').unwrap(),
+        ["A", "B", "", "C"]
+    );
+    assert_eq!(
+        property.get_list("format", ' ').unwrap(),
+        ["@name", "@pinlist", "@symname"]
+    );
+    assert_eq!(property.get_list("names", ',').unwrap(), ["a\"b\"", "c"]);
+    assert_eq!(property.get_list("braces", ',').unwrap(), ["a{b}", "c"]);
+    assert!(property.get_list("empty", ',').unwrap().is_empty());
+    assert_eq!(property.get_list("missing", ','), None);
+}
+
+#[test]
+fn finite_double() {
+    assert_eq!(FiniteDouble::new(1.5).map(|d| d.value()), Some(1.5));
+    assert_eq!(FiniteDouble::new(f64::INFINITY), None);
+    assert_eq!(FiniteDouble::new(f64::NAN), None);
+    assert_eq!(FiniteDouble::new(-0.0), FiniteDouble::new(0.0));
+}
+
+#[test]
+fn vec2_map_key() {
+    let point = |x, y| Vec2 {
+        x: FiniteDouble::new(x).unwrap(),
+        y: FiniteDouble::new(y).unwrap(),
+    };
+
+    let mut nets = HashMap::new();
+    nets.insert(point(10.0, 0.0), "A");
+    nets.insert(point(0.0, -20.0), "B");
+
+    assert_eq!(nets.get(&point(10.0, -0.0)), Some(&"A"));
+    assert_eq!(nets.get(&point(-0.0, -20.0)), Some(&"B"));
+    assert_eq!(nets.get(&point(10.0, 20.0)), None);
+}
+
+#[test]
+fn find_components() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let schematic = crate::from_str(input).unwrap();
+
+    let component = schematic.find_component_by_name("C4").unwrap();
+    assert_eq!(*component.reference.fragment(), "capa.sym");
+    assert_eq!(component.position, (890.0, -160.0).try_into().unwrap());
+    assert_eq!(
+        component.property.get_value("device"),
+        Some("tantalium capacitor")
+    );
+    assert!(schematic.find_component_by_name("U3").is_none());
+
+    let nands: Vec<_> = schematic
+        .components_where(|c| *c.reference.fragment() == "74ls00.sym")
+        .filter_map(|c| c.property.get_value("name"))
+        .collect();
+    assert_eq!(nands, ["U1:2", "U1:1", "U1:4"]);
+}
+
+#[test]
+fn component_symbol() {
+    let component = |reference| Component {
+        reference,
+        position: (0.0, 0.0).try_into().unwrap(),
+        rotation: Rotation::Zero,
+        flip: Flip::Unflipped,
+        extra: Vec::new(),
+        property: Property::default(),
+        embedding: None,
+    };
+
+    let plain = component("capa.sym");
+    assert_eq!(plain.symbol_name(), "capa.sym");
+    assert_eq!(plain.symbol_path(), Some("capa.sym"));
+    assert_eq!(plain.symbol_stem(), "capa");
+
+    let pathful = component(" devices/lab_pin.sym ");
+    assert_eq!(pathful.symbol_name(), "devices/lab_pin.sym");
+    assert_eq!(pathful.symbol_path(), Some("devices/lab_pin.sym"));
+    assert_eq!(pathful.symbol_stem(), "lab_pin");
+
+    assert_eq!(component("schematic.sch").symbol_stem(), "schematic.sch");
+    assert_eq!(component("").symbol_path(), None);
+    assert_eq!(component(" - ").symbol_path(), None);
+}
+
+#[test]
+fn reference_trimmed() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}\nC {  capa.sym\t} 0 0 0 0 {}\nT { a b } 0 0 0 0 0.2 0.2 {}";
+    let schematic = crate::from_str(input).unwrap();
+
+    let reference = schematic.components[0].reference_trimmed();
+    assert_eq!(*reference.fragment(), "capa.sym");
+    assert_eq!(reference.location_line(), 2);
+    assert_eq!(reference.get_column(), 6);
+    assert_eq!(*schematic.texts[0].text_trimmed().fragment(), "a b");
+
+    let blank = Component {
+        reference: "  ",
+        ..Default::default()
+    };
+    assert_eq!(blank.reference_trimmed(), "");
+}
+
+#[test]
+fn schematic_mutable_builder() {
+    let version = Version(Property {
+        prop: "xschem version=3.4.5 file_version=1.2",
+        attrs: [("version", "3.4.5"), ("file_version", "1.2")].into(),
+    });
+    let wire = Wire {
+        start: (0.0, 0.0).try_into().unwrap(),
+        end: (10.0, 0.0).try_into().unwrap(),
+        property: Property {
+            prop: "lab=A",
+            attrs: [("lab", "A")].into(),
+        },
+    };
+    let component = Component {
+        reference: "res.sym",
+        position: (10.0, 30.0).try_into().unwrap(),
+        rotation: Rotation::One,
+        flip: Flip::Unflipped,
+        extra: Vec::new(),
+        property: Property::default(),
+        embedding: None,
+    };
+    let arc = Arc {
+        layer: 4,
+        center: (0.0, 0.0).try_into().unwrap(),
+        radius: 5.0.try_into().unwrap(),
+        start_angle: 0.0.try_into().unwrap(),
+        sweep_angle: 360.0.try_into().unwrap(),
+        property: Property::default(),
+    };
+    let spice = SpiceProperty(Property {
+        prop: ".end",
+        attrs: crate::HashMap::new(),
+    });
+
+    let expected = Schematic::new(version.clone())
+        .add_object(wire.clone().into())
+        .add_object(component.clone().into())
+        .add_object(spice.clone().into())
+        .add_object(arc.clone().into())
+        .add_object(wire.clone().into());
+
+    let mut schematic = Schematic::new(version);
+    for i in 0..2 {
+        schematic.push_wire(wire.clone());
+        if i == 0 {
+            schematic.push_component(component.clone());
+            schematic.add_object_mut(spice.clone().into());
+            schematic.push_arc(arc.clone());
+        }
+    }
+
+    assert_eq!(schematic, expected);
+    assert_eq!(schematic.order, expected.order);
+    assert_eq!(schematic.to_string(), expected.to_string());
+}
+
+#[test]
+fn objects_into_iter() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let schematic = crate::from_str(input).unwrap();
+
+    let mut wires: Objects<Wire<_>> = schematic
+        .wires
+        .iter()
+        .filter(|w| {
+            w.property
+                .get("lab")
+                .is_some_and(|l| *l.fragment() == "ANALOG_GND")
+        })
+        .cloned()
+        .collect();
+    assert_eq!(wires.len(), 5);
+
+    for wire in &mut wires {
+        wire.start = wire.end;
+    }
+    for wire in &wires {
+        assert_eq!(wire.start, wire.end);
+    }
+
+    let mut count = 0;
+    for wire in wires {
+        assert_eq!(*wire.property.prop.fragment(), "lab=ANALOG_GND");
+        count += 1;
+    }
+    assert_eq!(count, 5);
+}
+
+#[test]
+fn schematic_objects_on_layer() {
+    let input = include_str!("../../../../assets/pmos.sym");
+    let schematic = crate::from_str(input).unwrap();
+
+    assert_eq!(schematic.lines_on_layer(4).count(), 9);
+    assert_eq!(schematic.lines_on_layer(18).count(), 1);
+    assert_eq!(schematic.rectangles_on_layer(5).count(), 4);
+    assert_eq!(schematic.arcs_on_layer(4).count(), 1);
+    assert_eq!(schematic.polygons_on_layer(4).count(), 0);
+    assert_eq!(schematic.lines_on_layer(5).count(), 0);
+
+    let on_layer_4: Vec<_> = schematic.objects_on_layer(4).collect();
+    assert_eq!(on_layer_4.len(), 10);
+    assert!(on_layer_4.iter().all(|o| o.layer() == Some(4)));
+    assert!(matches!(on_layer_4[0], ObjectRef::Line(_)));
+    for layer in schematic.layers() {
+        assert!(schematic.objects_on_layer(layer).next().is_some());
+    }
+    assert_eq!(schematic.objects_on_layer(99).count(), 0);
+}
+
+#[test]
+fn schematic_remove_objects() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let mut schematic = crate::from_str(input).unwrap();
+    let texts = schematic.texts.len();
+    let others = schematic.objects().count() - texts;
+    assert!(texts > 0);
+
+    let removed = schematic.remove_objects(|o| matches!(o, ObjectRef::Text(_)));
+    assert_eq!(removed, texts);
+    assert!(schematic.texts.is_empty());
+    assert_eq!(schematic.objects().count(), others);
+    assert_eq!(schematic.order.len(), others);
+    let output = schematic.to_string();
+    assert!(!output.contains("\nT {"));
+    let without_texts = ObjectMask::ARC
+        | ObjectMask::COMPONENT
+        | ObjectMask::LINE
+        | ObjectMask::POLYGON
+        | ObjectMask::RECTANGLE
+        | ObjectMask::WIRE;
+    let options = ParseOptions::new().keep(without_texts);
+    let expected = crate::parse_with_options(Span::new(input), &options).unwrap();
+    assert_eq!(output, expected.to_string());
+    assert_eq!(schematic.order, expected.order);
+    assert_eq!(schematic.remove_objects(|_| false), 0);
+
+    // Objects not tracked by the order are removed as well.
+    let mut schematic = crate::from_str_plain("v {}\nN 0 0 10 0 {lab=A}\nL 4 0 0 1 0 {}").unwrap();
+    let origin: Vec2 = (0.0, 0.0).try_into().unwrap();
+    let end: Vec2 = (0.0, 10.0).try_into().unwrap();
+    schematic.wires.push(Wire::new(origin, end));
+    schematic
+        .wires
+        .push(Wire::new(origin, (0.0, 20.0).try_into().unwrap()));
+    let mut visited = Vec::new();
+    let removed = schematic.remove_objects(|o| {
+        visited.push(o.kind());
+        matches!(o, ObjectRef::Wire(w) if w.end == end)
+    });
+    assert_eq!(removed, 1);
+    assert_eq!(
+        visited,
+        [
+            ObjectKind::Wire,
+            ObjectKind::Line,
+            ObjectKind::Wire,
+            ObjectKind::Wire
+        ]
+    );
+    assert_eq!(
+        schematic.to_string(),
+        "v {}\nN 0 0 10 0 {lab=A}\nL 4 0 0 1 0 {}\nN 0 0 0 20 {}"
+    );
+}
+
+#[test]
+fn schematic_layers() {
+    let input = "\
+v {xschem version=3.4.5 file_version=1.2}
+L 4 0 0 10 0 {}
+B 5 -2.5 -2.5 2.5 2.5 {name=p dir=in}
+P 3 3 0 0 10 0 0 0 {}
+A 4 0 0 5 0 360 {}
+N 0 0 10 0 {lab=A}
+T {a} 0 0 0 0 0.2 0.2 {layer=8}
+T {b} 0 0 0 0 0.2 0.2 {layer=x}
+";
+    let schematic = crate::from_str(input).unwrap();
+
+    assert_eq!(
+        schematic.layers().into_iter().collect::<Vec<_>>(),
+        [3, 4, 5]
+    );
+    assert_eq!(
+        schematic
+            .layers_with_texts()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        [3, 4, 5, 8]
+    );
+    assert_eq!(schematic.texts[1].layer(), None);
+    assert!(Schematic::<&str>::default().layers().is_empty());
+}
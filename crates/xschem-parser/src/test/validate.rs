@@ -0,0 +1,132 @@
+use crate::validate::{Severity, Validation, ValidationKind};
+
+#[test]
+fn validate_duplicate_name() {
+    let input = "\
+v {xschem version=3.4.5 file_version=1.2}
+C {res.sym} 0 0 0 0 {name=R1}
+C {res.sym} 20 0 0 0 {name=R2}
+C {res.sym} 40 0 0 0 {name=\"R1\"}
+C {gnd.sym} 60 0 0 0 {}";
+    let schematic = crate::from_str(input).unwrap();
+    let validations = schematic.validate();
+
+    assert_eq!(validations.len(), 2);
+    let duplicate = &validations[0];
+    assert_eq!(duplicate.kind, ValidationKind::DuplicateName);
+    assert_eq!(duplicate.severity, Severity::Error);
+    assert_eq!(*duplicate.span.fragment(), "\"R1\"");
+    assert_eq!(duplicate.span.location_line(), 4);
+    assert_eq!(validations[1].kind, ValidationKind::MissingName);
+    assert_eq!(*validations[1].span.fragment(), "gnd.sym");
+}
+
+#[test]
+fn validate_zero_length_wire() {
+    let input = "\
+v {xschem version=3.4.5 file_version=1.2}
+N 0 0 10 0 {lab=a}
+N 10 -0 10 0 {lab=b}";
+    let schematic = crate::from_str_plain(input).unwrap();
+
+    assert_eq!(
+        schematic.validate(),
+        [Validation {
+            severity: Severity::Warning,
+            kind: ValidationKind::ZeroLengthWire,
+            span: "lab=b",
+        }]
+    );
+}
+
+#[test]
+fn validate_non_positive_radius() {
+    let input = "\
+v {xschem version=3.4.5 file_version=1.2}
+A 3 0 0 -5 0 90 {}
+A 3 0 0 0 0 360 {name=zero}
+A 3 0 0 5 0 90 {name=valid}";
+    let schematic = crate::from_str_plain(input).unwrap();
+    assert_eq!(schematic.arcs[0].radius, (-5.0).try_into().unwrap());
+
+    assert_eq!(
+        schematic.validate(),
+        [
+            Validation {
+                severity: Severity::Error,
+                kind: ValidationKind::NonPositiveRadius,
+                span: "",
+            },
+            Validation {
+                severity: Severity::Error,
+                kind: ValidationKind::NonPositiveRadius,
+                span: "name=zero",
+            },
+        ]
+    );
+}
+
+#[test]
+fn validate_pcb_test1_sch() {
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let schematic = crate::from_str(input).unwrap();
+
+    assert!(
+        schematic
+            .validate()
+            .iter()
+            .all(|v| v.severity != Severity::Error)
+    );
+}
+
+#[test]
+fn erc_floating_labels() {
+    let input = "\
+v {xschem version=3.4.5 file_version=1.2}
+N 0 0 10 0 {lab=IN}
+N 10 0 10 20 {lab=IN}
+N 100 0 110 0 {lab=IN}
+N 50 50 60 50 {lab=\"OUT\"}
+C {res.sym} 10 20 0 0 {name=R1}
+C {lab_pin.sym} 60 50 0 0 {name=p1 lab=VDD}";
+    let schematic = crate::from_str(input).unwrap();
+    let warnings = schematic.erc_floating_labels();
+
+    let spans: Vec<_> = warnings
+        .iter()
+        .map(|v| (*v.span.fragment(), v.span.location_line()))
+        .collect();
+    assert_eq!(spans, [("\"OUT\"", 5), ("VDD", 7)]);
+    assert!(
+        warnings
+            .iter()
+            .all(|v| v.kind == ValidationKind::SingleUseLabel && v.severity == Severity::Warning)
+    );
+}
+
+#[test]
+fn erc_floating_labels_pin() {
+    let input = "\
+v {xschem version=3.4.5 file_version=1.2}
+N 0 0 10 0 {lab=LONE}
+C {lab_pin.sym} 0 0 0 0 {name=p1 lab=LONE}
+N 0 50 10 50 {lab=BOTH}
+C {lab_pin.sym} 10 50 0 0 {name=p2 lab=BOTH}
+C {lab_pin.sym} 100 100 0 0 {name=p3 lab=BOTH}";
+    let schematic = crate::from_str(input).unwrap();
+    let spans: Vec<_> = schematic
+        .erc_floating_labels()
+        .iter()
+        .map(|v| (*v.span.fragment(), v.span.location_line()))
+        .collect();
+    assert_eq!(spans, [("LONE", 2)]);
+
+    let input = include_str!("../../../../assets/pcb_test1.sch");
+    let schematic = crate::from_str(input).unwrap();
+    let labels: Vec<_> = schematic
+        .erc_floating_labels()
+        .iter()
+        .map(|v| *v.span.fragment())
+        .collect();
+    assert!(labels.contains(&"VCC5"), "{labels:?}");
+}
@@ -0,0 +1,53 @@
+use crate::Span;
+use crate::token::{Component, Embedding, Wire};
+use crate::visit::Visitor;
+
+#[derive(Default)]
+struct Counter {
+    wires: usize,
+    components: Vec<String>,
+    embeddings: usize,
+}
+
+impl Visitor<Span<'_>> for Counter {
+    fn visit_component(&mut self, component: &Component<Span<'_>>) {
+        self.components.push(component.reference.to_string());
+    }
+
+    fn visit_embedding(&mut self, _embedding: &Embedding<Span<'_>>) {
+        self.embeddings += 1;
+    }
+
+    fn visit_wire(&mut self, _wire: &Wire<Span<'_>>) {
+        self.wires += 1;
+    }
+}
+
+#[test]
+fn visit_wires_in_embeddings() {
+    let input = "\
+v {xschem version=3.4.5 file_version=1.2}
+N 0 0 10 0 {}
+C {inv.sym} 0 0 0 0 {name=x1}
+[
+v {xschem version=3.4.5 file_version=1.2}
+N 0 0 0 10 {}
+N 0 10 0 20 {}
+C {nmos.sym} 0 0 0 0 {name=m1}
+[
+v {xschem version=3.4.5 file_version=1.2}
+N 0 0 5 0 {}
+]
+]
+C {res.sym} 100 0 0 0 {name=R1}
+N 10 0 20 0 {}
+";
+    let schematic = crate::from_str(input).unwrap();
+
+    let mut counter = Counter::default();
+    schematic.accept(&mut counter);
+    assert_eq!(schematic.wires.len(), 2);
+    assert_eq!(counter.wires, 5);
+    assert_eq!(counter.embeddings, 2);
+    assert_eq!(counter.components, ["inv.sym", "nmos.sym", "res.sym"]);
+}
@@ -0,0 +1,111 @@
+use crate::Span;
+use crate::options::ParseOptions;
+use crate::test::ASSETS;
+use crate::write::FloatFormat;
+
+#[test]
+fn round_trip_assets() {
+    for input in ASSETS {
+        let schematic = crate::from_str(input).unwrap();
+        let output = schematic.to_xschem_string();
+        let result = crate::from_str(&output).unwrap();
+
+        assert_eq!(
+            result.map(|s| *s.fragment()),
+            schematic.map(|s| *s.fragment())
+        );
+        assert_eq!(result.order, schematic.order);
+        assert_eq!(result.to_xschem_string(), output);
+    }
+}
+
+#[test]
+fn round_trip_unknown_objects() {
+    let options = ParseOptions::new().unknown_objects(true);
+    let input = "v {}\nZ {foo=bar}\nN 0 0 10 0 {}";
+    let schematic = crate::parse_with_options(Span::new(input), &options).unwrap();
+    let output = schematic.to_xschem_string();
+
+    let result = crate::parse_with_options(Span::new(&output), &options).unwrap();
+    assert!(result.content_eq(&schematic));
+    assert!(crate::from_str(&output).is_err());
+}
+
+#[test]
+fn round_trip_escapes() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+                 K {type=\"a \\\\\"b\\\\\"\" format=\\{x\\}}\n\
+                 T {\\{@name\\}} -1e-7 1e10 0 1 0.2 0.2 {}\n\
+                 P 4 2 0 0 1 1 {fill=true}\n\
+                 N 0 0 10 0 {lab=A}\n\
+                 B 5 0 0 1 1 {}\n";
+    let schematic = crate::from_str(input).unwrap();
+    let output = schematic.to_xschem_string();
+    let result = crate::from_str(&output).unwrap();
+
+    assert_eq!(
+        result.map(|s| *s.fragment()),
+        schematic.map(|s| *s.fragment())
+    );
+    assert_eq!(result.order, schematic.order);
+}
+
+#[test]
+fn write_float_formats() {
+    let input = "v {xschem version=3.4.5 file_version=1.2}\nT {@name} -17.5 -15.0 0 0 0.2 0.2 {}";
+    let schematic = crate::from_str(input).unwrap();
+
+    assert_eq!(
+        schematic.to_xschem_string_with_format(FloatFormat::Shortest),
+        "v {xschem version=3.4.5 file_version=1.2}\nT {@name} -17.5 -15 0 0 0.2 0.2 {}\n"
+    );
+    assert_eq!(
+        schematic.to_xschem_string_with_format(FloatFormat::Fixed(3)),
+        "v {xschem version=3.4.5 file_version=1.2}\nT {@name} -17.500 -15.000 0 0 0.200 0.200 {}\n"
+    );
+
+    for (value, expected) in [
+        (-15.0, "-15"),
+        (0.1 + 0.2, "0.3"),
+        (1e21, "1e+21"),
+        (1.5e-7, "1.5e-07"),
+        (0.0001, "0.0001"),
+        (123_456.0, "123456"),
+        (-0.0, "-0"),
+    ] {
+        assert_eq!(FloatFormat::Xschem.display(value).to_string(), expected);
+    }
+}
+
+#[test]
+fn write_with_format_matches_display() {
+    for input in ASSETS {
+        let schematic = crate::from_str(input).unwrap();
+        let output = schematic.to_xschem_string();
+
+        assert_eq!(
+            schematic.to_xschem_string_with_format(FloatFormat::Shortest),
+            output
+        );
+        assert_eq!(
+            schematic.to_xschem_string_with_format(FloatFormat::Xschem),
+            output
+        );
+    }
+}
+
+#[test]
+fn write_to_matches_display() {
+    for input in ASSETS {
+        let schematic = crate::from_str(input).unwrap();
+        let mut output = Vec::new();
+        schematic.write_to(&mut output).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), schematic.to_string());
+    }
+
+    let schematic = crate::from_str(ASSETS[2]).unwrap();
+    let mut output = [0u8; 16];
+    let error = schematic.write_to(&mut output.as_mut_slice()).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::WriteZero);
+}
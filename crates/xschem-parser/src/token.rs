@@ -1,17 +1,32 @@
 //! Parsed data structures.
-use std::collections::HashMap;
-use std::fmt;
-use std::fmt::Formatter;
-use std::hash::Hash;
-use std::vec::Vec;
+use alloc::borrow::Cow;
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::fmt::Formatter;
+use core::hash::{Hash, Hasher};
+use core::ops::{Index, IndexMut};
+use core::slice::{self, SliceIndex};
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
 
 use derive_more::{Deref, DerefMut, Display, From, Into, TryFrom};
+use nom::{AsChar, Compare, Input, Offset};
+use nom_locate::LocatedSpan;
 
 use crate::error::Error;
-use crate::{ByteSpan, Span, parse};
+#[cfg(feature = "std")]
+use crate::error::FileError;
+use crate::parse::{ESCAPED_CHARS, ESCAPED_VALUE_CHARS, unescape};
+use crate::write::{FloatFormat, write_object, write_schematic};
+use crate::{ByteSpan, HashMap, Span, parse};
 
 /// Xschem schematic (or symbol).
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Schematic<I> {
     pub version: Version<I>,
     pub vhdl_property: Option<VhdlProperty<I>>,
@@ -26,46 +41,121 @@ pub struct Schematic<I> {
     pub arcs: Objects<Arc<I>>,
     pub wires: Objects<Wire<I>>,
     pub components: Objects<Component<I>>,
+    /// Objects with an unknown tag, only parsed if enabled by
+    /// [`ParseOptions::unknown_objects`].
+    ///
+    /// [`ParseOptions::unknown_objects`]: crate::options::ParseOptions::unknown_objects
+    pub unknown_objects: Objects<UnknownObject<I>>,
+    /// Order in which objects appear in the input.
+    ///
+    /// The n-th occurrence of a kind refers to the n-th object in the
+    /// collection of that kind. Objects that are not tracked by the order are
+    /// iterated and displayed after the ordered objects, grouped by type.
+    /// The order is not taken into account when comparing schematics.
+    pub order: Vec<ObjectKind>,
+}
+
+/// Version and global properties of a schematic (or symbol).
+///
+/// Created with [`parse_header`](crate::parse_header).
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SchematicHeader<I> {
+    pub version: Version<I>,
+    pub vhdl_property: Option<VhdlProperty<I>>,
+    pub symbol_property: Option<SymbolProperty<I>>,
+    pub verilog_property: Option<VerilogProperty<I>>,
+    pub spice_property: Option<SpiceProperty<I>>,
+    pub tedax_property: Option<TedaXProperty<I>>,
 }
 
 /// Xschem property string with parsed attributes.
 #[derive(Clone, Debug, Default, Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[display("{{{prop}}}")]
 pub struct Property<I> {
     /// Full property input.
     pub prop: I,
     /// Parsed attributes from `prop`.
+    ///
+    /// Unquoted values end at ASCII whitespace, quoted values keep their
+    /// quotes and may span multiple lines.
+    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_attrs"))]
     pub attrs: HashMap<I, I>,
 }
 
+/// Serializes attributes as a map, without requiring keys to be hashable.
+#[cfg(feature = "serde")]
+fn serialize_attrs<I: serde::Serialize, S: serde::Serializer>(
+    attrs: &HashMap<I, I>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(attrs)
+}
+
 /// Xschem schematic or symbol version specifiication.
 #[derive(Clone, Debug, Default, Display)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[display("v {_0}")]
 pub struct Version<I>(pub Property<I>);
 
+/// Versions declared by a [`Version`] object.
+///
+/// Fields are [`None`] if the attribute is missing or malformed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VersionInfo {
+    /// Xschem application version as major, minor and patch.
+    pub xschem: Option<(u32, u32, u32)>,
+    /// File format version as major and minor.
+    pub file_version: Option<(u32, u32)>,
+}
+
+/// Global VHDL property (`G`), usually a block of VHDL code.
+///
+/// Attributes are parsed like for any property, but are only meaningful if
+/// the property holds attributes rather than code. Use [`VhdlProperty::code`]
+/// to get the code.
 #[derive(Clone, Debug, Default, Deref, Display, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[display("G {_0}")]
 pub struct VhdlProperty<I>(pub Property<I>);
 
 #[derive(Clone, Debug, Default, Deref, Display, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[display("K {_0}")]
 pub struct SymbolProperty<I>(pub Property<I>);
 
+/// Global Verilog property (`V`), usually a block of Verilog code.
+///
+/// Attributes are parsed like for any property, but are only meaningful if
+/// the property holds attributes rather than code. Use
+/// [`VerilogProperty::code`] to get the code.
 #[derive(Clone, Debug, Default, Deref, Display, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[display("V {_0}")]
 pub struct VerilogProperty<I>(pub Property<I>);
 
+/// Global SPICE property (`S`), usually a block of SPICE code.
+///
+/// Attributes are parsed like for any property, but are only meaningful if
+/// the property holds attributes rather than code. Use [`SpiceProperty::code`]
+/// to get the code.
 #[derive(Clone, Debug, Default, Deref, Display, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[display("S {_0}")]
 pub struct SpiceProperty<I>(pub Property<I>);
 
 #[derive(Clone, Debug, Default, Deref, Display, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[display("E {_0}")]
 pub struct TedaXProperty<I>(pub Property<I>);
 
 #[derive(Clone, Debug, From)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[from(forward)]
 #[allow(clippy::large_enum_variant)]
+#[non_exhaustive]
 pub enum Object<I> {
     SpiceProperty(SpiceProperty<I>),
     VerilogProperty(VerilogProperty<I>),
@@ -80,17 +170,72 @@ pub enum Object<I> {
     Rectangle(Rectangle<I>),
     Text(Text<I>),
     Wire(Wire<I>),
+    Unknown(UnknownObject<I>),
+}
+
+/// Kind of object in a [`Schematic`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[non_exhaustive]
+pub enum ObjectKind {
+    Arc,
+    Component,
+    Line,
+    Polygon,
+    Rectangle,
+    Text,
+    Wire,
+    Unknown,
+}
+
+/// Borrowed reference to an object in a [`Schematic`].
+#[derive(Debug, Display, From)]
+#[non_exhaustive]
+pub enum ObjectRef<'a, I> {
+    Arc(&'a Arc<I>),
+    Component(&'a Component<I>),
+    Line(&'a Line<I>),
+    Polygon(&'a Polygon<I>),
+    Rectangle(&'a Rectangle<I>),
+    Text(&'a Text<I>),
+    Wire(&'a Wire<I>),
+    Unknown(&'a UnknownObject<I>),
 }
 
 #[derive(Clone, Debug, Deref, DerefMut, From, Into, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Objects<O>(pub Vec<O>);
 
+/// Iterator over the objects of a [`Schematic`] in input order.
+///
+/// Created with [`Schematic::objects`].
+#[derive(Clone, Debug)]
+pub struct ObjectIter<'a, I> {
+    order: slice::Iter<'a, ObjectKind>,
+    arcs: slice::Iter<'a, Arc<I>>,
+    components: slice::Iter<'a, Component<I>>,
+    lines: slice::Iter<'a, Line<I>>,
+    polygons: slice::Iter<'a, Polygon<I>>,
+    rectangles: slice::Iter<'a, Rectangle<I>>,
+    texts: slice::Iter<'a, Text<I>>,
+    wires: slice::Iter<'a, Wire<I>>,
+    unknown_objects: slice::Iter<'a, UnknownObject<I>>,
+}
+
 /// Xschem arc object.
-#[derive(Clone, Debug, Default, Display)]
-#[display("A {layer} {center} {radius} {start_angle} {sweep_angle} {property}")]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Arc<I> {
     pub layer: u64,
     pub center: Coordinate,
+    /// Radius of the arc.
+    ///
+    /// Zero and negative radii are parsed like Xschem does, and reported by
+    /// [`Schematic::validate`] as [`NonPositiveRadius`]. Geometry helpers use
+    /// the absolute value, so an arc with a zero radius is a point at its
+    /// center.
+    ///
+    /// [`NonPositiveRadius`]: crate::validate::ValidationKind::NonPositiveRadius
     pub radius: FiniteDouble,
     pub start_angle: FiniteDouble,
     pub sweep_angle: FiniteDouble,
@@ -99,18 +244,27 @@ pub struct Arc<I> {
 
 /// Xschem component instance.
 #[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Component<I> {
     pub reference: I,
     pub position: Coordinate,
     pub rotation: Rotation,
     pub flip: Flip,
+    /// Unknown tokens between the flip and the property.
+    ///
+    /// No file version up to [`SUPPORTED_FILE_VERSION`] defines such tokens,
+    /// so they are only accepted in files declaring a newer `file_version`,
+    /// and are kept to write the component back unchanged.
+    ///
+    /// [`SUPPORTED_FILE_VERSION`]: crate::options::SUPPORTED_FILE_VERSION
+    pub extra: Vec<I>,
     pub property: Property<I>,
     pub embedding: Option<Embedding<I>>,
 }
 
 /// Xschem line object.
-#[derive(Clone, Debug, Default, Display)]
-#[display("L {layer} {start} {end} {property}")]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Line<I> {
     pub layer: u64,
     pub start: Coordinate,
@@ -119,8 +273,8 @@ pub struct Line<I> {
 }
 
 /// Xschem polygon object.
-#[derive(Clone, Debug, Default, Display)]
-#[display("P {layer} {npoints} {points} {property}", npoints = points.len())]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Polygon<I> {
     pub layer: u64,
     pub points: Coordinates,
@@ -128,8 +282,8 @@ pub struct Polygon<I> {
 }
 
 /// Xschem rectangle object.
-#[derive(Clone, Debug, Default, Display)]
-#[display("B {layer} {start} {end} {property}")]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Rectangle<I> {
     pub layer: u64,
     pub start: Coordinate,
@@ -138,8 +292,8 @@ pub struct Rectangle<I> {
 }
 
 /// Xschem text object.
-#[derive(Clone, Debug, Default, Display)]
-#[display("T {{{text}}} {position} {rotation} {flip} {size} {property}")]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Text<I> {
     pub text: I,
     pub position: Coordinate,
@@ -150,8 +304,8 @@ pub struct Text<I> {
 }
 
 /// Xschem wire object.
-#[derive(Clone, Debug, Default, Display)]
-#[display("N {start} {end} {property}")]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Wire<I> {
     pub start: Coordinate,
     pub end: Coordinate,
@@ -159,14 +313,127 @@ pub struct Wire<I> {
 }
 
 #[derive(Clone, Debug, Default, Deref, Display, From, Into)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[display("[\n{_0}\n]")]
 pub struct Embedding<I>(pub Schematic<I>);
 
+/// Object with a tag that is not known to this library, such as an object
+/// introduced by a newer Xschem version.
+///
+/// The tokens between the tag and the property are kept as is, so the object
+/// can be written back unchanged, but they are not interpreted.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct UnknownObject<I> {
+    pub tag: I,
+    pub tokens: Vec<I>,
+    pub property: Property<I>,
+}
+
+/// Value with the span of the input it was parsed from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Spanned<I, T> {
+    pub value: T,
+    pub span: I,
+}
+
+/// Field of an object, as parsed by [`object_fields`].
+///
+/// Fields are in the order of the object, so the two angles of an arc are
+/// its start and sweep angle, and a coordinate or size is an [`Field::X`]
+/// followed by a [`Field::Y`].
+///
+/// [`object_fields`]: crate::parse::object_fields
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Field<I> {
+    Layer(u64),
+    /// X coordinate of a point, such as the center of an arc, an end point of
+    /// a line, rectangle or wire, the position of a component or text, or a
+    /// point of a polygon, or the horizontal size of a text.
+    X(FiniteDouble),
+    /// Y coordinate of a point or the vertical size of a text, see
+    /// [`Field::X`].
+    Y(FiniteDouble),
+    Radius(FiniteDouble),
+    Angle(FiniteDouble),
+    /// Declared number of points of a polygon.
+    PointCount(usize),
+    Rotation(Rotation),
+    Flip(Flip),
+    Reference(I),
+    Text(I),
+    /// Unknown token of a component or unknown object.
+    Token(I),
+    Property(I),
+}
+
+/// Owned schematic with the path of the file it was read from.
+///
+/// Created with [`Schematic::from_file`].
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, Deref, DerefMut)]
+pub struct FileSchematic {
+    pub path: PathBuf,
+    #[deref]
+    #[deref_mut]
+    pub schematic: Schematic<String>,
+}
+
+/// Read-only view of a parsed schematic with the exact input text of each
+/// object and the whitespace between objects.
+///
+/// Displaying it reproduces the input byte for byte, so it is read-only:
+/// the parsed [`Schematic`] can only be borrowed, or taken with
+/// [`LosslessView::into_schematic`], which drops the input text. To edit with
+/// a minimal diff, write the text of [`LosslessView::objects`] for unchanged
+/// objects and display the changed ones. Created with
+/// [`from_str_lossless`](crate::from_str_lossless).
+#[derive(Clone, Debug)]
+pub struct LosslessView<I> {
+    pub(crate) schematic: Schematic<I>,
+    pub(crate) objects: Vec<(I, I)>,
+    pub(crate) trailing: I,
+}
+
+impl<I> LosslessView<I> {
+    /// Returns the parsed schematic.
+    #[must_use]
+    pub fn schematic(&self) -> &Schematic<I> {
+        &self.schematic
+    }
+
+    /// Returns the preceding whitespace and input text of the version object
+    /// and each following object, in input order.
+    ///
+    /// The whitespace preceding the version object includes a byte order
+    /// mark, if any.
+    #[must_use]
+    pub fn objects(&self) -> &[(I, I)] {
+        &self.objects
+    }
+
+    /// Returns the whitespace after the last object.
+    #[must_use]
+    pub fn trailing(&self) -> &I {
+        &self.trailing
+    }
+
+    /// Returns the parsed schematic, dropping the input text.
+    #[must_use]
+    pub fn into_schematic(self) -> Schematic<I> {
+        self.schematic
+    }
+}
+
 /// Finite double precision type.
 #[derive(Clone, Copy, Debug, Default, Deref, Display, Into, PartialEq, PartialOrd)]
-pub struct FiniteDouble(f64);
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FiniteDouble(pub(crate) f64);
 
-#[derive(Clone, Copy, Debug, Default, Display, From, Into, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Default, Display, From, Into, PartialEq, Eq, Hash, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[from((FiniteDouble, FiniteDouble))]
 #[into((FiniteDouble, FiniteDouble))]
 #[display("{x} {y}")]
@@ -178,10 +445,12 @@ pub struct Vec2 {
 pub type Coordinate = Vec2;
 pub type Size = Vec2;
 
-#[derive(Clone, Debug, Default, Deref, DerefMut, From, Into, PartialEq)]
+#[derive(Clone, Debug, Default, Deref, DerefMut, From, Into, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Coordinates(pub Vec<Coordinate>);
 
-#[derive(Clone, Copy, Debug, Default, Display, PartialEq, Eq, PartialOrd, Ord, TryFrom)]
+#[derive(Clone, Copy, Debug, Default, Display, PartialEq, Eq, Hash, PartialOrd, Ord, TryFrom)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(into = "u8"))]
 #[try_from(repr)]
 #[repr(u8)]
 pub enum Rotation {
@@ -196,7 +465,8 @@ pub enum Rotation {
     Three,
 }
 
-#[derive(Clone, Copy, Debug, Default, Display, PartialEq, Eq, PartialOrd, Ord, TryFrom)]
+#[derive(Clone, Copy, Debug, Default, Display, PartialEq, Eq, Hash, PartialOrd, Ord, TryFrom)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(into = "bool"))]
 #[try_from(repr)]
 #[repr(u8)]
 pub enum Flip {
@@ -243,49 +513,91 @@ impl<'a, X: Clone> TryFrom<ByteSpan<'a, X>> for Schematic<ByteSpan<'a, X>> {
     }
 }
 
+#[cfg(feature = "std")]
+impl TryFrom<&Path> for FileSchematic {
+    type Error = FileError;
+
+    /// Tries to read and parse a schematic from a file.
+    fn try_from(value: &Path) -> Result<Self, Self::Error> {
+        match crate::from_path(value) {
+            Ok(schematic) => Ok(Self {
+                path: value.to_path_buf(),
+                schematic,
+            }),
+            Err(error) => Err(FileError {
+                path: value.to_path_buf(),
+                error: Box::new(error),
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Schematic<String> {
+    /// Reads and parses a schematic from a file into an owned schematic that
+    /// keeps the path of the file.
+    ///
+    /// Unlike [`from_path`](crate::from_path), the error also refers to the
+    /// path if reading the file fails.
+    ///
+    /// ```
+    /// use xschem_parser::token::Schematic;
+    ///
+    /// let error = Schematic::from_file("does/not/exist.sch").unwrap_err();
+    ///
+    /// assert_eq!(error.path.to_str(), Some("does/not/exist.sch"));
+    /// assert!(error.to_string().contains("does/not/exist.sch"));
+    /// ```
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<FileSchematic, FileError> {
+        FileSchematic::try_from(path.as_ref())
+    }
+}
+
 impl<I> fmt::Display for Schematic<I>
 where
     I: fmt::Display,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.version)?;
-        if let Some(p) = &self.vhdl_property {
-            write!(f, "\n{p}")?;
-        }
-        if let Some(p) = &self.symbol_property {
-            write!(f, "\n{p}")?;
-        }
-        if let Some(p) = &self.verilog_property {
-            write!(f, "\n{p}")?;
-        }
-        if let Some(p) = &self.spice_property {
-            write!(f, "\n{p}")?;
-        }
-        if let Some(p) = &self.tedax_property {
-            write!(f, "\n{p}")?;
-        }
-        if !self.texts.is_empty() {
-            write!(f, "\n{}", self.texts)?;
-        }
-        if !self.lines.is_empty() {
-            write!(f, "\n{}", self.lines)?;
-        }
-        if !self.rectangles.is_empty() {
-            write!(f, "\n{}", self.rectangles)?;
-        }
-        if !self.polygons.is_empty() {
-            write!(f, "\n{}", self.polygons)?;
-        }
-        if !self.arcs.is_empty() {
-            write!(f, "\n{}", self.arcs)?;
+        write_schematic(f, self, FloatFormat::Shortest)
+    }
+}
+
+impl<I> From<Schematic<I>> for SchematicHeader<I> {
+    fn from(schematic: Schematic<I>) -> Self {
+        Self {
+            version: schematic.version,
+            vhdl_property: schematic.vhdl_property,
+            symbol_property: schematic.symbol_property,
+            verilog_property: schematic.verilog_property,
+            spice_property: schematic.spice_property,
+            tedax_property: schematic.tedax_property,
         }
-        if !self.wires.is_empty() {
-            write!(f, "\n{}", self.wires)?;
+    }
+}
+
+impl<I> From<SchematicHeader<I>> for Schematic<I> {
+    /// Creates a schematic without objects from a header.
+    fn from(header: SchematicHeader<I>) -> Self {
+        Self {
+            vhdl_property: header.vhdl_property,
+            symbol_property: header.symbol_property,
+            verilog_property: header.verilog_property,
+            spice_property: header.spice_property,
+            tedax_property: header.tedax_property,
+            ..Self::new(header.version)
         }
-        if !self.components.is_empty() {
-            write!(f, "\n{}", self.components)?;
+    }
+}
+
+impl<I> fmt::Display for LosslessView<I>
+where
+    I: fmt::Display,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (space, text) in &self.objects {
+            write!(f, "{space}{text}")?;
         }
-        Ok(())
+        write!(f, "{}", self.trailing)
     }
 }
 
@@ -355,6 +667,7 @@ where
             && self.arcs == other.arcs
             && self.wires == other.wires
             && self.components == other.components
+            && self.unknown_objects == other.unknown_objects
     }
 }
 
@@ -374,11 +687,26 @@ impl<I> Schematic<I> {
             arcs: Objects::default(),
             wires: Objects::default(),
             components: Objects::default(),
+            unknown_objects: Objects::default(),
+            order: Vec::default(),
         }
     }
 
+    /// Adds an object, returning the schematic for chaining.
+    ///
+    /// See [`Schematic::add_object_mut`] to add objects through a mutable
+    /// reference.
     #[must_use]
     pub fn add_object(mut self, object: Object<I>) -> Self {
+        self.add_object_mut(object);
+        self
+    }
+
+    /// Adds an object.
+    ///
+    /// Global properties replace any previous global property of the same
+    /// kind. Other objects are appended and recorded in [`Schematic::order`].
+    pub fn add_object_mut(&mut self, object: Object<I>) {
         match object {
             Object::VhdlProperty(p) => {
                 self.vhdl_property.replace(p);
@@ -397,31 +725,1147 @@ impl<I> Schematic<I> {
             }
             Object::Arc(o) => {
                 self.arcs.push(o);
+                self.order.push(ObjectKind::Arc);
             }
             Object::Component(o) => {
                 self.components.push(o);
+                self.order.push(ObjectKind::Component);
             }
             Object::Line(o) => {
                 self.lines.push(o);
+                self.order.push(ObjectKind::Line);
             }
             Object::Polygon(o) => {
                 self.polygons.push(o);
+                self.order.push(ObjectKind::Polygon);
             }
             Object::Rectangle(o) => {
                 self.rectangles.push(o);
+                self.order.push(ObjectKind::Rectangle);
             }
             Object::Text(o) => {
                 self.texts.push(o);
+                self.order.push(ObjectKind::Text);
             }
             Object::Wire(o) => {
                 self.wires.push(o);
+                self.order.push(ObjectKind::Wire);
+            }
+            Object::Unknown(o) => {
+                self.unknown_objects.push(o);
+                self.order.push(ObjectKind::Unknown);
             }
         }
+    }
 
-        self
+    /// Appends an arc.
+    pub fn push_arc(&mut self, arc: Arc<I>) {
+        self.add_object_mut(Object::Arc(arc));
+    }
+
+    /// Appends a component.
+    pub fn push_component(&mut self, component: Component<I>) {
+        self.add_object_mut(Object::Component(component));
+    }
+
+    /// Appends a line.
+    pub fn push_line(&mut self, line: Line<I>) {
+        self.add_object_mut(Object::Line(line));
+    }
+
+    /// Appends a polygon.
+    pub fn push_polygon(&mut self, polygon: Polygon<I>) {
+        self.add_object_mut(Object::Polygon(polygon));
+    }
+
+    /// Appends a rectangle.
+    pub fn push_rectangle(&mut self, rectangle: Rectangle<I>) {
+        self.add_object_mut(Object::Rectangle(rectangle));
+    }
+
+    /// Appends a text.
+    pub fn push_text(&mut self, text: Text<I>) {
+        self.add_object_mut(Object::Text(text));
+    }
+
+    /// Appends a wire.
+    pub fn push_wire(&mut self, wire: Wire<I>) {
+        self.add_object_mut(Object::Wire(wire));
+    }
+
+    /// Returns an iterator over all objects in the schematic.
+    ///
+    /// Global properties and the version are not included. Objects are yielded
+    /// in the order given by [`Schematic::order`], followed by any untracked
+    /// objects grouped by type.
+    pub fn objects(&self) -> ObjectIter<'_, I> {
+        ObjectIter {
+            order: self.order.iter(),
+            arcs: self.arcs.iter(),
+            components: self.components.iter(),
+            lines: self.lines.iter(),
+            polygons: self.polygons.iter(),
+            rectangles: self.rectangles.iter(),
+            texts: self.texts.iter(),
+            wires: self.wires.iter(),
+            unknown_objects: self.unknown_objects.iter(),
+        }
+    }
+
+    /// Returns an iterator over the components matching a predicate, in input
+    /// order.
+    ///
+    /// ```
+    /// let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+    ///              C {res.sym} 0 0 0 0 {name=R1}\n\
+    ///              C {capa.sym} 0 50 0 0 {name=C1}\n\
+    ///              C {res.sym} 0 100 0 0 {name=R2}\n";
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    ///
+    /// let resistors = schematic.components_where(|c| *c.reference.fragment() == "res.sym");
+    /// assert_eq!(resistors.count(), 2);
+    /// ```
+    pub fn components_where<P>(&self, predicate: P) -> impl Iterator<Item = &Component<I>>
+    where
+        P: Fn(&Component<I>) -> bool,
+    {
+        self.components.iter().filter(move |c| predicate(c))
+    }
+
+    /// Returns whether any component has an embedded symbol.
+    pub fn has_embeddings(&self) -> bool {
+        self.components.iter().any(|c| c.embedding.is_some())
+    }
+
+    /// Returns an iterator over the embedded symbols of the components, in
+    /// input order.
+    ///
+    /// Only the symbols embedded directly in this schematic are yielded, not
+    /// those nested in embedded symbols.
+    pub fn embedded_schematics(&self) -> impl Iterator<Item = &Schematic<I>> {
+        self.components
+            .iter()
+            .filter_map(|c| c.embedding.as_ref().map(|e| &e.0))
+    }
+
+    /// Returns the sorted set of layers used by arcs, lines, polygons and
+    /// rectangles.
+    ///
+    /// Wires and components have no layer, and texts only have a layer
+    /// attribute, see [`Schematic::layers_with_texts`]. Embedded symbols are
+    /// not taken into account.
+    ///
+    /// ```
+    /// let input = "v {}\nL 4 0 0 10 0 {}\nB 5 0 0 1 1 {}\nL 4 0 10 10 10 {}";
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    ///
+    /// assert_eq!(schematic.layers().into_iter().collect::<Vec<_>>(), [4, 5]);
+    /// ```
+    pub fn layers(&self) -> BTreeSet<u64> {
+        let arcs = self.arcs.iter().map(|o| o.layer);
+        let lines = self.lines.iter().map(|o| o.layer);
+        let polygons = self.polygons.iter().map(|o| o.layer);
+        let rectangles = self.rectangles.iter().map(|o| o.layer);
+        arcs.chain(lines)
+            .chain(polygons)
+            .chain(rectangles)
+            .collect()
+    }
+
+    /// Removes the objects for which `remove` returns `true`, returning the
+    /// number of removed objects.
+    ///
+    /// The predicate is called once for each object, in the order of
+    /// [`Schematic::objects`]. [`Schematic::order`] is updated along with the
+    /// collections, so the remaining objects keep their order, unlike
+    /// removing objects from a collection directly. Global properties are
+    /// not removed.
+    ///
+    /// ```
+    /// use xschem_parser::token::ObjectRef;
+    ///
+    /// let input = "v {}\nL 4 0 0 10 0 {}\nN 0 0 10 0 {}\nL 5 0 0 10 0 {}\nN 0 0 0 10 {}";
+    /// let mut schematic = xschem_parser::from_str(input).unwrap();
+    ///
+    /// let removed = schematic.remove_objects(|o| o.layer() == Some(4));
+    /// assert_eq!(removed, 1);
+    /// assert_eq!(
+    ///     schematic.to_string(),
+    ///     "v {}\nN 0 0 10 0 {}\nL 5 0 0 10 0 {}\nN 0 0 0 10 {}",
+    /// );
+    /// ```
+    pub fn remove_objects<F>(&mut self, mut remove: F) -> usize
+    where
+        F: FnMut(&ObjectRef<'_, I>) -> bool,
+    {
+        // Whether to remove each object of a kind, indexed by the kind.
+        let mut removals: [Vec<bool>; OBJECT_KINDS] = Default::default();
+        for object in self.objects() {
+            removals[object.kind() as usize].push(remove(&object));
+        }
+        let count = removals.iter().flatten().filter(|&&r| r).count();
+        if count == 0 {
+            return 0;
+        }
+
+        let mut seen = [0; OBJECT_KINDS];
+        self.order.retain(|&kind| {
+            let index = seen[kind as usize];
+            seen[kind as usize] += 1;
+            !removals[kind as usize].get(index).copied().unwrap_or(false)
+        });
+        retain_unremoved(&mut self.arcs, &removals[ObjectKind::Arc as usize]);
+        retain_unremoved(
+            &mut self.components,
+            &removals[ObjectKind::Component as usize],
+        );
+        retain_unremoved(&mut self.lines, &removals[ObjectKind::Line as usize]);
+        retain_unremoved(&mut self.polygons, &removals[ObjectKind::Polygon as usize]);
+        retain_unremoved(
+            &mut self.rectangles,
+            &removals[ObjectKind::Rectangle as usize],
+        );
+        retain_unremoved(&mut self.texts, &removals[ObjectKind::Text as usize]);
+        retain_unremoved(&mut self.wires, &removals[ObjectKind::Wire as usize]);
+        retain_unremoved(
+            &mut self.unknown_objects,
+            &removals[ObjectKind::Unknown as usize],
+        );
+        count
+    }
+
+    /// Returns an iterator over the arcs, lines, polygons and rectangles on
+    /// a layer, in input order.
+    ///
+    /// Like [`Schematic::layers`], objects without a layer field and
+    /// embedded symbols are not included.
+    ///
+    /// ```
+    /// let input = "v {}\nL 4 0 0 10 0 {}\nB 5 0 0 1 1 {}\nA 4 0 0 5 0 90 {}";
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    ///
+    /// assert_eq!(schematic.objects_on_layer(4).count(), 2);
+    /// assert_eq!(schematic.lines_on_layer(4).count(), 1);
+    /// ```
+    pub fn objects_on_layer(&self, layer: u64) -> impl Iterator<Item = ObjectRef<'_, I>> {
+        self.objects().filter(move |o| o.layer() == Some(layer))
+    }
+
+    /// Returns an iterator over the arcs on a layer.
+    pub fn arcs_on_layer(&self, layer: u64) -> impl Iterator<Item = &Arc<I>> {
+        self.arcs.iter().filter(move |o| o.layer == layer)
+    }
+
+    /// Returns an iterator over the lines on a layer.
+    pub fn lines_on_layer(&self, layer: u64) -> impl Iterator<Item = &Line<I>> {
+        self.lines.iter().filter(move |o| o.layer == layer)
+    }
+
+    /// Returns an iterator over the polygons on a layer.
+    pub fn polygons_on_layer(&self, layer: u64) -> impl Iterator<Item = &Polygon<I>> {
+        self.polygons.iter().filter(move |o| o.layer == layer)
+    }
+
+    /// Returns an iterator over the rectangles on a layer.
+    pub fn rectangles_on_layer(&self, layer: u64) -> impl Iterator<Item = &Rectangle<I>> {
+        self.rectangles.iter().filter(move |o| o.layer == layer)
+    }
+}
+
+/// Number of [`ObjectKind`]s.
+const OBJECT_KINDS: usize = ObjectKind::Unknown as usize + 1;
+
+/// Keeps the objects that are not marked in `removals`, which has an entry
+/// for each object in order.
+fn retain_unremoved<T>(objects: &mut Objects<T>, removals: &[bool]) {
+    let mut removals = removals.iter();
+    objects.retain(|_| !removals.next().copied().unwrap_or(false));
+}
+
+impl<I: AsRef<str>> Schematic<I> {
+    /// Returns the sorted set of layers used by objects like
+    /// [`Schematic::layers`], including the layers of texts given by their
+    /// `layer` attribute (see [`Text::layer`]).
+    pub fn layers_with_texts(&self) -> BTreeSet<u64> {
+        let mut layers = self.layers();
+        layers.extend(self.texts.iter().filter_map(Text::layer));
+        layers
+    }
+
+    /// Returns the first component with the given `name` attribute.
+    ///
+    /// Quotes around the attribute value are ignored, see
+    /// [`Property::get_value`]. Components of embedded symbols are not
+    /// searched.
+    pub fn find_component_by_name(&self, name: &str) -> Option<&Component<I>> {
+        self.components
+            .iter()
+            .find(|c| c.property.get_value("name") == Some(name))
+    }
+}
+
+impl<'a, I> ObjectIter<'a, I> {
+    fn next_of(&mut self, kind: ObjectKind) -> Option<ObjectRef<'a, I>> {
+        match kind {
+            ObjectKind::Arc => self.arcs.next().map(ObjectRef::Arc),
+            ObjectKind::Component => self.components.next().map(ObjectRef::Component),
+            ObjectKind::Line => self.lines.next().map(ObjectRef::Line),
+            ObjectKind::Polygon => self.polygons.next().map(ObjectRef::Polygon),
+            ObjectKind::Rectangle => self.rectangles.next().map(ObjectRef::Rectangle),
+            ObjectKind::Text => self.texts.next().map(ObjectRef::Text),
+            ObjectKind::Wire => self.wires.next().map(ObjectRef::Wire),
+            ObjectKind::Unknown => self.unknown_objects.next().map(ObjectRef::Unknown),
+        }
+    }
+}
+
+impl<'a, I> Iterator for ObjectIter<'a, I> {
+    type Item = ObjectRef<'a, I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&kind) = self.order.next() {
+            if let Some(o) = self.next_of(kind) {
+                return Some(o);
+            }
+        }
+
+        [
+            ObjectKind::Text,
+            ObjectKind::Line,
+            ObjectKind::Rectangle,
+            ObjectKind::Polygon,
+            ObjectKind::Arc,
+            ObjectKind::Wire,
+            ObjectKind::Component,
+            ObjectKind::Unknown,
+        ]
+        .into_iter()
+        .find_map(|kind| self.next_of(kind))
+    }
+}
+
+impl<I> Clone for ObjectRef<'_, I> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I> Copy for ObjectRef<'_, I> {}
+
+impl<I> ObjectRef<'_, I> {
+    /// Returns the kind of the object.
+    #[must_use]
+    pub fn kind(&self) -> ObjectKind {
+        match self {
+            ObjectRef::Arc(_) => ObjectKind::Arc,
+            ObjectRef::Component(_) => ObjectKind::Component,
+            ObjectRef::Line(_) => ObjectKind::Line,
+            ObjectRef::Polygon(_) => ObjectKind::Polygon,
+            ObjectRef::Rectangle(_) => ObjectKind::Rectangle,
+            ObjectRef::Text(_) => ObjectKind::Text,
+            ObjectRef::Wire(_) => ObjectKind::Wire,
+            ObjectRef::Unknown(_) => ObjectKind::Unknown,
+        }
+    }
+
+    /// Returns the layer of an arc, line, polygon or rectangle.
+    ///
+    /// Other objects have no layer field, see [`Schematic::layers`].
+    #[must_use]
+    pub fn layer(&self) -> Option<u64> {
+        match self {
+            ObjectRef::Arc(o) => Some(o.layer),
+            ObjectRef::Line(o) => Some(o.layer),
+            ObjectRef::Polygon(o) => Some(o.layer),
+            ObjectRef::Rectangle(o) => Some(o.layer),
+            ObjectRef::Component(_)
+            | ObjectRef::Text(_)
+            | ObjectRef::Wire(_)
+            | ObjectRef::Unknown(_) => None,
+        }
+    }
+}
+
+impl<I> Schematic<I> {
+    /// Converts the schematic to another input type by mapping every input
+    /// reference, including those of embedded symbols.
+    ///
+    /// This can be used to get an owned schematic that outlives the input.
+    ///
+    /// ```
+    /// use xschem_parser::token::Schematic;
+    ///
+    /// let owned: Schematic<String> = {
+    ///     let input = String::from("v {xschem version=3.4.5 file_version=1.2}");
+    ///     let schematic = xschem_parser::from_str(&input).unwrap();
+    ///     schematic.map(|s| s.to_string())
+    /// };
+    ///
+    /// assert_eq!(owned.version.0.prop, "xschem version=3.4.5 file_version=1.2");
+    /// ```
+    pub fn map<J: Eq + Hash>(&self, mut f: impl FnMut(&I) -> J) -> Schematic<J> {
+        self.map_with(&mut f)
+    }
+
+    fn map_with<'a, J: Eq + Hash>(&'a self, f: &mut impl FnMut(&'a I) -> J) -> Schematic<J> {
+        Schematic {
+            version: Version(self.version.0.map_with(f)),
+            vhdl_property: self
+                .vhdl_property
+                .as_ref()
+                .map(|p| VhdlProperty(p.0.map_with(f))),
+            symbol_property: self
+                .symbol_property
+                .as_ref()
+                .map(|p| SymbolProperty(p.0.map_with(f))),
+            verilog_property: self
+                .verilog_property
+                .as_ref()
+                .map(|p| VerilogProperty(p.0.map_with(f))),
+            spice_property: self
+                .spice_property
+                .as_ref()
+                .map(|p| SpiceProperty(p.0.map_with(f))),
+            tedax_property: self
+                .tedax_property
+                .as_ref()
+                .map(|p| TedaXProperty(p.0.map_with(f))),
+            texts: self.texts.iter().map(|o| o.map_with(f)).collect(),
+            lines: self.lines.iter().map(|o| o.map_with(f)).collect(),
+            rectangles: self.rectangles.iter().map(|o| o.map_with(f)).collect(),
+            polygons: self.polygons.iter().map(|o| o.map_with(f)).collect(),
+            arcs: self.arcs.iter().map(|o| o.map_with(f)).collect(),
+            wires: self.wires.iter().map(|o| o.map_with(f)).collect(),
+            components: self.components.iter().map(|o| o.map_with(f)).collect(),
+            unknown_objects: self.unknown_objects.iter().map(|o| o.map_with(f)).collect(),
+            order: self.order.clone(),
+        }
+    }
+}
+
+impl<'a, X> Schematic<Span<'a, X>> {
+    /// Converts the schematic to copy-on-write strings borrowing the input.
+    ///
+    /// Individual strings can then be replaced by owned strings while the
+    /// rest of the schematic keeps borrowing the input.
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// let input = "v {xschem version=3.4.5 file_version=1.2}\nN 0 0 10 0 {lab=A}\n";
+    /// let mut schematic = xschem_parser::from_str(input).unwrap().into_cow();
+    /// schematic.wires[0].property.prop = Cow::Owned("lab=B".to_string());
+    ///
+    /// assert!(matches!(schematic.version.0.prop, Cow::Borrowed(_)));
+    /// ```
+    #[must_use]
+    pub fn into_cow(self) -> Schematic<Cow<'a, str>> {
+        self.map(|s| Cow::Borrowed(*s.fragment()))
+    }
+}
+
+impl<'a, X> From<Schematic<Span<'a, X>>> for Schematic<&'a str> {
+    /// Drops the location of every span, borrowing the same input.
+    ///
+    /// ```
+    /// use xschem_parser::token::Schematic;
+    ///
+    /// let input = "v {xschem version=3.4.5 file_version=1.2}\nN 0 0 10 0 {lab=A}\n";
+    /// let schematic = Schematic::<&str>::from(xschem_parser::from_str(input).unwrap());
+    ///
+    /// assert_eq!(schematic, xschem_parser::from_str_plain(input).unwrap());
+    /// ```
+    fn from(schematic: Schematic<Span<'a, X>>) -> Self {
+        schematic.map(|s| *s.fragment())
+    }
+}
+
+impl<T: Copy + Eq + Hash, X> Schematic<LocatedSpan<T, X>> {
+    /// Returns whether both schematics have the same content, comparing
+    /// only the fragments of spans and not their locations.
+    ///
+    /// Schematics parsed from different inputs, or from different places of
+    /// an input, are not equal with [`PartialEq`] because the offsets of the
+    /// spans differ.
+    ///
+    /// ```
+    /// let a = xschem_parser::from_str("v {}\nN 0 0 10 0 {lab=A}\n").unwrap();
+    /// let b = xschem_parser::from_str("v {}\n\nN 0 0 10 0 {lab=A}\n").unwrap();
+    ///
+    /// assert_ne!(a, b);
+    /// assert!(a.content_eq(&b));
+    /// ```
+    #[must_use]
+    pub fn content_eq(&self, other: &Self) -> bool {
+        self.map(|s| *s.fragment()) == other.map(|s| *s.fragment())
+    }
+}
+
+impl Schematic<Cow<'_, str>> {
+    /// Converts the schematic to owned strings.
+    ///
+    /// The result no longer borrows the input, so it can be kept in a
+    /// long-lived cache.
+    #[must_use]
+    pub fn into_owned(self) -> Schematic<Cow<'static, str>> {
+        self.map(|s| Cow::Owned(s.clone().into_owned()))
+    }
+}
+
+impl<I> Property<I>
+where
+    I: Offset + Input + for<'s> Compare<&'s str>,
+    <I as Input>::Item: AsChar,
+{
+    /// Returns the text of the property string that is not part of an
+    /// attribute, such as comments.
+    ///
+    /// The text between attributes is returned as separate spans, without
+    /// surrounding whitespace. Empty spans are omitted.
+    ///
+    /// ```
+    /// let input = "v {xschem version=3.4.5 file_version=1.2\n* copyright info\n}";
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    /// let comments = schematic.version.0.comments();
+    ///
+    /// assert_eq!(*comments[0].fragment(), "xschem");
+    /// assert_eq!(*comments[1].fragment(), "* copyright info");
+    /// assert_eq!(comments[1].location_line(), 2);
+    /// ```
+    pub fn comments(&self) -> Vec<I> {
+        parse::free_text(&self.prop)
+    }
+
+    /// Returns the key and value spans of the attributes in input order.
+    ///
+    /// Unlike [`Property::attrs`], a repeated key is returned once for each
+    /// occurrence, and the spans keep their location in the input.
+    ///
+    /// ```
+    /// let input = "v {}\nC {res.sym} 0 0 0 0 {name=R1\nvalue=\"10 k\"}";
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    /// let spans = schematic.components[0].property.attribute_spans();
+    ///
+    /// let (key, value) = &spans[1];
+    /// assert_eq!(*key.fragment(), "value");
+    /// assert_eq!(*value.fragment(), "\"10 k\"");
+    /// assert_eq!((value.location_line(), value.get_column()), (3, 7));
+    /// ```
+    pub fn attribute_spans(&self) -> Vec<(I, I)> {
+        parse::attribute_spans(&self.prop)
+    }
+}
+
+impl<I> Component<I>
+where
+    I: Input,
+    <I as Input>::Item: AsChar,
+{
+    /// Returns the span of the symbol reference without surrounding
+    /// whitespace, which keeps the location of the reference in the input.
+    ///
+    /// ```
+    /// let input = "v {}\nC { capa.sym } 0 0 0 0 {name=C1}";
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    /// let reference = schematic.components[0].reference_trimmed();
+    ///
+    /// assert_eq!(*reference.fragment(), "capa.sym");
+    /// assert_eq!(reference.get_column(), 5);
+    /// ```
+    pub fn reference_trimmed(&self) -> I {
+        parse::trim(&self.reference)
+    }
+}
+
+impl<I> Text<I>
+where
+    I: Input,
+    <I as Input>::Item: AsChar,
+{
+    /// Returns the span of the text without surrounding whitespace, see
+    /// [`Component::reference_trimmed`].
+    pub fn text_trimmed(&self) -> I {
+        parse::trim(&self.text)
+    }
+}
+
+impl<I: Default> Arc<I> {
+    /// Creates an arc with an empty property.
+    #[must_use]
+    pub fn new(
+        layer: u64,
+        center: Coordinate,
+        radius: FiniteDouble,
+        start_angle: FiniteDouble,
+        sweep_angle: FiniteDouble,
+    ) -> Self {
+        Self {
+            layer,
+            center,
+            radius,
+            start_angle,
+            sweep_angle,
+            property: Property::default(),
+        }
+    }
+}
+
+impl<I: Default> Component<I> {
+    /// Creates an unrotated and unflipped component with an empty property
+    /// and without embedding.
+    #[must_use]
+    pub fn new(reference: I, position: Coordinate) -> Self {
+        Self {
+            reference,
+            position,
+            ..Self::default()
+        }
+    }
+}
+
+impl<I: Default> Line<I> {
+    /// Creates a line with an empty property.
+    #[must_use]
+    pub fn new(layer: u64, start: Coordinate, end: Coordinate) -> Self {
+        Self {
+            layer,
+            start,
+            end,
+            property: Property::default(),
+        }
+    }
+}
+
+impl<I: Default> Polygon<I> {
+    /// Creates a polygon with an empty property.
+    #[must_use]
+    pub fn new(layer: u64, points: Coordinates) -> Self {
+        Self {
+            layer,
+            points,
+            property: Property::default(),
+        }
+    }
+}
+
+impl<I: Default> Rectangle<I> {
+    /// Creates a rectangle with an empty property.
+    #[must_use]
+    pub fn new(layer: u64, start: Coordinate, end: Coordinate) -> Self {
+        Self {
+            layer,
+            start,
+            end,
+            property: Property::default(),
+        }
+    }
+}
+
+impl<I: Default> Text<I> {
+    /// Creates an unrotated and unflipped text with an empty property.
+    ///
+    /// ```
+    /// use xschem_parser::token::{Text, Vec2};
+    ///
+    /// let position = Vec2::try_from((10.0, -20.0)).unwrap();
+    /// let size = Vec2::try_from((0.4, 0.4)).unwrap();
+    /// let text = Text::new("hello", position, size);
+    ///
+    /// assert_eq!(text.to_string(), "T {hello} 10 -20 0 0 0.4 0.4 {}");
+    /// ```
+    #[must_use]
+    pub fn new(text: I, position: Coordinate, size: Size) -> Self {
+        Self {
+            text,
+            position,
+            rotation: Rotation::default(),
+            flip: Flip::default(),
+            size,
+            property: Property::default(),
+        }
+    }
+}
+
+impl<I: Default> Wire<I> {
+    /// Creates a wire with an empty property.
+    #[must_use]
+    pub fn new(start: Coordinate, end: Coordinate) -> Self {
+        Self {
+            start,
+            end,
+            property: Property::default(),
+        }
+    }
+}
+
+impl<I> Property<I> {
+    fn map_with<'a, J: Eq + Hash>(&'a self, f: &mut impl FnMut(&'a I) -> J) -> Property<J> {
+        Property {
+            prop: f(&self.prop),
+            attrs: self.attrs.iter().map(|(k, v)| (f(k), f(v))).collect(),
+        }
+    }
+}
+
+impl<I> Arc<I> {
+    pub(crate) fn map_with<'a, J: Eq + Hash>(&'a self, f: &mut impl FnMut(&'a I) -> J) -> Arc<J> {
+        Arc {
+            layer: self.layer,
+            center: self.center,
+            radius: self.radius,
+            start_angle: self.start_angle,
+            sweep_angle: self.sweep_angle,
+            property: self.property.map_with(f),
+        }
+    }
+}
+
+impl<I> Component<I> {
+    pub(crate) fn map_with<'a, J: Eq + Hash>(
+        &'a self,
+        f: &mut impl FnMut(&'a I) -> J,
+    ) -> Component<J> {
+        Component {
+            reference: f(&self.reference),
+            position: self.position,
+            rotation: self.rotation,
+            flip: self.flip,
+            extra: self.extra.iter().map(&mut *f).collect(),
+            property: self.property.map_with(f),
+            embedding: self.embedding.as_ref().map(|e| Embedding(e.0.map_with(f))),
+        }
+    }
+}
+
+impl<I> Field<I> {
+    /// Converts the field to another input type by mapping its input
+    /// reference, if any, see [`Schematic::map`].
+    pub fn map<J>(&self, mut f: impl FnMut(&I) -> J) -> Field<J> {
+        match self {
+            Field::Layer(v) => Field::Layer(*v),
+            Field::X(v) => Field::X(*v),
+            Field::Y(v) => Field::Y(*v),
+            Field::Radius(v) => Field::Radius(*v),
+            Field::Angle(v) => Field::Angle(*v),
+            Field::PointCount(v) => Field::PointCount(*v),
+            Field::Rotation(v) => Field::Rotation(*v),
+            Field::Flip(v) => Field::Flip(*v),
+            Field::Reference(v) => Field::Reference(f(v)),
+            Field::Text(v) => Field::Text(f(v)),
+            Field::Token(v) => Field::Token(f(v)),
+            Field::Property(v) => Field::Property(f(v)),
+        }
+    }
+}
+
+impl<I> UnknownObject<I> {
+    pub(crate) fn map_with<'a, J: Eq + Hash>(
+        &'a self,
+        f: &mut impl FnMut(&'a I) -> J,
+    ) -> UnknownObject<J> {
+        UnknownObject {
+            tag: f(&self.tag),
+            tokens: self.tokens.iter().map(&mut *f).collect(),
+            property: self.property.map_with(f),
+        }
+    }
+}
+
+impl<I> Line<I> {
+    pub(crate) fn map_with<'a, J: Eq + Hash>(&'a self, f: &mut impl FnMut(&'a I) -> J) -> Line<J> {
+        Line {
+            layer: self.layer,
+            start: self.start,
+            end: self.end,
+            property: self.property.map_with(f),
+        }
+    }
+}
+
+impl<I> Polygon<I> {
+    pub(crate) fn map_with<'a, J: Eq + Hash>(
+        &'a self,
+        f: &mut impl FnMut(&'a I) -> J,
+    ) -> Polygon<J> {
+        Polygon {
+            layer: self.layer,
+            points: self.points.clone(),
+            property: self.property.map_with(f),
+        }
+    }
+}
+
+impl<I> Rectangle<I> {
+    pub(crate) fn map_with<'a, J: Eq + Hash>(
+        &'a self,
+        f: &mut impl FnMut(&'a I) -> J,
+    ) -> Rectangle<J> {
+        Rectangle {
+            layer: self.layer,
+            start: self.start,
+            end: self.end,
+            property: self.property.map_with(f),
+        }
+    }
+}
+
+impl<I> Text<I> {
+    pub(crate) fn map_with<'a, J: Eq + Hash>(&'a self, f: &mut impl FnMut(&'a I) -> J) -> Text<J> {
+        Text {
+            text: f(&self.text),
+            position: self.position,
+            rotation: self.rotation,
+            flip: self.flip,
+            size: self.size,
+            property: self.property.map_with(f),
+        }
+    }
+}
+
+impl<I> Wire<I> {
+    pub(crate) fn map_with<'a, J: Eq + Hash>(&'a self, f: &mut impl FnMut(&'a I) -> J) -> Wire<J> {
+        Wire {
+            start: self.start,
+            end: self.end,
+            property: self.property.map_with(f),
+        }
+    }
+}
+
+impl<I: AsRef<str>> Component<I> {
+    /// Returns the symbol reference without surrounding whitespace, such as
+    /// `devices/lab_pin.sym`.
+    pub fn symbol_name(&self) -> &str {
+        self.reference.as_ref().trim()
+    }
+
+    /// Returns the symbol reference, or [`None`] for an empty reference or
+    /// `-`, which do not refer to a symbol file.
+    pub fn symbol_path(&self) -> Option<&str> {
+        Some(self.symbol_name()).filter(|name| !name.is_empty() && *name != "-")
+    }
+
+    /// Returns the file name of the symbol reference without directory and
+    /// `.sym` extension, such as `lab_pin` for `devices/lab_pin.sym`.
+    pub fn symbol_stem(&self) -> &str {
+        let name = self.symbol_name();
+        let file = name.rsplit('/').next().unwrap_or(name);
+        file.strip_suffix(".sym").unwrap_or(file)
+    }
+}
+
+impl<I: AsRef<str>> Property<I> {
+    /// Returns the value of the attribute with the given key as in the
+    /// input, including surrounding quotes of a quoted value.
+    pub fn get(&self, key: &str) -> Option<&I> {
+        self.attrs
+            .iter()
+            .find_map(|(k, v)| (k.as_ref() == key).then_some(v))
+    }
+
+    /// Returns the value of the attribute with the given key without
+    /// surrounding quotes.
+    ///
+    /// ```
+    /// let schematic = xschem_parser::from_str(r#"v {format="spice netlist"}"#).unwrap();
+    /// let property = &schematic.version.0;
+    ///
+    /// assert_eq!(property.get("format").unwrap().fragment(), &r#""spice netlist""#);
+    /// assert_eq!(property.get_value("format"), Some("spice netlist"));
+    /// assert_eq!(property.is_quoted("format"), Some(true));
+    /// ```
+    pub fn get_value(&self, key: &str) -> Option<&str> {
+        self.get(key).map(|v| unquote(v.as_ref()).0)
+    }
+
+    /// Returns whether the value of the attribute with the given key is
+    /// quoted, or [`None`] if the attribute is missing.
+    pub fn is_quoted(&self, key: &str) -> Option<bool> {
+        self.get(key).map(|v| unquote(v.as_ref()).1)
+    }
+
+    /// Returns the property string with escape sequences of
+    /// [`ESCAPED_CHARS`] replaced.
+    ///
+    /// ```
+    /// let schematic = xschem_parser::from_str(r"v {\\\}}").unwrap();
+    ///
+    /// assert_eq!(schematic.version.0.unescaped(), r"\}");
+    /// ```
+    pub fn unescaped(&self) -> String {
+        unescape(self.prop.as_ref(), ESCAPED_CHARS).into_owned()
+    }
+
+    /// Returns the property string with `\r\n` line endings replaced by `\n`.
+    ///
+    /// The property span itself is left untouched, so offsets into the input
+    /// remain valid.
+    ///
+    /// ```
+    /// let schematic = xschem_parser::from_str("v {xschem version=3.4.5\r\n* copyright}").unwrap();
+    ///
+    /// assert_eq!(schematic.version.0.prop_normalized(), "xschem version=3.4.5\n* copyright");
+    /// ```
+    pub fn prop_normalized(&self) -> Cow<'_, str> {
+        normalize_line_endings(self.prop.as_ref())
+    }
+
+    /// Returns the value of the attribute with the given key without
+    /// surrounding quotes and with escape sequences replaced.
+    ///
+    /// Values are part of the property string, so escape sequences of
+    /// [`ESCAPED_CHARS`] are replaced first, followed by those of
+    /// [`ESCAPED_VALUE_CHARS`] if the value is quoted.
+    pub fn get_unescaped(&self, key: &str) -> Option<String> {
+        self.get(key).map(|v| {
+            let (v, quoted) = unquote(v.as_ref());
+            unescape_value(v, quoted).into_owned()
+        })
+    }
+
+    /// Returns the items of a list in the value of the attribute with the
+    /// given key, such as the pin numbers of `pinseq=1,2,3`.
+    ///
+    /// The value is unquoted (see [`Property::get_value`]) and split on
+    /// `sep`, and surrounding whitespace of each item is trimmed. If `sep` is
+    /// whitespace, items are separated by any run of whitespace instead, as
+    /// in `"@name @pinlist"`. An empty value has no items. Escape sequences
+    /// of each item are replaced like by [`Property::get_unescaped`].
+    ///
+    /// ```
+    /// let input = r#"v {format="@name @pinlist  @model" names="a\\"b\\",c"}"#;
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    /// let property = &schematic.version.0;
+    ///
+    /// assert_eq!(property.get_list("format", ' ').unwrap(), ["@name", "@pinlist", "@model"]);
+    /// assert_eq!(property.get_list("names", ',').unwrap(), [r#"a"b""#, "c"]);
+    /// assert_eq!(property.get_list("missing", ','), None);
+    /// ```
+    pub fn get_list(&self, key: &str, sep: char) -> Option<Vec<Cow<'_, str>>> {
+        let (value, quoted) = unquote(self.get(key)?.as_ref());
+        let value = value.trim();
+        let items: Vec<&str> = if sep.is_whitespace() {
+            value.split_whitespace().collect()
+        } else if value.is_empty() {
+            Vec::new()
+        } else {
+            value.split(sep).map(str::trim).collect()
+        };
+        Some(
+            items
+                .into_iter()
+                .map(|item| unescape_value(item, quoted))
+                .collect(),
+        )
+    }
+}
+
+/// Replaces the escape sequences of an attribute value, see
+/// [`Property::get_unescaped`].
+fn unescape_value(value: &str, quoted: bool) -> Cow<'_, str> {
+    let value = unescape(value, ESCAPED_CHARS);
+    if !quoted {
+        return value;
+    }
+    match value {
+        Cow::Borrowed(v) => unescape(v, ESCAPED_VALUE_CHARS),
+        Cow::Owned(v) => Cow::Owned(unescape(&v, ESCAPED_VALUE_CHARS).into_owned()),
+    }
+}
+
+/// Replaces `\r\n` line endings by `\n`, borrowing the input if it has none.
+fn normalize_line_endings(s: &str) -> Cow<'_, str> {
+    if s.contains("\r\n") {
+        Cow::Owned(s.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(s)
+    }
+}
+
+/// Strips the surrounding quotes of an attribute value, returning whether the
+/// value was quoted.
+fn unquote(value: &str) -> (&str, bool) {
+    match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(v) if value.len() >= 2 => (v, true),
+        _ => (value, false),
+    }
+}
+
+/// Returns whether a character is part of an `@key` token of a text.
+///
+/// This is narrower than attribute keys, so punctuation following a token,
+/// as in `@name.`, is not part of the key.
+fn is_token_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Layer of texts without a `layer` attribute in Xschem.
+pub const TEXT_LAYER: u64 = 3;
+
+impl<I: AsRef<str>> Text<I> {
+    /// Returns the layer given by the `layer` attribute, or [`None`] if the
+    /// attribute is missing or not a valid layer.
+    ///
+    /// Xschem draws texts without a layer on [`TEXT_LAYER`].
+    pub fn layer(&self) -> Option<u64> {
+        self.property.get_value("layer")?.parse().ok()
+    }
+
+    /// Returns the text with escape sequences of [`ESCAPED_CHARS`] replaced.
+    pub fn unescaped_text(&self) -> String {
+        unescape(self.text.as_ref(), ESCAPED_CHARS).into_owned()
+    }
+
+    /// Returns the text with `@key` tokens replaced by the value of the
+    /// attribute `key` of a property, such as the property of a component
+    /// instantiating a symbol.
+    ///
+    /// Escape sequences are replaced first (see [`Text::unescaped_text`]),
+    /// and values are unquoted and unescaped (see [`Property::get_unescaped`]).
+    /// Keys consist of alphanumeric characters and underscores. Tokens of
+    /// missing attributes and other Xschem substitutions, such as
+    /// `@#0:net_name`, are kept as is.
+    ///
+    /// ```
+    /// let input = "v {}\nC {res.sym} 0 0 0 0 {name=R1 value=\"10 k\"}\nT {@name: @value @spice_get_current} 0 0 0 0 0.2 0.2 {}";
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    /// let property = &schematic.components[0].property;
+    ///
+    /// assert_eq!(schematic.texts[0].resolve(property), "R1: 10 k @spice_get_current");
+    /// ```
+    pub fn resolve<J: AsRef<str>>(&self, attrs: &Property<J>) -> String {
+        let text = self.unescaped_text();
+        let mut s = String::with_capacity(text.len());
+        let mut rest = text.as_str();
+        while let Some(at) = rest.find('@') {
+            s.push_str(&rest[..at]);
+            let token = &rest[at + 1..];
+            let len = token
+                .find(|c: char| !is_token_char(c))
+                .unwrap_or(token.len());
+            let key = &token[..len];
+            if let Some(value) = attrs.get_unescaped(key).filter(|_| !key.is_empty()) {
+                s.push_str(&value);
+            } else {
+                s.push('@');
+                s.push_str(key);
+            }
+            rest = &token[len..];
+        }
+        s.push_str(rest);
+        s
+    }
+
+    /// Returns the text with `\r\n` line endings replaced by `\n`, see
+    /// [`Property::prop_normalized`].
+    pub fn text_normalized(&self) -> Cow<'_, str> {
+        normalize_line_endings(self.text.as_ref())
     }
 }
 
+impl<I> VhdlProperty<I> {
+    /// Returns the VHDL code, which is the whole property string as in the
+    /// input.
+    pub fn code(&self) -> &I {
+        &self.0.prop
+    }
+}
+
+impl<I: AsRef<str>> VhdlProperty<I> {
+    /// Returns the VHDL code with escape sequences of [`ESCAPED_CHARS`]
+    /// replaced, see [`Property::unescaped`].
+    pub fn code_unescaped(&self) -> String {
+        self.0.unescaped()
+    }
+}
+
+impl<I> VerilogProperty<I> {
+    /// Returns the Verilog code, which is the whole property string as in the
+    /// input.
+    pub fn code(&self) -> &I {
+        &self.0.prop
+    }
+}
+
+impl<I: AsRef<str>> VerilogProperty<I> {
+    /// Returns the Verilog code unescaped like
+    /// [`VhdlProperty::code_unescaped`].
+    pub fn code_unescaped(&self) -> String {
+        self.0.unescaped()
+    }
+}
+
+impl<I> SpiceProperty<I> {
+    /// Returns the SPICE code, which is the whole property string as in the
+    /// input.
+    ///
+    /// ```
+    /// let input = "v {}\nS {.param w=1u\n.model nch nmos}";
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    /// let spice = schematic.spice_property.unwrap();
+    ///
+    /// assert_eq!(*spice.code().fragment(), ".param w=1u\n.model nch nmos");
+    /// ```
+    pub fn code(&self) -> &I {
+        &self.0.prop
+    }
+}
+
+impl<I: AsRef<str>> SpiceProperty<I> {
+    /// Returns the SPICE code unescaped like
+    /// [`VhdlProperty::code_unescaped`].
+    pub fn code_unescaped(&self) -> String {
+        self.0.unescaped()
+    }
+}
+
+impl<I: AsRef<str>> Version<I> {
+    /// Returns the Xschem version of the `version` attribute as in the
+    /// input, without parsing it.
+    ///
+    /// ```
+    /// let schematic = xschem_parser::from_str("v {xschem version=3.4.5 file_version=1.2}").unwrap();
+    ///
+    /// assert_eq!(*schematic.version.app_version_str().unwrap().fragment(), "3.4.5");
+    /// assert_eq!(*schematic.version.file_version_str().unwrap().fragment(), "1.2");
+    /// ```
+    pub fn app_version_str(&self) -> Option<&I> {
+        self.0.get("version")
+    }
+
+    /// Returns the file format version of the `file_version` attribute as in
+    /// the input, without parsing it.
+    pub fn file_version_str(&self) -> Option<&I> {
+        self.0.get("file_version")
+    }
+
+    /// Returns the parsed `version` and `file_version` attributes, or [`None`]
+    /// if neither attribute is present.
+    pub fn info(&self) -> Option<VersionInfo> {
+        let xschem = self.0.get_value("version");
+        let file_version = self.0.get_value("file_version");
+        if xschem.is_none() && file_version.is_none() {
+            return None;
+        }
+
+        Some(VersionInfo {
+            xschem: xschem.and_then(|v| match parse_version(v)?[..] {
+                [major, minor, patch] => Some((major, minor, patch)),
+                _ => None,
+            }),
+            file_version: file_version.and_then(|v| match parse_version(v)?[..] {
+                [major, minor] => Some((major, minor)),
+                _ => None,
+            }),
+        })
+    }
+}
+
+/// Parses a dot separated version number.
+fn parse_version(s: &str) -> Option<Vec<u32>> {
+    s.split('.').map(|n| n.parse().ok()).collect()
+}
+
 impl<I: Eq + Hash + PartialEq> PartialEq for Property<I> {
     fn eq(&self, other: &Self) -> bool {
         self.prop == other.prop && self.attrs == other.attrs
@@ -496,30 +1940,38 @@ where
     }
 }
 
-impl<I> fmt::Display for Component<I>
+impl<I> fmt::Display for UnknownObject<I>
 where
     I: fmt::Display,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        let Self {
-            reference,
-            position,
-            rotation,
-            flip,
-            property,
-            embedding,
-        } = self;
-        write!(
-            f,
-            "C {{{reference}}} {position} {rotation} {flip} {property}"
-        )?;
-        if let Some(e) = embedding {
-            write!(f, "\n{e}")?;
+        write!(f, "{}", self.tag)?;
+        for token in &self.tokens {
+            write!(f, " {token}")?;
         }
-        Ok(())
+        write!(f, " {}", self.property)
     }
 }
 
+/// Implements [`Display`](fmt::Display) for objects with the serializer of
+/// the [`write`](crate::write) module.
+macro_rules! impl_display_object {
+    ($($object:ident),* $(,)?) => {
+        $(
+            impl<I> fmt::Display for $object<I>
+            where
+                I: fmt::Display,
+            {
+                fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                    write_object(f, ObjectRef::$object(self), FloatFormat::Shortest)
+                }
+            }
+        )*
+    };
+}
+
+impl_display_object!(Arc, Component, Line, Polygon, Rectangle, Text, Wire);
+
 impl<I: PartialEq> PartialEq for Component<I>
 where
     Property<I>: PartialEq,
@@ -529,6 +1981,7 @@ where
             && self.position == other.position
             && self.rotation == other.rotation
             && self.flip == other.flip
+            && self.extra == other.extra
             && self.property == other.property
             && self.embedding == other.embedding
     }
@@ -590,6 +2043,15 @@ where
     }
 }
 
+impl<I: PartialEq> PartialEq for UnknownObject<I>
+where
+    Property<I>: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.tag == other.tag && self.tokens == other.tokens && self.property == other.property
+    }
+}
+
 impl<I: PartialEq> PartialEq for Embedding<I>
 where
     Property<I>: PartialEq,
@@ -599,12 +2061,175 @@ where
     }
 }
 
+impl<I: Eq + Hash> Eq for Schematic<I> {}
+impl<I: Eq + Hash> Eq for Property<I> {}
+impl<I: Eq + Hash> Eq for Version<I> {}
+impl<I: Eq + Hash> Eq for SpiceProperty<I> {}
+impl<I: Eq + Hash> Eq for VerilogProperty<I> {}
+impl<I: Eq + Hash> Eq for VhdlProperty<I> {}
+impl<I: Eq + Hash> Eq for TedaXProperty<I> {}
+impl<I: Eq + Hash> Eq for SymbolProperty<I> {}
+impl<I: Eq + Hash> Eq for Arc<I> {}
+impl<I: Eq + Hash> Eq for Component<I> {}
+impl<I: Eq + Hash> Eq for Line<I> {}
+impl<I: Eq + Hash> Eq for Polygon<I> {}
+impl<I: Eq + Hash> Eq for Rectangle<I> {}
+impl<I: Eq + Hash> Eq for Text<I> {}
+impl<I: Eq + Hash> Eq for Wire<I> {}
+impl<I: Eq + Hash> Eq for Embedding<I> {}
+impl<I: Eq + Hash> Eq for UnknownObject<I> {}
+
+/// Hashes the property string only, since the attributes are parsed from it.
+impl<I: Hash> Hash for Property<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.prop.hash(state);
+    }
+}
+
+impl<I: Hash> Hash for Arc<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layer.hash(state);
+        self.center.hash(state);
+        self.radius.hash(state);
+        self.start_angle.hash(state);
+        self.sweep_angle.hash(state);
+        self.property.hash(state);
+    }
+}
+
+/// Hashes all fields except the embedded symbol, which is still compared by
+/// [`Eq`].
+impl<I: Hash> Hash for Component<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.reference.hash(state);
+        self.position.hash(state);
+        self.rotation.hash(state);
+        self.flip.hash(state);
+        self.extra.hash(state);
+        self.property.hash(state);
+    }
+}
+
+impl<I: Hash> Hash for Line<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layer.hash(state);
+        self.start.hash(state);
+        self.end.hash(state);
+        self.property.hash(state);
+    }
+}
+
+impl<I: Hash> Hash for Polygon<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layer.hash(state);
+        self.points.hash(state);
+        self.property.hash(state);
+    }
+}
+
+impl<I: Hash> Hash for Rectangle<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.layer.hash(state);
+        self.start.hash(state);
+        self.end.hash(state);
+        self.property.hash(state);
+    }
+}
+
+impl<I: Hash> Hash for Text<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.text.hash(state);
+        self.position.hash(state);
+        self.rotation.hash(state);
+        self.flip.hash(state);
+        self.size.hash(state);
+        self.property.hash(state);
+    }
+}
+
+impl<I: Hash> Hash for Wire<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.start.hash(state);
+        self.end.hash(state);
+        self.property.hash(state);
+    }
+}
+
+impl<I: Hash> Hash for UnknownObject<I> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tag.hash(state);
+        self.tokens.hash(state);
+        self.property.hash(state);
+    }
+}
+
 impl<O> Default for Objects<O> {
     fn default() -> Self {
         Self(Vec::default())
     }
 }
 
+impl<O> Objects<O> {
+    /// Returns the first object, or [`None`] if there are no objects.
+    #[must_use]
+    pub fn first(&self) -> Option<&O> {
+        self.0.first()
+    }
+
+    /// Returns the last object, or [`None`] if there are no objects.
+    #[must_use]
+    pub fn last(&self) -> Option<&O> {
+        self.0.last()
+    }
+}
+
+impl<O, Idx: SliceIndex<[O]>> Index<Idx> for Objects<O> {
+    type Output = Idx::Output;
+
+    fn index(&self, index: Idx) -> &Self::Output {
+        &self.0[index]
+    }
+}
+
+impl<O, Idx: SliceIndex<[O]>> IndexMut<Idx> for Objects<O> {
+    fn index_mut(&mut self, index: Idx) -> &mut Self::Output {
+        &mut self.0[index]
+    }
+}
+
+impl<O> FromIterator<O> for Objects<O> {
+    fn from_iter<T: IntoIterator<Item = O>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<O> IntoIterator for Objects<O> {
+    type Item = O;
+    type IntoIter = alloc::vec::IntoIter<O>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, O> IntoIterator for &'a Objects<O> {
+    type Item = &'a O;
+    type IntoIter = slice::Iter<'a, O>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<'a, O> IntoIterator for &'a mut Objects<O> {
+    type Item = &'a mut O;
+    type IntoIter = slice::IterMut<'a, O>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter_mut()
+    }
+}
+
 impl<O: fmt::Display> fmt::Display for Objects<O> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         self.iter().enumerate().try_for_each(
@@ -625,20 +2250,39 @@ impl fmt::Display for Coordinates {
     }
 }
 
+impl FiniteDouble {
+    /// Creates a finite double, or `None` if the value is infinite or NaN.
+    #[must_use]
+    pub fn new(value: f64) -> Option<Self> {
+        value.is_finite().then_some(Self(value))
+    }
+
+    /// Returns the value as a primitive double.
+    #[must_use]
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+}
+
 impl TryFrom<f64> for FiniteDouble {
     type Error = &'static str;
 
     fn try_from(value: f64) -> Result<Self, Self::Error> {
-        if value.is_finite() {
-            Ok(Self(value))
-        } else {
-            Err("value is not finite")
-        }
+        Self::new(value).ok_or("value is not finite")
     }
 }
 
 impl Eq for FiniteDouble {}
 
+/// Hashes the bit pattern of the value, with `-0.0` hashed as `0.0` to be
+/// consistent with [`Eq`].
+impl Hash for FiniteDouble {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Adding zero normalizes negative zero.
+        (self.0 + 0.0).to_bits().hash(state);
+    }
+}
+
 impl TryFrom<(f64, f64)> for Vec2 {
     type Error = <FiniteDouble as TryFrom<f64>>::Error;
 
@@ -0,0 +1,239 @@
+//! Semantic checks of parsed schematics.
+//!
+//! Unlike parse errors, validations report syntactically valid objects that
+//! are likely mistakes, see [`Schematic::validate`].
+use alloc::vec;
+use alloc::vec::Vec;
+
+use derive_more::Display;
+
+use crate::token::Schematic;
+use crate::{HashMap, HashSet};
+
+/// Severity of a [`Validation`].
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    #[display("warning")]
+    Warning,
+    #[display("error")]
+    Error,
+}
+
+/// Kind of a [`Validation`].
+#[derive(Clone, Copy, Debug, Display, PartialEq, Eq, Hash)]
+pub enum ValidationKind {
+    /// Component without a `name` attribute.
+    #[display("component has no name")]
+    MissingName,
+    /// Component with the `name` of a preceding component.
+    #[display("duplicate component name")]
+    DuplicateName,
+    /// Wire with the same start and end.
+    #[display("wire has zero length")]
+    ZeroLengthWire,
+    /// Polygon whose first and last point do not coincide.
+    #[display("polygon is not closed")]
+    OpenPolygon,
+    /// Arc with a zero or negative radius.
+    #[display("arc radius is not positive")]
+    NonPositiveRadius,
+    /// Text without content.
+    #[display("text is empty")]
+    EmptyText,
+    /// Net label that is used only once.
+    #[display("net label is used only once")]
+    SingleUseLabel,
+}
+
+impl ValidationKind {
+    /// Returns the severity of this kind of validation.
+    ///
+    /// Duplicate names and non-positive radii are errors, since Xschem cannot
+    /// netlist or draw such objects correctly. Other validations are warnings.
+    #[must_use]
+    pub fn severity(self) -> Severity {
+        match self {
+            Self::DuplicateName | Self::NonPositiveRadius => Severity::Error,
+            Self::MissingName
+            | Self::ZeroLengthWire
+            | Self::OpenPolygon
+            | Self::EmptyText
+            | Self::SingleUseLabel => Severity::Warning,
+        }
+    }
+}
+
+/// Semantic issue of an object.
+#[derive(Clone, Debug, PartialEq, Eq, Display)]
+#[display("{severity}: {kind}")]
+pub struct Validation<I> {
+    pub severity: Severity,
+    pub kind: ValidationKind,
+    /// Offending part of the input.
+    ///
+    /// This is the `name` value of a duplicate name, the reference of a
+    /// component without a name, the text of a text object and the property
+    /// string of other objects.
+    pub span: I,
+}
+
+impl<I> Validation<I> {
+    fn new(kind: ValidationKind, span: I) -> Self {
+        Self {
+            severity: kind.severity(),
+            kind,
+            span,
+        }
+    }
+}
+
+impl<I: AsRef<str> + Clone> Schematic<I> {
+    /// Returns the semantic issues of the top-level objects.
+    ///
+    /// Validations are grouped by object type, in the order components,
+    /// wires, polygons, arcs and texts, and are in input order within a group.
+    /// Embedded symbols are not validated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use xschem_parser::validate::ValidationKind;
+    ///
+    /// let schematic = xschem_parser::from_str("v {}\nN 10 0 10 0 {lab=out}").unwrap();
+    /// let validations = schematic.validate();
+    ///
+    /// assert_eq!(validations[0].kind, ValidationKind::ZeroLengthWire);
+    /// assert_eq!(validations[0].span.location_line(), 2);
+    /// ```
+    #[must_use]
+    pub fn validate(&self) -> Vec<Validation<I>> {
+        let mut validations = Vec::new();
+
+        let mut names = HashSet::new();
+        for component in &self.components {
+            match component.property.get("name") {
+                Some(name) => {
+                    let value = component.property.get_value("name").unwrap_or_default();
+                    if !names.insert(value) {
+                        validations
+                            .push(Validation::new(ValidationKind::DuplicateName, name.clone()));
+                    }
+                }
+                None => validations.push(Validation::new(
+                    ValidationKind::MissingName,
+                    component.reference.clone(),
+                )),
+            }
+        }
+
+        validations.extend(
+            self.wires
+                .iter()
+                .filter(|w| w.start == w.end)
+                .map(|w| Validation::new(ValidationKind::ZeroLengthWire, w.property.prop.clone())),
+        );
+        validations.extend(
+            self.polygons
+                .iter()
+                .filter(|p| p.points.first() != p.points.last())
+                .map(|p| Validation::new(ValidationKind::OpenPolygon, p.property.prop.clone())),
+        );
+        validations.extend(
+            self.arcs
+                .iter()
+                .filter(|a| a.radius.value() <= 0.0)
+                .map(|a| {
+                    Validation::new(ValidationKind::NonPositiveRadius, a.property.prop.clone())
+                }),
+        );
+        validations.extend(
+            self.texts
+                .iter()
+                .filter(|t| t.text.as_ref().is_empty())
+                .map(|t| Validation::new(ValidationKind::EmptyText, t.text.clone())),
+        );
+
+        validations
+    }
+
+    /// Returns an electrical rule check warning for each net label that is
+    /// used only once, see [`ValidationKind::SingleUseLabel`].
+    ///
+    /// A label is used once by each group of connected wires (see
+    /// [`Schematic::connected_components`]) carrying it, either on one of its
+    /// wires or on a label component with the `lab` attribute (see
+    /// [`Schematic::net_labels`]) at one of its endpoints. Xschem copies the
+    /// label of an attached label pin onto its wires, so a pin and its wires
+    /// are a single use. Each label component that touches no wire is a use
+    /// of its own. A label used once connects nothing by name, so it may be a
+    /// mistake or left unconnected. This check is conservative: symbol pins
+    /// are not known, so labels of wires that do not connect to any pin are
+    /// not reported.
+    ///
+    /// Warnings are in input order of wire groups, with the wires of a group
+    /// before its label components, followed by the label components that
+    /// touch no wire. They point at the first `lab` value of a use.
+    ///
+    /// ```
+    /// let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+    ///              N 0 0 10 0 {lab=IN}\n\
+    ///              C {lab_pin.sym} 10 0 0 0 {name=p1 lab=IN}\n\
+    ///              C {lab_pin.sym} 50 0 0 0 {name=p2 lab=IN}\n\
+    ///              C {lab_pin.sym} 90 0 0 0 {name=p3 lab=OUT}\n";
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    /// let warnings = schematic.erc_floating_labels();
+    ///
+    /// assert_eq!(warnings.len(), 1);
+    /// assert_eq!(*warnings[0].span.fragment(), "OUT");
+    /// ```
+    #[must_use]
+    pub fn erc_floating_labels(&self) -> Vec<Validation<I>> {
+        let groups = self.connected_components();
+        let mut group_at = HashMap::new();
+        for (i, group) in groups.iter().enumerate() {
+            for wire in group {
+                group_at.insert(wire.start, i);
+                group_at.insert(wire.end, i);
+            }
+        }
+        let mut attached = vec![Vec::new(); groups.len()];
+        let mut detached = Vec::new();
+        for component in &self.components {
+            if component.property.get("lab").is_some() {
+                match group_at.get(&component.position) {
+                    Some(&i) => attached[i].push(&component.property),
+                    None => detached.push(&component.property),
+                }
+            }
+        }
+
+        let mut uses: Vec<(&str, &I)> = Vec::new();
+        for (group, components) in groups.iter().zip(attached) {
+            let mut seen = HashSet::new();
+            let properties = group.iter().map(|w| &w.property).chain(components);
+            for property in properties {
+                if let Some(span) = property.get("lab") {
+                    let label = property.get_value("lab").unwrap_or_default();
+                    if seen.insert(label) {
+                        uses.push((label, span));
+                    }
+                }
+            }
+        }
+        for property in detached {
+            if let Some(span) = property.get("lab") {
+                let label = property.get_value("lab").unwrap_or_default();
+                uses.push((label, span));
+            }
+        }
+
+        let mut counts = HashMap::new();
+        for (label, _) in &uses {
+            *counts.entry(*label).or_insert(0) += 1;
+        }
+        uses.into_iter()
+            .filter(|(label, _)| counts[label] == 1)
+            .map(|(_, span)| Validation::new(ValidationKind::SingleUseLabel, span.clone()))
+            .collect()
+    }
+}
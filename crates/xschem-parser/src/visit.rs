@@ -0,0 +1,76 @@
+//! Visitor over the objects of a schematic, including embedded symbols.
+use crate::token::{
+    Arc, Component, Embedding, Line, ObjectRef, Polygon, Rectangle, Schematic, Text, UnknownObject,
+    Wire,
+};
+
+/// Visitor of the objects of a [`Schematic`], see [`Schematic::accept`].
+///
+/// All methods do nothing by default, so a visitor only overrides the methods
+/// of the objects it is interested in.
+pub trait Visitor<I> {
+    fn visit_arc(&mut self, _arc: &Arc<I>) {}
+    fn visit_component(&mut self, _component: &Component<I>) {}
+    /// Called with the embedded symbol of a component, before visiting the
+    /// objects of the embedded symbol.
+    fn visit_embedding(&mut self, _embedding: &Embedding<I>) {}
+    fn visit_line(&mut self, _line: &Line<I>) {}
+    fn visit_polygon(&mut self, _polygon: &Polygon<I>) {}
+    fn visit_rectangle(&mut self, _rectangle: &Rectangle<I>) {}
+    fn visit_text(&mut self, _text: &Text<I>) {}
+    fn visit_wire(&mut self, _wire: &Wire<I>) {}
+    fn visit_unknown(&mut self, _object: &UnknownObject<I>) {}
+}
+
+impl<I> Schematic<I> {
+    /// Visits all objects in input order.
+    ///
+    /// The embedded symbol of a component is visited right after the
+    /// component, descending into nested embedded symbols.
+    ///
+    /// ```
+    /// use xschem_parser::token::Wire;
+    /// use xschem_parser::visit::Visitor;
+    ///
+    /// struct WireCount(usize);
+    ///
+    /// impl<I> Visitor<I> for WireCount {
+    ///     fn visit_wire(&mut self, _wire: &Wire<I>) {
+    ///         self.0 += 1;
+    ///     }
+    /// }
+    ///
+    /// let input = "v {xschem version=3.4.5 file_version=1.2}\n\
+    ///              N 0 0 10 0 {}\n\
+    ///              C {a.sym} 0 0 0 0 {}\n\
+    ///              [\n\
+    ///              v {xschem version=3.4.5 file_version=1.2}\n\
+    ///              N 0 0 0 10 {}\n\
+    ///              ]\n";
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    ///
+    /// let mut count = WireCount(0);
+    /// schematic.accept(&mut count);
+    /// assert_eq!(count.0, 2);
+    /// ```
+    pub fn accept(&self, visitor: &mut impl Visitor<I>) {
+        for object in self.objects() {
+            match object {
+                ObjectRef::Arc(arc) => visitor.visit_arc(arc),
+                ObjectRef::Component(component) => {
+                    visitor.visit_component(component);
+                    if let Some(embedding) = &component.embedding {
+                        visitor.visit_embedding(embedding);
+                        embedding.0.accept(visitor);
+                    }
+                }
+                ObjectRef::Line(line) => visitor.visit_line(line),
+                ObjectRef::Polygon(polygon) => visitor.visit_polygon(polygon),
+                ObjectRef::Rectangle(rectangle) => visitor.visit_rectangle(rectangle),
+                ObjectRef::Text(text) => visitor.visit_text(text),
+                ObjectRef::Wire(wire) => visitor.visit_wire(wire),
+                ObjectRef::Unknown(object) => visitor.visit_unknown(object),
+            }
+        }
+    }
+}
@@ -0,0 +1,338 @@
+//! Serialization to the Xschem file format.
+//!
+//! # Stability
+//!
+//! Output of [`write()`] and [`Schematic::to_xschem_string`] is guaranteed to
+//! parse back into a schematic equal to the original, as long as each
+//! property string matches its parsed attributes, which is always the case
+//! for parsed schematics, and the output is parsed with the same
+//! [`ParseOptions`]. Unknown objects, kept with
+//! [`ParseOptions::unknown_objects`], are written back unchanged, so output
+//! with unknown objects only parses with that option set. Property strings and
+//! texts are written verbatim, so the order of attributes, quoting and escape
+//! sequences are preserved. Objects are written in the order given by
+//! [`Schematic::order`].
+//!
+//! The exact formatting of numbers and whitespace may change between
+//! releases. Use [`write_with_format`] to choose the formatting of numbers.
+//!
+//! [`ParseOptions`]: crate::options::ParseOptions
+//! [`ParseOptions::unknown_objects`]: crate::options::ParseOptions::unknown_objects
+use alloc::format;
+use alloc::string::String;
+use core::fmt::{self, Display};
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::token::{
+    Arc, Component, Line, ObjectRef, Polygon, Rectangle, Schematic, Text, Vec2, Wire,
+};
+
+/// Formatting of floating point numbers, such as coordinates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum FloatFormat {
+    /// Shortest representation that parses back to the same value, as used by
+    /// [`Display`].
+    #[default]
+    Shortest,
+    /// Fixed number of decimals.
+    ///
+    /// Values are rounded, so the output may not parse back into the same
+    /// values.
+    Fixed(usize),
+    /// Like Xschem, which formats numbers as `%.16g` in C.
+    ///
+    /// Numbers have at most 16 significant digits without trailing zeros.
+    /// Very large or small numbers are formatted in scientific notation.
+    Xschem,
+}
+
+impl FloatFormat {
+    /// Returns a value that displays a number in this format.
+    ///
+    /// ```
+    /// use xschem_parser::write::FloatFormat;
+    ///
+    /// assert_eq!(FloatFormat::Shortest.display(-15.0).to_string(), "-15");
+    /// assert_eq!(FloatFormat::Fixed(3).display(-15.0).to_string(), "-15.000");
+    /// assert_eq!(FloatFormat::Xschem.display(0.1 + 0.2).to_string(), "0.3");
+    /// ```
+    #[must_use]
+    pub fn display(self, value: f64) -> impl Display {
+        Number(value, self)
+    }
+}
+
+/// Number displayed in a [`FloatFormat`].
+struct Number(f64, FloatFormat);
+
+impl Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self(value, format) = *self;
+        match format {
+            FloatFormat::Shortest => write!(f, "{value}"),
+            FloatFormat::Fixed(decimals) => write!(f, "{value:.decimals$}"),
+            FloatFormat::Xschem => write_general(f, value),
+        }
+    }
+}
+
+/// Removes trailing zeros after the decimal point, and the point itself if
+/// no decimals remain.
+fn trim_decimals(s: &str) -> &str {
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        s
+    }
+}
+
+/// Writes a number like `%.16g` in C.
+fn write_general(f: &mut fmt::Formatter<'_>, value: f64) -> fmt::Result {
+    const PRECISION: i32 = 16;
+
+    if value == 0.0 {
+        return f.write_str(if value.is_sign_negative() { "-0" } else { "0" });
+    }
+    // The exponent after rounding to `PRECISION` significant digits decides
+    // the notation.
+    let scientific = format!("{value:.15e}");
+    let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or_default();
+
+    if (-4..PRECISION).contains(&exponent) {
+        let decimals = usize::try_from(PRECISION - 1 - exponent).unwrap_or_default();
+        f.write_str(trim_decimals(&format!("{value:.decimals$}")))
+    } else {
+        let sign = if exponent < 0 { '-' } else { '+' };
+        write!(
+            f,
+            "{}e{sign}{:02}",
+            trim_decimals(mantissa),
+            exponent.unsigned_abs()
+        )
+    }
+}
+
+/// Coordinate displayed in a [`FloatFormat`].
+struct Point(Vec2, FloatFormat);
+
+impl Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self(Vec2 { x, y }, format) = *self;
+        write!(f, "{} {}", Number(*x, format), Number(*y, format))
+    }
+}
+
+/// Writes an object with numbers in the given format.
+///
+/// The [`Display`] implementations of objects write them with
+/// [`FloatFormat::Shortest`].
+pub(crate) fn write_object<W: fmt::Write, I: Display>(
+    w: &mut W,
+    object: ObjectRef<'_, I>,
+    format: FloatFormat,
+) -> fmt::Result {
+    let num = |value| Number(value, format);
+    let point = |value| Point(value, format);
+    match object {
+        ObjectRef::Arc(Arc {
+            layer,
+            center,
+            radius,
+            start_angle,
+            sweep_angle,
+            property,
+        }) => write!(
+            w,
+            "A {layer} {} {} {} {} {property}",
+            point(*center),
+            num(**radius),
+            num(**start_angle),
+            num(**sweep_angle),
+        ),
+        ObjectRef::Component(Component {
+            reference,
+            position,
+            rotation,
+            flip,
+            extra,
+            property,
+            embedding,
+        }) => {
+            write!(
+                w,
+                "C {{{reference}}} {} {rotation} {flip}",
+                point(*position)
+            )?;
+            for token in extra {
+                write!(w, " {token}")?;
+            }
+            write!(w, " {property}")?;
+            if let Some(e) = embedding {
+                w.write_str("\n[\n")?;
+                write_schematic(w, e, format)?;
+                w.write_str("\n]")?;
+            }
+            Ok(())
+        }
+        ObjectRef::Line(Line {
+            layer,
+            start,
+            end,
+            property,
+        }) => write!(w, "L {layer} {} {} {property}", point(*start), point(*end)),
+        ObjectRef::Polygon(Polygon {
+            layer,
+            points,
+            property,
+        }) => {
+            write!(w, "P {layer} {}", points.len())?;
+            for p in points.iter() {
+                write!(w, " {}", point(*p))?;
+            }
+            write!(w, " {property}")
+        }
+        ObjectRef::Rectangle(Rectangle {
+            layer,
+            start,
+            end,
+            property,
+        }) => write!(w, "B {layer} {} {} {property}", point(*start), point(*end)),
+        ObjectRef::Text(Text {
+            text,
+            position,
+            rotation,
+            flip,
+            size,
+            property,
+        }) => write!(
+            w,
+            "T {{{text}}} {} {rotation} {flip} {} {property}",
+            point(*position),
+            point(*size),
+        ),
+        ObjectRef::Wire(Wire {
+            start,
+            end,
+            property,
+        }) => write!(w, "N {} {} {property}", point(*start), point(*end)),
+        ObjectRef::Unknown(o) => write!(w, "{o}"),
+    }
+}
+
+/// Writes a schematic with numbers in the given format, without a trailing
+/// newline.
+///
+/// The [`Display`] implementation of [`Schematic`] writes it with
+/// [`FloatFormat::Shortest`].
+pub(crate) fn write_schematic<W: fmt::Write, I: Display>(
+    w: &mut W,
+    schematic: &Schematic<I>,
+    format: FloatFormat,
+) -> fmt::Result {
+    write!(w, "{}", schematic.version)?;
+    if let Some(p) = &schematic.vhdl_property {
+        write!(w, "\n{p}")?;
+    }
+    if let Some(p) = &schematic.symbol_property {
+        write!(w, "\n{p}")?;
+    }
+    if let Some(p) = &schematic.verilog_property {
+        write!(w, "\n{p}")?;
+    }
+    if let Some(p) = &schematic.spice_property {
+        write!(w, "\n{p}")?;
+    }
+    if let Some(p) = &schematic.tedax_property {
+        write!(w, "\n{p}")?;
+    }
+    schematic.objects().try_for_each(|o| {
+        w.write_char('\n')?;
+        write_object(w, o, format)
+    })
+}
+
+/// Writes a schematic in the Xschem file format, terminated by a newline.
+///
+/// ```
+/// let input = "v {xschem version=3.4.5 file_version=1.2}\nN 0 0 10 0 {lab=A}\n";
+/// let schematic = xschem_parser::from_str(input).unwrap();
+///
+/// let mut output = String::new();
+/// xschem_parser::write::write(&mut output, &schematic).unwrap();
+///
+/// assert_eq!(output, input);
+/// ```
+pub fn write<W: fmt::Write, I: Display>(w: &mut W, schematic: &Schematic<I>) -> fmt::Result {
+    writeln!(w, "{schematic}")
+}
+
+/// Writes a schematic in the Xschem file format like [`write()`], with
+/// numbers in the given [`FloatFormat`].
+///
+/// ```
+/// use xschem_parser::write::FloatFormat;
+///
+/// let input = "v {xschem version=3.4.5 file_version=1.2}\nN 0 -15.0 10 0 {lab=A}";
+/// let schematic = xschem_parser::from_str(input).unwrap();
+///
+/// let mut output = String::new();
+/// xschem_parser::write::write_with_format(&mut output, &schematic, FloatFormat::Fixed(1))
+///     .unwrap();
+///
+/// assert!(output.ends_with("N 0.0 -15.0 10.0 0.0 {lab=A}\n"));
+/// ```
+pub fn write_with_format<W: fmt::Write, I: Display>(
+    w: &mut W,
+    schematic: &Schematic<I>,
+    format: FloatFormat,
+) -> fmt::Result {
+    write_schematic(w, schematic, format)?;
+    w.write_char('\n')
+}
+
+impl<I: Display> Schematic<I> {
+    /// Writes the schematic as displayed to a writer, without building the
+    /// output in memory first.
+    ///
+    /// The output is the same as of [`to_string`](alloc::string::ToString),
+    /// which is not terminated by a newline. The schematic is written in
+    /// many small parts, so wrap unbuffered writers such as files in a
+    /// [`BufWriter`](std::io::BufWriter).
+    ///
+    /// ```
+    /// let input = "v {xschem version=3.4.5 file_version=1.2}\nN 0 0 10 0 {lab=A}";
+    /// let schematic = xschem_parser::from_str(input).unwrap();
+    ///
+    /// let mut output = Vec::new();
+    /// schematic.write_to(&mut output).unwrap();
+    ///
+    /// assert_eq!(output, input.as_bytes());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of the writer.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        write!(w, "{self}")
+    }
+
+    /// Returns the schematic in the Xschem file format, see [`write()`].
+    pub fn to_xschem_string(&self) -> String {
+        let mut s = String::new();
+        // Writing to a `String` does not fail.
+        let _ = write(&mut s, self);
+        s
+    }
+
+    /// Returns the schematic in the Xschem file format with numbers in the
+    /// given format, see [`write_with_format`].
+    pub fn to_xschem_string_with_format(&self, format: FloatFormat) -> String {
+        let mut s = String::new();
+        // Writing to a `String` does not fail.
+        let _ = write_with_format(&mut s, self, format);
+        s
+    }
+}
@@ -102,6 +102,23 @@
             }
           );
 
+          clippy-all-features = craneLib.cargoClippy (
+            commonArgs
+            // {
+              inherit cargoArtifacts;
+              src = testSrc;
+              cargoClippyExtraArgs = "--all-targets --all-features -- --deny warnings";
+            }
+          );
+
+          no-std = craneLib.cargoBuild (
+            commonArgs
+            // {
+              inherit cargoArtifacts;
+              cargoExtraArgs = "-p xschem-parser --no-default-features";
+            }
+          );
+
           doc = craneLib.cargoDoc (
             commonArgs
             // {
@@ -143,6 +160,18 @@
             }
           );
 
+          nextest-all-features = craneLib.cargoNextest (
+            commonArgs
+            // {
+              inherit cargoArtifacts;
+              src = testSrc;
+              partitions = 1;
+              partitionType = "count";
+              cargoNextestExtraArgs = "--all-features";
+              cargoNextestPartitionsExtraArgs = "--no-tests=pass";
+            }
+          );
+
           pre-commit-check = git-hooks.lib.${system}.run {
             src = ./.;
             settings.rust.check.cargoDeps = pkgs.rustPlatform.importCargoLock {
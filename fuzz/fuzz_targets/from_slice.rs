@@ -0,0 +1,22 @@
+//! Parses arbitrary bytes, which must return an error instead of panicking.
+//!
+//! Run with `cargo +nightly fuzz run from_slice` from the repository root.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    match xschem_parser::from_slice(data) {
+        Ok(schematic) => {
+            let _ = schematic.objects().count();
+        }
+        Err(error) => {
+            let _ = error.to_string();
+            let _ = error.to_plain_string();
+        }
+    }
+
+    if let Ok(Err(error)) = core::str::from_utf8(data).map(xschem_parser::from_str) {
+        let _ = error.to_string();
+    }
+});